@@ -0,0 +1,567 @@
+// gateway/src/main.rs - Authenticating HTTP entry point for Rheo Mesh
+// The one cell meant to be reachable from outside. Clients authenticate with a
+// bearer token or an HMAC-signed request from a local credential file, and
+// `POST /api/<capability>` becomes an `ask_mesh` carrying the client's id in
+// `proofs.caller` - covered by the gateway's origin signature.
+//
+// Internal cells can then refuse anything the gateway didn't vouch for:
+//   [[rule]]  effect = "allow"  capabilities = ["trading/*"]  callers = ["gateway"]
+//   [[rule]]  effect = "deny"   capabilities = ["trading/*"]
+//   [identities]  gateway = "<public key of the gateway's RHEO_IDENTITY_KEY>"
+//
+// `GET /openapi.json` describes every capability the mesh currently offers.
+//
+// The mesh listener skips all of that, so it binds `mesh.bind_host` (loopback in
+// Cell.toml) - keep it on an interface clients can't reach.
+//
+//   RHEO_GATEWAY_PORT         public HTTP port (default 8080)
+//   RHEO_GATEWAY_CREDENTIALS  credential file (default credentials.toml)
+
+use cell_protocol_example1_rs::{
+    axum::{
+        body::Bytes,
+        extract::{Path, State},
+        http::{HeaderMap, HeaderValue, StatusCode},
+        response::{IntoResponse, Response},
+        routing::{get, post},
+        Json, Router,
+    },
+    openapi::OpenApi,
+    policy, CellConfig, ErrorCode, RheoCell, TraceResult,
+};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{info, warn};
+
+/// HMAC-signed requests must be this fresh; seen signatures are kept for this
+/// long to refuse replays
+const HMAC_WINDOW_MS: u64 = 5 * 60 * 1000;
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// ============================================================================
+// CREDENTIALS
+// ============================================================================
+
+/// One API client from the credential file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    /// Identity stamped into `proofs.caller`
+    pub id: String,
+    /// Hex sha256 of the bearer token - the token itself is never stored
+    #[serde(default)]
+    pub token_sha256: Option<String>,
+    /// Shared secret for HMAC-signed requests
+    #[serde(default)]
+    pub hmac_secret: Option<String>,
+    /// Requests per minute; 0 is unlimited
+    #[serde(default)]
+    pub quota_per_minute: u32,
+    /// Capability patterns this key may call
+    #[serde(default = "any_capability")]
+    pub capabilities: Vec<String>,
+}
+
+fn any_capability() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+#[derive(Debug, Default)]
+pub struct Credentials {
+    keys: Vec<ApiKey>,
+}
+
+impl Credentials {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct File {
+            #[serde(default, rename = "key")]
+            keys: Vec<ApiKey>,
+        }
+        let file: File = toml::from_str(text).map_err(|e| e.message().to_string())?;
+
+        for (index, key) in file.keys.iter().enumerate() {
+            if key.id.is_empty() {
+                return Err(format!("key[{}]: empty id", index));
+            }
+            if file.keys[..index].iter().any(|k| k.id == key.id) {
+                return Err(format!("key[{}]: duplicate id {}", index, key.id));
+            }
+            if key.token_sha256.is_none() && key.hmac_secret.is_none() {
+                return Err(format!("key[{}]: needs token_sha256 or hmac_secret", index));
+            }
+        }
+        Ok(Self { keys: file.keys })
+    }
+
+    fn by_token(&self, token: &str) -> Option<&ApiKey> {
+        let digest = hex::encode(Sha256::digest(token.as_bytes()));
+        self.keys.iter().find(|k| {
+            k.token_sha256.as_ref().is_some_and(|h| {
+                constant_time_eq(h.to_ascii_lowercase().as_bytes(), digest.as_bytes())
+            })
+        })
+    }
+
+    fn by_id(&self, id: &str) -> Option<&ApiKey> {
+        self.keys.iter().find(|k| k.id == id)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// What an HMAC client signs
+pub fn hmac_message(timestamp: u64, method: &str, path: &str, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("{}\n{}\n{}\n", timestamp, method, path).into_bytes();
+    message.extend_from_slice(body);
+    message
+}
+
+// ============================================================================
+// GATEWAY
+// ============================================================================
+
+pub struct Gateway {
+    cell: Arc<RheoCell>,
+    credentials: Credentials,
+    /// Key id -> (minute, requests in it)
+    quotas: Mutex<HashMap<String, (u64, u32)>>,
+    /// HMAC signatures seen inside the window, so requests can't be replayed
+    seen_signatures: Mutex<HashMap<String, u64>>,
+}
+
+/// Rejections before the mesh is involved
+struct Refusal(StatusCode, ErrorCode, String);
+
+impl IntoResponse for Refusal {
+    fn into_response(self) -> Response {
+        let Refusal(status, code, message) = self;
+        let mut response = (
+            status,
+            Json(json!({ "ok": false, "error": { "code": code, "message": message } })),
+        )
+            .into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert("WWW-Authenticate", HeaderValue::from_static("Bearer"));
+        }
+        response
+    }
+}
+
+impl Gateway {
+    pub fn new(cell: Arc<RheoCell>, credentials: Credentials) -> Self {
+        Self {
+            cell,
+            credentials,
+            quotas: Mutex::new(HashMap::new()),
+            seen_signatures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/api/*capability", post(api))
+            .route("/openapi.json", get(openapi))
+            .route("/health", get(|| async { Json(json!({ "status": "ok" })) }))
+            .with_state(self)
+    }
+
+    fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+        body: &[u8],
+    ) -> Result<&ApiKey, Refusal> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let unauthorized = |message: &str| {
+            Refusal(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::Unauthorized,
+                message.to_string(),
+            )
+        };
+
+        if let Some(token) = header("authorization").and_then(|h| h.strip_prefix("Bearer ")) {
+            return self
+                .credentials
+                .by_token(token.trim())
+                .ok_or_else(|| unauthorized("unknown bearer token"));
+        }
+
+        let (Some(id), Some(timestamp), Some(signature)) = (
+            header("x-rheo-key"),
+            header("x-rheo-timestamp"),
+            header("x-rheo-signature"),
+        ) else {
+            return Err(unauthorized("expected a bearer token or an HMAC signature"));
+        };
+        let key = self
+            .credentials
+            .by_id(id)
+            .filter(|k| k.hmac_secret.is_some())
+            .ok_or_else(|| unauthorized("unknown key"))?;
+        let timestamp: u64 = timestamp
+            .parse()
+            .map_err(|_| unauthorized("malformed timestamp"))?;
+        let now = now_millis();
+        if now.abs_diff(timestamp) > HMAC_WINDOW_MS {
+            return Err(unauthorized("stale timestamp"));
+        }
+
+        let secret = key.hmac_secret.as_deref().unwrap_or_default();
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|_| unauthorized("unusable secret"))?;
+        mac.update(&hmac_message(timestamp, "POST", path, body));
+        let signature_bytes =
+            hex::decode(signature).map_err(|_| unauthorized("malformed signature"))?;
+        mac.verify_slice(&signature_bytes)
+            .map_err(|_| unauthorized("bad signature"))?;
+
+        let mut seen = self.seen_signatures.lock().unwrap();
+        seen.retain(|_, at| now.saturating_sub(*at) <= HMAC_WINDOW_MS);
+        if seen.insert(signature.to_ascii_lowercase(), now).is_some() {
+            return Err(unauthorized("replayed request"));
+        }
+        Ok(key)
+    }
+
+    /// Count a request against the key's per-minute quota
+    fn take_quota(&self, key: &ApiKey) -> Result<(), Refusal> {
+        if key.quota_per_minute == 0 {
+            return Ok(());
+        }
+        let now = now_millis();
+        let minute = now / 60_000;
+        let mut quotas = self.quotas.lock().unwrap();
+        let window = quotas.entry(key.id.clone()).or_insert((minute, 0));
+        if window.0 != minute {
+            *window = (minute, 0);
+        }
+        if window.1 >= key.quota_per_minute {
+            let retry_after = 60 - (now / 1000) % 60;
+            return Err(Refusal(
+                StatusCode::TOO_MANY_REQUESTS,
+                ErrorCode::RateLimited,
+                format!(
+                    "quota of {} per minute used, retry in {}s",
+                    key.quota_per_minute, retry_after
+                ),
+            ));
+        }
+        window.1 += 1;
+        Ok(())
+    }
+}
+
+/// `POST /api/<capability>` - the body is the capability's args
+async fn api(
+    State(gateway): State<Arc<Gateway>>,
+    Path(capability): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let started = Instant::now();
+    let path = format!("/api/{}", capability);
+
+    // Cell control and mesh plumbing never leave the mesh
+    if capability.starts_with("mesh/") || capability.starts_with("cell/") {
+        return Refusal(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            format!("No capability {}", capability),
+        )
+        .into_response();
+    }
+
+    let key = match gateway.authenticate(&headers, &path, &body) {
+        Ok(key) => key,
+        Err(refusal) => {
+            warn!(capability = %capability, reason = %refusal.2, "Rejected unauthenticated request");
+            return refusal.into_response();
+        }
+    };
+    if !key
+        .capabilities
+        .iter()
+        .any(|p| policy::glob(p, &capability))
+    {
+        return Refusal(
+            StatusCode::FORBIDDEN,
+            ErrorCode::Unauthorized,
+            format!("Key {} may not call {}", key.id, capability),
+        )
+        .into_response();
+    }
+    if let Err(refusal) = gateway.take_quota(key) {
+        return refusal.into_response();
+    }
+
+    let args: Value = if body.is_empty() {
+        Value::Null
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(args) => args,
+            Err(e) => {
+                return Refusal(
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::ValidationFailed,
+                    e.to_string(),
+                )
+                .into_response()
+            }
+        }
+    };
+
+    let proofs = HashMap::from([(policy::CALLER_PROOF.to_string(), key.id.clone())]);
+    let result = gateway
+        .cell
+        .ask_mesh_with_proofs(&capability, args, proofs)
+        .await;
+    info!(
+        key = %key.id,
+        capability = %capability,
+        cid = %result.cid,
+        ok = result.ok,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "API call"
+    );
+    respond(result)
+}
+
+/// `GET /openapi.json` - every public capability in the atlas, as served here.
+/// Anyone may ask, so the mesh is asked at most once per cache interval.
+async fn openapi(State(gateway): State<Arc<Gateway>>) -> Json<Value> {
+    let descriptors = gateway.cell.describe_mesh_cached().await;
+    Json(OpenApi::new("Rheo Mesh API").document(&descriptors))
+}
+
+/// Only the outcome leaves the mesh - narratives and traces stay inside
+fn respond(result: TraceResult) -> Response {
+    if result.ok {
+        let body = json!({ "ok": true, "cid": result.cid, "value": result.value });
+        return (StatusCode::OK, Json(body)).into_response();
+    }
+    let (code, message) = result
+        .error
+        .map(|e| (e.code, e.message))
+        .unwrap_or((ErrorCode::Internal, "Unknown mesh failure".to_string()));
+    let status =
+        StatusCode::from_u16(code.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = json!({
+        "ok": false,
+        "cid": result.cid,
+        "error": { "code": code, "message": message },
+    });
+    (status, Json(body)).into_response()
+}
+
+#[tokio::main]
+async fn main() {
+    let config = CellConfig::load().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    let cell = RheoCell::new(config);
+    cell.init_logging().expect("Failed to init logging");
+
+    let path =
+        std::env::var("RHEO_GATEWAY_CREDENTIALS").unwrap_or_else(|_| "credentials.toml".into());
+    let credentials = Credentials::load(&path).unwrap_or_else(|e| {
+        eprintln!("cannot load credentials {}", e);
+        std::process::exit(2);
+    });
+    let port: u16 = std::env::var("RHEO_GATEWAY_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(8080);
+
+    if cell.config.bind_host.is_unspecified() {
+        warn!("Mesh listener is bound on all interfaces - set mesh.bind_host so clients can't bypass the API");
+    }
+    let handle = cell.clone().listen().await.expect("Failed to join mesh");
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+        .await
+        .expect("Failed to bind API port");
+    info!(port, keys = credentials.keys.len(), "🔐 Gateway API ready");
+
+    let app = Arc::new(Gateway::new(cell, credentials)).router();
+    cell_protocol_example1_rs::axum::serve(listener, app)
+        .with_graceful_shutdown(handle.join())
+        .await
+        .expect("API server failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cell_protocol_example1_rs::{policy::Policy, Context, MeshError};
+
+    #[tokio::test]
+    async fn test_gateway_requests() {
+        let provider = RheoCell::new(CellConfig::default());
+        provider.provide("acct/whoami", |_: Value, ctx: Context| {
+            Box::pin(async move {
+                ctx.proofs
+                    .get(policy::CALLER_PROOF)
+                    .cloned()
+                    .ok_or_else(|| MeshError::new(ErrorCode::Unauthorized, "anonymous", "acct"))
+            })
+        });
+        let seed = format!(
+            "http://127.0.0.1:{}",
+            provider.clone().listen().await.unwrap().addr().port()
+        );
+
+        let cell = RheoCell::new(CellConfig {
+            id: "gateway-test".into(),
+            seed: Some(seed),
+            ..Default::default()
+        });
+        // Only the gateway may call acct/*, and it must say for whom
+        provider.set_policy(
+            Policy::parse(&format!(
+                r#"
+                [[rule]]
+                effect = "allow"
+                capabilities = ["acct/*"]
+                callers = ["gateway-test"]
+
+                [[rule]]
+                effect = "deny"
+                capabilities = ["acct/*"]
+
+                [identities]
+                gateway-test = "{}"
+                "#,
+                cell.pub_key_hex
+            ))
+            .unwrap(),
+        );
+        cell.clone().listen().await.unwrap().ready().await;
+        let credentials = Credentials::parse(&format!(
+            r#"
+            [[key]]
+            id = "desk"
+            token_sha256 = "{}"
+            quota_per_minute = 2
+
+            [[key]]
+            id = "batch"
+            hmac_secret = "s3cret"
+            capabilities = ["acct/*"]
+            "#,
+            hex::encode(Sha256::digest(b"desk-token"))
+        ))
+        .unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api = format!("http://{}/api", listener.local_addr().unwrap());
+        let app = Arc::new(Gateway::new(cell.clone(), credentials)).router();
+        tokio::spawn(async move { cell_protocol_example1_rs::axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let whoami = format!("{}/acct/whoami", api);
+
+        let anonymous = client.post(&whoami).send().await.unwrap();
+        assert_eq!(anonymous.status(), 401);
+        let body: Value = anonymous.json().await.unwrap();
+        assert_eq!(body["error"]["code"], "Unauthorized");
+
+        let spec: Value = client
+            .get(api.replace("/api", "/openapi.json"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(spec["paths"]["/api/acct/whoami"]["post"].is_object());
+
+        let desk = || {
+            client
+                .post(&whoami)
+                .bearer_auth("desk-token")
+                .json(&json!({}))
+                .send()
+        };
+        let response = desk().await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["value"], "desk");
+        assert!(body.get("error").is_none());
+
+        let signed = |timestamp: u64, body: &'static str| {
+            let mut mac = Hmac::<Sha256>::new_from_slice(b"s3cret").unwrap();
+            mac.update(&hmac_message(
+                timestamp,
+                "POST",
+                "/api/acct/whoami",
+                body.as_bytes(),
+            ));
+            client
+                .post(&whoami)
+                .header("x-rheo-key", "batch")
+                .header("x-rheo-timestamp", timestamp.to_string())
+                .header("x-rheo-signature", hex::encode(mac.finalize().into_bytes()))
+                .body(body)
+        };
+        let now = now_millis();
+        let batch: Value = signed(now, "{}")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(batch["value"], "batch");
+        assert_eq!(
+            signed(now, "{}").send().await.unwrap().status(),
+            401,
+            "replay refused"
+        );
+        let stale = now - HMAC_WINDOW_MS - 1000;
+        assert_eq!(signed(stale, "{}").send().await.unwrap().status(), 401);
+
+        // The second desk request used the quota of 2
+        assert_eq!(desk().await.unwrap().status(), 200);
+        assert_eq!(desk().await.unwrap().status(), 429);
+
+        let internal = client
+            .post(format!("{}/cell/shutdown", api))
+            .bearer_auth("desk-token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(internal.status(), 404);
+
+        // Anyone else asking the provider directly is refused by its policy
+        let direct = provider.ask_mesh("acct/whoami", json!({})).await;
+        assert_eq!(direct.error.map(|e| e.code), Some(ErrorCode::Unauthorized));
+
+        cell.shutdown().await;
+        provider.shutdown().await;
+    }
+}
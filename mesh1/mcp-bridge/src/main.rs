@@ -0,0 +1,364 @@
+// mcp-bridge/src/main.rs - Model Context Protocol bridge for Rheo Mesh
+// Joins the mesh as a consumer-only cell and exposes every discovered capability
+// as an MCP tool over stdio (newline-delimited JSON-RPC 2.0).
+//
+// stdout carries protocol messages only - all logging goes to stderr.
+//
+// Register with an MCP client as a stdio server, e.g.:
+//   { "command": "mcp-bridge", "env": { "RHEO_SEED": "http://127.0.0.1:<port>" } }
+
+use cell_protocol_example1_rs::{
+    tools::{self, CapabilityDescriptor},
+    CellConfig, ErrorCode, MeshError, RheoCell, TraceResult,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::{mpsc, RwLock},
+};
+use tracing::{debug, info, warn};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+// ============================================================================
+// BRIDGE
+// ============================================================================
+
+pub struct McpBridge {
+    cell: Arc<RheoCell>,
+    tools: RwLock<Vec<CapabilityDescriptor>>,
+}
+
+impl McpBridge {
+    pub fn new(cell: Arc<RheoCell>) -> Self {
+        Self {
+            cell,
+            tools: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Handle one JSON-RPC message. Notifications produce no response.
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+            return Some(rpc_error(id, INVALID_REQUEST, "Missing method", None));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(self.initialize()),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools().await),
+            "tools/call" => self.call_tool(params).await,
+            _ if method.starts_with("notifications/") => return None,
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+        };
+
+        // Requests without an id are notifications - never answer them
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => rpc_error(Some(id), code, &message, None),
+        })
+    }
+
+    fn initialize(&self) -> Value {
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": {
+                "name": "rheo-mcp-bridge",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    /// Re-discover the mesh so newly joined cells show up as tools
+    async fn refresh(&self) -> Vec<CapabilityDescriptor> {
+        let descriptors: Vec<_> = self
+            .cell
+            .describe_mesh()
+            .await
+            .into_iter()
+            .filter(|d| !d.is_internal())
+            .collect();
+        debug!(count = descriptors.len(), "Discovered mesh tools");
+        *self.tools.write().await = descriptors.clone();
+        descriptors
+    }
+
+    async fn list_tools(&self) -> Value {
+        let descriptors = self.refresh().await;
+        let tools: Vec<Value> = tools::to_anthropic_tools(&descriptors)
+            .into_iter()
+            .map(|tool| {
+                json!({
+                    "name": tool["name"],
+                    "description": tool["description"],
+                    "inputSchema": tool["input_schema"],
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    async fn call_tool(&self, params: Value) -> Result<Value, (i64, String)> {
+        let Some(name) = params.get("name").and_then(|n| n.as_str()) else {
+            return Err((INVALID_PARAMS, "tools/call requires a tool name".into()));
+        };
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+        let known = tools::find_tool(&self.tools.read().await, name).cloned();
+        let descriptor = match known {
+            Some(d) => d,
+            // The client may call before listing, or the provider joined since
+            None => tools::find_tool(&self.refresh().await, name)
+                .cloned()
+                .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool: {}", name)))?,
+        };
+
+        let args = descriptor.args_from_tool_call(arguments);
+        info!(tool = %name, capability = %descriptor.capability, "Calling mesh");
+        let result = self.cell.ask_mesh(&descriptor.capability, args).await;
+        Ok(tool_result(result))
+    }
+}
+
+/// Mesh failures are tool results with `isError`, so the model can see and react to them
+fn tool_result(result: TraceResult) -> Value {
+    if result.ok {
+        let value = result.value.unwrap_or(Value::Null);
+        let text = match &value {
+            Value::String(s) => s.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        let mut out = json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": false,
+        });
+        if value.is_object() {
+            out["structuredContent"] = value;
+        }
+        return out;
+    }
+
+    let error = result.error.unwrap_or_else(|| {
+        MeshError::new(ErrorCode::Internal, "Unknown mesh failure", "mcp-bridge")
+    });
+    json!({
+        "content": [{ "type": "text", "text": format!("{}: {}", error.code, error.message) }],
+        "isError": true,
+        "structuredContent": {
+            "code": error.code.to_string(),
+            "message": error.message,
+            "from": error.from,
+            "trace": error.trace,
+            "details": error.details,
+        },
+    })
+}
+
+fn rpc_error(id: Option<Value>, code: i64, message: &str, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id.unwrap_or(Value::Null), "error": error })
+}
+
+// ============================================================================
+// STDIO TRANSPORT
+// ============================================================================
+
+async fn serve_stdio(bridge: Arc<McpBridge>) -> std::io::Result<()> {
+    let (tx, mut rx) = mpsc::channel::<Value>(64);
+
+    // Single writer so concurrent responses never interleave
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: Value = match serde_json::from_str(&line) {
+            Ok(m) => m,
+            Err(e) => {
+                warn!(error = %e, "Unparseable MCP message");
+                let _ = tx
+                    .send(rpc_error(None, PARSE_ERROR, &e.to_string(), None))
+                    .await;
+                continue;
+            }
+        };
+
+        // Tool calls can take seconds - don't block the reader
+        let bridge = Arc::clone(&bridge);
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            if let Some(response) = bridge.handle(message).await {
+                let _ = tx.send(response).await;
+            }
+        });
+    }
+
+    drop(tx);
+    let _ = writer.await;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter("info")
+        .with_writer(std::io::stderr)
+        .init();
+
+    let config = match CellConfig::from_env() {
+        Ok(config) => CellConfig {
+            id: "mcp-bridge".to_string(),
+            ..config
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    if config.seed.is_none() {
+        warn!("RHEO_SEED not set - relying on gossip to discover the mesh");
+    }
+
+    // Provides nothing itself - only listens so gossip can reach it
+    let cell = RheoCell::new(config);
+    let handle = cell.clone().listen().await.expect("Failed to join mesh");
+
+    info!("🔌 MCP bridge ready on stdio");
+    tokio::select! {
+        served = serve_stdio(Arc::new(McpBridge::new(cell.clone()))) => {
+            if let Err(e) = served {
+                warn!(error = %e, "stdio closed with error");
+            }
+            cell.shutdown().await;
+        }
+        // SIGINT/SIGTERM stopped the cell
+        _ = handle.join() => {
+            info!("Cell shut down, exiting");
+            // The pending stdin read can't be cancelled and would keep the runtime alive
+            std::process::exit(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cell_protocol_example1_rs::router;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_tools_round_trip() {
+        let provider = RheoCell::new(CellConfig::default());
+        provider.use_router(
+            router::Router::new()
+                .procedure(
+                    "math/double",
+                    router::Procedure::query(|n: i64, _| async move { Ok(n * 2) })
+                        .with_schemas()
+                        .describe("Double a number"),
+                )
+                .procedure(
+                    "math/fail",
+                    router::Procedure::query(|_n: i64, _| async move {
+                        Err::<i64, _>(MeshError::new(ErrorCode::HandlerError, "nope", "provider"))
+                    })
+                    .with_schemas(),
+                ),
+        );
+        let addr = provider.clone().listen().await.unwrap().addr();
+
+        let bridge_cell = RheoCell::new(CellConfig {
+            id: "mcp-bridge-test".into(),
+            seed: Some(format!("http://127.0.0.1:{}", addr.port())),
+            ..Default::default()
+        });
+        bridge_cell.clone().listen().await.unwrap();
+        let bridge = McpBridge::new(bridge_cell.clone());
+
+        let init = bridge
+            .handle(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }))
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+        assert!(bridge
+            .handle(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await
+            .is_none());
+
+        let mut listed = Value::Null;
+        for _ in 0..50 {
+            listed = bridge
+                .handle(json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }))
+                .await
+                .unwrap();
+            if listed["result"]["tools"].as_array().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let names: Vec<_> = listed["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, vec!["math__double", "math__fail"]);
+
+        let call = |id: i64, name: &str, arguments: Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": arguments },
+            })
+        };
+
+        let ok = bridge
+            .handle(call(3, "math__double", json!({ "input": 21 })))
+            .await
+            .unwrap();
+        assert_eq!(ok["result"]["isError"], false);
+        assert_eq!(ok["result"]["content"][0]["text"], "42");
+
+        let failed = bridge
+            .handle(call(4, "math__fail", json!({ "input": 1 })))
+            .await
+            .unwrap();
+        assert_eq!(failed["result"]["isError"], true);
+        assert_eq!(
+            failed["result"]["structuredContent"]["code"],
+            "HANDLER_ERROR"
+        );
+
+        let unknown = bridge.handle(call(5, "nope", json!({}))).await.unwrap();
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+
+        bridge_cell.shutdown().await;
+        provider.shutdown().await;
+    }
+}
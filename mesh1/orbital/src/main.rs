@@ -2,7 +2,7 @@
 // Provides: N-body gravity simulation, orbital propagation, trajectory prediction
 
 use cell_protocol_example1_rs::{
-    CellConfig, ErrorCode, MeshError, RheoCell, Signal,
+    CellConfig, Context, Contract, ErrorCode, MeshError, RheoCell,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -23,6 +23,10 @@ const SOLAR_MASS: f64 = 1.98892e30; // kg
 const EARTH_MASS: f64 = 5.972e24; // kg
 const MOON_MASS: f64 = 7.342e22; // kg
 
+// Contract version advertised for every orbital/* capability.
+// Bump the major version on breaking request/response changes.
+const CONTRACT_VERSION: &str = "1.0.0";
+
 // ============================================================================
 // CORE TYPES
 // ============================================================================
@@ -447,10 +451,16 @@ fn create_binary_star_preset() -> Vec<CelestialBody> {
 
 async fn create_simulation(
     args: CreateSimulationRequest,
-    _signal: Signal,
+    ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<Simulation, MeshError> {
-    let id = uuid::Uuid::new_v4().to_string();
+    let id = owned_simulation_id(&ctx).ok_or_else(|| {
+        MeshError::new(
+            ErrorCode::NotReady,
+            "No simulation id routes to this instance",
+            &ctx.cell().id,
+        )
+    })?;
 
     let bodies = if let Some(preset) = args.preset {
         match preset.as_str() {
@@ -500,9 +510,25 @@ async fn create_simulation(
     Ok(simulation)
 }
 
+// Calls naming a simulation are routed by simulation_id, so mint an id whose
+// calls come back to this instance when orbital is scaled out.
+fn owned_simulation_id(ctx: &Context) -> Option<String> {
+    let cell = ctx.cell();
+    let ring = cell.shard_ring("orbital/step");
+    for _ in 0..256 {
+        let id = uuid::Uuid::new_v4().to_string();
+        if ring.is_owner(&cell.id, &id) {
+            return Some(id);
+        }
+    }
+    // Only likely when we are missing from our own view of the ring
+    warn!(members = ?ring.members(), "No simulation id owned by this instance");
+    None
+}
+
 async fn add_body(
     args: AddBodyRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<Simulation, MeshError> {
     let mut sims = state.simulations.write().await;
@@ -522,7 +548,7 @@ async fn add_body(
 
 async fn step_simulation(
     args: StepRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<Simulation, MeshError> {
     let mut sims = state.simulations.write().await;
@@ -550,7 +576,7 @@ async fn step_simulation(
 
 async fn get_state(
     args: GetStateRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<Simulation, MeshError> {
     let sims = state.simulations.read().await;
@@ -568,7 +594,7 @@ async fn get_state(
 
 async fn get_stats(
     args: GetStateRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<SimulationStats, MeshError> {
     let sims = state.simulations.read().await;
@@ -586,7 +612,7 @@ async fn get_stats(
 
 async fn predict_trajectory(
     args: PredictTrajectoryRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<Vec<TrajectoryPoint>, MeshError> {
     let sims = state.simulations.read().await;
@@ -639,7 +665,7 @@ async fn predict_trajectory(
 
 async fn list_simulations(
     _args: (),
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<Vec<String>, MeshError> {
     let sims = state.simulations.read().await;
@@ -648,7 +674,7 @@ async fn list_simulations(
 
 async fn delete_simulation(
     sim_id: String,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<OrbitalState>,
 ) -> Result<bool, MeshError> {
     let mut sims = state.simulations.write().await;
@@ -668,96 +694,116 @@ fn now_millis() -> u64 {
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter("info")
-        .init();
+    // Cell.toml, then RHEO_* from the orchestrator (seed, id, ...)
+    let config = CellConfig::load().unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(2)
+    });
 
+    let cell = RheoCell::new(config);
+    cell.init_logging().expect("Failed to initialise logging");
     info!("🌌 Starting Orbital Mechanics Cell...");
 
-    let seed = std::env::var("RHEO_SEED").unwrap_or_default();
-
-    let config = CellConfig {
-        id: "orbital".to_string(),
-        port: 0,
-        seed: if seed.is_empty() { None } else { Some(seed) },
-        // registry_dir uses default (automatic workspace root resolution)
-        ..Default::default()
-    };
-
-    let cell = RheoCell::new(config);
     let state = Arc::new(OrbitalState::new());
 
     // Register capabilities
     {
         let s = state.clone();
-        cell.provide("orbital/create", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { create_simulation(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/create", CONTRACT_VERSION),
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { create_simulation(args, ctx, s).await })
+            },
+        );
     }
 
     {
         let s = state.clone();
-        cell.provide("orbital/add_body", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { add_body(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/add_body", CONTRACT_VERSION).shard_by("simulation_id"),
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { add_body(args, ctx, s).await })
+            },
+        );
     }
 
     {
         let s = state.clone();
-        cell.provide("orbital/step", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { step_simulation(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/step", CONTRACT_VERSION).shard_by("simulation_id"),
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { step_simulation(args, ctx, s).await })
+            },
+        );
     }
 
     {
         let s = state.clone();
-        cell.provide("orbital/state", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { get_state(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/state", CONTRACT_VERSION)
+                .shard_by("simulation_id")
+                .query(),
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { get_state(args, ctx, s).await })
+            },
+        );
     }
 
     {
         let s = state.clone();
-        cell.provide("orbital/stats", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { get_stats(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/stats", CONTRACT_VERSION)
+                .shard_by("simulation_id")
+                .query(),
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { get_stats(args, ctx, s).await })
+            },
+        );
     }
 
     {
         let s = state.clone();
-        cell.provide("orbital/predict", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { predict_trajectory(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/predict", CONTRACT_VERSION)
+                .shard_by("simulation_id")
+                .query(),
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { predict_trajectory(args, ctx, s).await })
+            },
+        );
     }
 
     {
         let s = state.clone();
-        cell.provide("orbital/list", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { list_simulations(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/list", CONTRACT_VERSION).query(),
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { list_simulations(args, ctx, s).await })
+            },
+        );
     }
 
     {
         let s = state.clone();
-        cell.provide("orbital/delete", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { delete_simulation(args, signal, s).await })
-        });
+        cell.provide_contract(
+            Contract::new("orbital/delete", CONTRACT_VERSION).shard_by(""), // args are the id
+            move |args, ctx| {
+                let s = s.clone();
+                Box::pin(async move { delete_simulation(args, ctx, s).await })
+            },
+        );
     }
 
-    let cell_for_shutdown = cell.clone();
-    let addr = cell.listen().await.expect("Failed to start orbital cell");
-    info!(addr = ?addr, "🪐 Orbital Mechanics Cell online");
-
-    // Wait a moment for server to fully initialize
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    let handle = cell.listen().await.expect("Failed to start orbital cell");
+    info!(addr = ?handle.addr(), "🪐 Orbital Mechanics Cell online");
+    handle.ready().await;
 
     // Announce capabilities to mesh
     info!("📡 Announcing capabilities to mesh...");
@@ -776,13 +822,9 @@ async fn main() {
         info!("  ✓ {}", cap);
     }
 
-    // Verify we're in our own atlas
-    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
     info!("✅ Orbital cell ready and advertising {} capabilities", capabilities.len());
 
-    tokio::signal::ctrl_c()
-        .await
-        .expect("Failed to listen for ctrl-c");
-    info!("Shutting down orbital cell...");
-    cell_for_shutdown.shutdown().await;
+    // Runs until SIGTERM/SIGINT
+    handle.join().await;
+    info!("Orbital cell stopped");
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ import { RheoCell } from "../protocols/example1/core";
 import { spawn } from "node:child_process";
 import { unlinkSync, existsSync, readFileSync, writeFileSync, readdirSync, statSync, mkdirSync, createWriteStream } from "node:fs";
 import { join, resolve } from "node:path";
-import { createHash } from "node:crypto";
+import { createHash, randomBytes } from "node:crypto";
 import { config } from "dotenv";
 
 // 1. Load Environment
@@ -14,6 +14,9 @@ if (!existsSync(LOG_DIR)) mkdirSync(LOG_DIR, { recursive: true });
 
 const PID_REGISTRY = ".rheo_pids";
 
+// Children refuse admin calls (cell/shutdown included) without this token
+const ADMIN_TOKEN = process.env.RHEO_ADMIN_TOKEN || randomBytes(24).toString("hex");
+
 
 const cellDeaths = new Map<string, { code: number | null; signal: string | null; time: string }>();
 
@@ -40,6 +43,7 @@ function parseToml(content: string): any {
         const trimmed = line.trim();
         if (!trimmed || trimmed.startsWith("#")) return;
         if (trimmed === "[env]") { target = result.env; return; }
+        if (trimmed.startsWith("[")) { target = result[trimmed.slice(1, -1)] = {}; return; } // e.g. [mesh], read by the cell itself
         const [key, ...rest] = trimmed.split("=");
         if (rest.length > 0) {
             const val = rest.join("=").trim().replace(/^["']|["']$/g, "");
@@ -121,6 +125,8 @@ async function spawnCell(blueprint: Blueprint) {
             ...blueprint.env,
             RHEO_CELL_ID: instanceId,
             RHEO_DISABLE_GHOST_CLEANUP: "false",
+            RHEO_MANIFESTS: join(ROOT_DIR, ".rheo", "manifests"),
+            RHEO_ADMIN_TOKEN: ADMIN_TOKEN,
             RHEO_SEED: orchestratorCell?.addr || ""  // FIXED: Pass orchestrator as seed
         }
     });
@@ -206,7 +212,7 @@ process.on("SIGINT", async () => {
     for (const [cellId, pid] of children.entries()) {
         try {
             // Try mesh shutdown first
-            const result = await orchestratorCell!.askMesh("cell/shutdown" as any, {});
+            const result = await orchestratorCell!.askMesh("cell/shutdown" as any, {}, { adminToken: ADMIN_TOKEN });
             shutdownResults.push({ cellId, pid, method: "mesh", ok: result.ok });
             console.log(`  ✅ ${cellId} (PID:${pid}) - graceful shutdown acknowledged`);
         } catch (e: any) {
@@ -0,0 +1,162 @@
+// rheo-replay - re-send journaled signals to a cell and diff the results
+//
+//   rheo-replay <journal.jsonl>... --target http://127.0.0.1:4000
+//               [--id <signal-id>] [--capability <cap>]
+//               [--fresh-ids] [--deadline-ms <ms>] [--realtime]
+//
+// Without --id every matching record is replayed in recorded order (a session).
+// Exits 1 if any replayed result differs from the recorded one.
+
+use cell_protocol_example1_rs::{
+    journal::{self, JournalRecord, ReplayOptions},
+    TraceResult,
+};
+use serde_json::Value;
+use std::{process::ExitCode, time::Duration};
+
+struct Args {
+    journals: Vec<String>,
+    target: String,
+    id: Option<String>,
+    capability: Option<String>,
+    options: ReplayOptions,
+    realtime: bool,
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: rheo-replay <journal.jsonl>... --target <url> [--id <signal-id>] \
+         [--capability <cap>] [--fresh-ids] [--deadline-ms <ms>] [--realtime]"
+    );
+    std::process::exit(2);
+}
+
+fn parse_args() -> Args {
+    let mut args = Args {
+        journals: Vec::new(),
+        target: String::new(),
+        id: None,
+        capability: None,
+        options: ReplayOptions::default(),
+        realtime: false,
+    };
+
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        let mut value = || it.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--target" => args.target = value(),
+            "--id" => args.id = Some(value()),
+            "--capability" => args.capability = Some(value()),
+            "--fresh-ids" => args.options.fresh_ids = true,
+            "--deadline-ms" => {
+                let ms = value().parse().unwrap_or_else(|_| usage());
+                args.options.deadline = Some(Duration::from_millis(ms));
+            }
+            "--realtime" => args.realtime = true,
+            "-h" | "--help" => usage(),
+            _ if arg.starts_with("--") => usage(),
+            _ => args.journals.push(arg),
+        }
+    }
+
+    if args.journals.is_empty() || args.target.is_empty() {
+        usage();
+    }
+    args
+}
+
+async fn send(
+    client: &reqwest::Client,
+    target: &str,
+    record: &JournalRecord,
+    options: &ReplayOptions,
+) -> Result<TraceResult, String> {
+    let signal = record.replay_signal(options);
+    let response: Value = client
+        .post(target)
+        .json(&signal)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Cells wrap results in { "result": ... } for TS compatibility
+    let result = response.get("result").cloned().unwrap_or(response);
+    serde_json::from_value(result).map_err(|e| format!("unreadable result: {}", e))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = parse_args();
+
+    let mut records = Vec::new();
+    for path in &args.journals {
+        match journal::read(path) {
+            Ok(r) => records.extend(r),
+            Err(e) => {
+                eprintln!("cannot read {}: {}", path, e);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    records.retain(|r| {
+        args.id.as_ref().is_none_or(|id| &r.signal.id == id)
+            && args
+                .capability
+                .as_ref()
+                .is_none_or(|cap| &r.signal.payload.capability == cap)
+    });
+    records.sort_by_key(|r| r.recorded_at.saturating_sub(r.duration_ms));
+
+    if records.is_empty() {
+        eprintln!("no matching records");
+        return ExitCode::from(2);
+    }
+
+    let client = reqwest::Client::new();
+    let mut differing = 0;
+    let mut previous_arrival = None;
+
+    for record in &records {
+        let arrival = record.recorded_at.saturating_sub(record.duration_ms);
+        if let (true, Some(prev)) = (args.realtime, previous_arrival) {
+            tokio::time::sleep(Duration::from_millis(arrival.saturating_sub(prev))).await;
+        }
+        previous_arrival = Some(arrival);
+
+        let label = format!("{} {}", record.signal.id, record.signal.payload.capability);
+        match send(&client, &args.target, record, &args.options).await {
+            Ok(replayed) => {
+                let diffs = journal::diff_results(&record.result, &replayed);
+                if diffs.is_empty() {
+                    println!("SAME  {}", label);
+                } else {
+                    differing += 1;
+                    println!("DIFF  {}", label);
+                    for diff in diffs {
+                        println!("      {}", diff);
+                    }
+                }
+            }
+            Err(e) => {
+                differing += 1;
+                println!("ERROR {}: {}", label, e);
+            }
+        }
+    }
+
+    println!(
+        "\n{} replayed, {} matched, {} differed",
+        records.len(),
+        records.len() - differing,
+        differing
+    );
+    if differing > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -7,7 +7,7 @@
 use std::{
     collections::HashMap,
     fmt,
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc, RwLock,
@@ -16,27 +16,29 @@ use std::{
 };
 
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
 use dashmap::DashMap;
-use ed25519_dalek::{SigningKey, VerifyingKey};
-use futures::future::join_all;
-use rand::rngs::OsRng;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use futures::{future::join_all, stream::FuturesUnordered, StreamExt};
+use rand::{rngs::OsRng, seq::IteratorRandom};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::path::PathBuf;
 use tokio::{
     net::TcpListener,
-    sync::{mpsc, Mutex, RwLock as TokioRwLock},
+    sync::{broadcast, mpsc, watch, Mutex, RwLock as TokioRwLock},
     task::JoinHandle,
     time::{interval, sleep, timeout},
 };
+use tokio_util::sync::CancellationToken;
 use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
-use tracing::{debug, error, info, warn, Level};
+use tracing::{debug, error, info, warn, Instrument, Level};
 use uuid::Uuid;
 
 // Re-exports
@@ -104,6 +106,70 @@ pub fn get_registry_dir() -> String {
         .to_string()
 }
 
+/// Get the standard manifest directory path
+/// Returns: <workspace>/.rheo/manifests (where the orchestrator sweeps for ghosts)
+pub fn get_manifest_dir() -> String {
+    get_workspace_root()
+        .join(".rheo")
+        .join("manifests")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// How long `describe_mesh_cached` reuses a fan-out
+const DESCRIBE_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// How far a process start time read from `/proc` may trail the recorded one
+/// (boot time is only known to the second)
+#[cfg(unix)]
+const GHOST_START_SLACK_MS: u64 = 2_000;
+
+/// When process `pid` started, in unix millis - None if it isn't running or
+/// this platform can't say
+#[cfg(unix)]
+fn process_started_at(pid: u32) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // The command name may hold spaces; starttime is the 20th field after it
+        let ticks: u64 = stat
+            .rsplit_once(')')?
+            .1
+            .split_whitespace()
+            .nth(19)?
+            .parse()
+            .ok()?;
+        let boot_secs: u64 = std::fs::read_to_string("/proc/stat")
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))?
+            .trim()
+            .parse()
+            .ok()?;
+        // SAFETY: sysconf has no memory-safety preconditions
+        let hz = u64::try_from(unsafe { libc::sysconf(libc::_SC_CLK_TCK) })
+            .ok()
+            .filter(|hz| *hz > 0)?;
+        Some(boot_secs * 1000 + ticks * 1000 / hz)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Version of the running binary: the first 16 hex chars of its SHA-256
+pub fn binary_version() -> &'static str {
+    static VERSION: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    VERSION.get_or_init(|| {
+        std::env::current_exe()
+            .and_then(std::fs::read)
+            .map(|bytes| hex::encode(Sha256::digest(bytes))[..16].to_string())
+            .unwrap_or_else(|_| format!("v_{}", now_millis()))
+    })
+}
+
 // ============================================================================
 // ERROR SYSTEM
 // ============================================================================
@@ -119,6 +185,20 @@ pub struct MeshError {
     pub timestamp: u64,
     pub history: Option<Vec<NarrativeStep>>,
     pub details: Option<Value>,
+    /// Calls the failing handler made through its `Context`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ChildCall>,
+    /// Narrative envelope of the failed signal (TS `_envelope`)
+    #[serde(
+        rename = "_envelope",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "narrative::deserialize_lenient"
+    )]
+    pub envelope: Option<Box<narrative::NarrativeEnvelope>>,
+    /// Verification of `history` against atlas keys, filled in by the reporting cell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<narrative::StepVerification>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -133,8 +213,10 @@ pub enum ErrorCode {
     CircuitOpen,
     NotReady,
     ValidationFailed,
+    OutputValidationFailed,
     Unauthorized,
     RateLimited,
+    Conflict,
     Internal,
 }
 
@@ -151,13 +233,67 @@ impl fmt::Display for ErrorCode {
             ErrorCode::CircuitOpen => write!(f, "CIRCUIT_OPEN"),
             ErrorCode::NotReady => write!(f, "NOT_READY"),
             ErrorCode::ValidationFailed => write!(f, "VALIDATION_FAILED"),
+            ErrorCode::OutputValidationFailed => write!(f, "OUTPUT_VALIDATION_FAILED"),
             ErrorCode::Unauthorized => write!(f, "UNAUTHORIZED"),
             ErrorCode::RateLimited => write!(f, "RATE_LIMITED"),
+            ErrorCode::Conflict => write!(f, "CONFLICT"),
             ErrorCode::Internal => write!(f, "INTERNAL"),
         }
     }
 }
 
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 15] = [
+        ErrorCode::NotFound,
+        ErrorCode::Timeout,
+        ErrorCode::LoopDetected,
+        ErrorCode::HandlerError,
+        ErrorCode::RpcFail,
+        ErrorCode::RpcUnreachable,
+        ErrorCode::RpcTimeout,
+        ErrorCode::CircuitOpen,
+        ErrorCode::NotReady,
+        ErrorCode::ValidationFailed,
+        ErrorCode::OutputValidationFailed,
+        ErrorCode::Unauthorized,
+        ErrorCode::RateLimited,
+        ErrorCode::Conflict,
+        ErrorCode::Internal,
+    ];
+
+    /// Failures of the route itself, where another provider may still succeed.
+    /// Anything else is the provider's answer and must not be retried elsewhere.
+    pub fn is_routing_failure(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::NotFound
+                | ErrorCode::RpcFail
+                | ErrorCode::RpcUnreachable
+                | ErrorCode::RpcTimeout
+                | ErrorCode::CircuitOpen
+                | ErrorCode::NotReady
+        )
+    }
+
+    /// Status for HTTP clients outside the mesh
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ErrorCode::NotFound => 404,
+            ErrorCode::ValidationFailed => 400,
+            ErrorCode::Unauthorized => 403,
+            ErrorCode::RateLimited => 429,
+            ErrorCode::Conflict => 409,
+            ErrorCode::Timeout | ErrorCode::RpcTimeout => 504,
+            ErrorCode::RpcFail | ErrorCode::RpcUnreachable => 502,
+            ErrorCode::CircuitOpen | ErrorCode::NotReady => 503,
+            ErrorCode::LoopDetected
+            | ErrorCode::HandlerError
+            | ErrorCode::OutputValidationFailed
+            | ErrorCode::Internal => 500,
+        }
+    }
+}
+
 impl MeshError {
     pub fn new(code: ErrorCode, message: impl Into<String>, from: impl Into<String>) -> Self {
         Self {
@@ -168,6 +304,9 @@ impl MeshError {
             timestamp: now_millis(),
             history: None,
             details: None,
+            children: Vec::new(),
+            envelope: None,
+            integrity: None,
         }
     }
 
@@ -186,6 +325,30 @@ impl MeshError {
         self
     }
 
+    pub fn with_children(mut self, children: Vec<ChildCall>) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn with_envelope(mut self, envelope: narrative::NarrativeEnvelope) -> Self {
+        self.envelope = Some(Box::new(envelope));
+        self
+    }
+
+    /// Full ledger report (path, failure and timing analysis) from the attached envelope
+    pub fn narrative_report(&self) -> Option<narrative::ForensicReport> {
+        let envelope = self.envelope.as_ref()?;
+        let ledger = narrative::NarrativeLedger::new();
+        ledger.merge(envelope);
+        ledger.generate_forensic_report(
+            envelope.signal_id()?,
+            Some(serde_json::json!({
+                "failedAt": self.from,
+                "error": { "code": self.code.to_string(), "message": self.message },
+            })),
+        )
+    }
+
     /// Generate forensic report for debugging
     pub fn forensic_report(&self) -> String {
         let mut lines = vec![
@@ -227,23 +390,93 @@ impl MeshError {
             lines.push(format!(
                 "╠══════════════════════════════════════════════════════════════════╣"
             ));
+            lines.push("║ DETAILS:".to_string());
+            for line in serde_json::to_string_pretty(details)
+                .unwrap_or_default()
+                .lines()
+            {
+                lines.push(format!("║   {}", line));
+            }
+        }
+
+        if let Some(integrity) = &self.integrity {
+            lines.push(
+                "╠══════════════════════════════════════════════════════════════════╣".to_string(),
+            );
             lines.push(format!(
-                "║ DETAILS: {}",
-                serde_json::to_string_pretty(details)
-                    .unwrap_or_default()
-                    .lines()
-                    .next()
-                    .unwrap_or("")
+                "║ STEP INTEGRITY: {} ({}/{} steps verified)",
+                if integrity.is_intact() {
+                    "INTACT"
+                } else {
+                    "TAMPERED"
+                },
+                integrity.verified,
+                integrity.steps
+            ));
+            for issue in &integrity.issues {
+                lines.push(format!(
+                    "║ ⚠ step {} {}: {} - {:?}",
+                    issue.index, issue.cell, issue.action, issue.kind
+                ));
+            }
+        }
+
+        if !self.children.is_empty() {
+            lines.push(
+                "╠══════════════════════════════════════════════════════════════════╣".to_string(),
+            );
+            lines.push(format!(
+                "║ CALL TREE ({} child calls):",
+                self.children.len()
             ));
+            for child in &self.children {
+                child.render(1, &mut lines);
+            }
         }
 
         lines.push(format!(
             "╚══════════════════════════════════════════════════════════════════╝"
         ));
+        if let Some(report) = self.narrative_report() {
+            lines.push(report.render());
+        }
         lines.join("\n")
     }
 }
 
+/// A call a handler made through `Context::call`, linked to its parent signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildCall {
+    pub signal_id: String,
+    pub capability: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<MeshError>,
+}
+
+impl ChildCall {
+    fn render(&self, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        match &self.error {
+            None => lines.push(format!(
+                "║ {}├─ {} ✓ ({}ms)",
+                indent, self.capability, self.duration_ms
+            )),
+            Some(e) => {
+                lines.push(format!(
+                    "║ {}├─ {} ✗ [{}] {} (from: {}, {}ms)",
+                    indent, self.capability, e.code, e.message, e.from, self.duration_ms
+                ));
+                for child in &e.children {
+                    child.render(depth + 1, lines);
+                }
+            }
+        }
+    }
+}
+
 impl std::error::Error for MeshError {}
 
 impl fmt::Display for MeshError {
@@ -267,6 +500,14 @@ pub struct NarrativeStep {
     pub data: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration_micros: Option<u64>,
+    /// Digest of the previous step, so removed or reordered steps show up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Ed25519 signature over `hash` by the recording cell (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 impl NarrativeStep {
@@ -277,9 +518,50 @@ impl NarrativeStep {
             action: action.into(),
             data: None,
             duration_micros: None,
+            prev_hash: None,
+            hash: None,
+            signature: None,
         }
     }
 
+    /// sha256 over the step's content and its link to the previous step
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        let data = self
+            .data
+            .as_ref()
+            .map(|d| d.to_string())
+            .unwrap_or_default();
+        let duration = self
+            .duration_micros
+            .map(|d| d.to_string())
+            .unwrap_or_default();
+        for part in [
+            self.prev_hash.as_deref().unwrap_or(""),
+            &self.cell,
+            &self.timestamp.to_string(),
+            &self.action,
+            &data,
+            &duration,
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.hash.is_some() && self.signature.is_some()
+    }
+
+    /// Link to `previous` and sign the resulting hash
+    pub fn sign(&mut self, previous: Option<&NarrativeStep>, key: &SigningKey) {
+        self.prev_hash = previous.map(NarrativeStep::digest);
+        let hash = self.digest();
+        self.signature = Some(hex::encode(key.sign(hash.as_bytes()).to_bytes()));
+        self.hash = Some(hash);
+    }
+
     pub fn with_data(mut self, data: impl Serialize) -> Self {
         self.data = serde_json::to_value(data).ok();
         self
@@ -307,6 +589,30 @@ pub struct AtlasEntry {
     pub metadata: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub latency_ms: Option<u64>,
+    #[serde(rename = "capInfo", default, skip_serializing_if = "HashMap::is_empty")]
+    pub cap_info: HashMap<String, CapInfo>,
+    /// Tombstone: the cell is draining and should no longer be routed to
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub leaving: bool,
+    /// Most signals the cell takes in one `{"batch": [...]}` request - None
+    /// for cells that don't take batches (every TypeScript cell)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_batch: Option<usize>,
+}
+
+/// Per-capability details a provider advertises through the atlas
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CapInfo {
+    /// Contract versions served: the primary version followed by compatible ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<String>,
+    /// Args field whose value picks the provider (see [`shard`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<String>,
+    /// Declared a query (`Procedure::query`), so a duplicate call is harmless
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub query: bool,
 }
 
 impl AtlasEntry {
@@ -322,6 +628,9 @@ impl AtlasEntry {
             gossip_hop_count: 0,
             metadata: None,
             latency_ms: None,
+            cap_info: HashMap::new(),
+            leaving: false,
+            max_batch: None,
         }
     }
 
@@ -329,6 +638,122 @@ impl AtlasEntry {
         self.pub_key = key.into();
         self
     }
+
+    /// Does this entry provide `capability` in a version matching `version_req`?
+    pub fn serves(&self, capability: &str, version_req: Option<&str>) -> bool {
+        if self.leaving || !self.caps.iter().any(|c| c == capability) {
+            return false;
+        }
+        let versions = self
+            .cap_info
+            .get(capability)
+            .map(|i| i.versions.as_slice())
+            .unwrap_or_default();
+        versions_satisfy(version_req, versions)
+    }
+}
+
+/// Contract of a versioned capability (mirrors `Contract` in core.ts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contract {
+    pub capability: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_schema: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    #[serde(default)]
+    pub transport: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<Value>,
+    #[serde(default)]
+    pub compatibility: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<String>,
+    /// Free of side effects, so a duplicate call is harmless
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub query: bool,
+}
+
+impl Contract {
+    pub fn new(capability: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            capability: capability.into(),
+            version: version.into(),
+            description: None,
+            input_schema: None,
+            output_schema: None,
+            transport: serde_json::json!({ "protocol": "INTERNAL", "adapters": [] }),
+            machine: None,
+            compatibility: Vec::new(),
+            shard_key: None,
+            query: false,
+        }
+    }
+
+    /// Generate the input/output schemas from the Rust types
+    pub fn with_schemas<I: schemars::JsonSchema, O: schemars::JsonSchema>(mut self) -> Self {
+        self.input_schema = serde_json::to_value(schemars::schema_for!(I)).ok();
+        self.output_schema = serde_json::to_value(schemars::schema_for!(O)).ok();
+        self
+    }
+
+    pub fn describe(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Also serve callers asking for an older, still compatible version
+    pub fn compatible_with(mut self, version: impl Into<String>) -> Self {
+        self.compatibility.push(version.into());
+        self
+    }
+
+    /// Send calls with the same `args.<field>` to the same provider
+    pub fn shard_by(mut self, field: impl Into<String>) -> Self {
+        self.shard_key = Some(field.into());
+        self
+    }
+
+    /// Declare the capability read-only (contracts are mutations by default)
+    pub fn query(mut self) -> Self {
+        self.query = true;
+        self
+    }
+
+    /// Primary version followed by the compatible ones
+    pub fn versions(&self) -> Vec<String> {
+        std::iter::once(self.version.clone())
+            .chain(self.compatibility.iter().cloned())
+            .collect()
+    }
+}
+
+/// Split `orbital/create@^1` into the capability and its version requirement
+pub fn split_versioned(capability: &str) -> (&str, Option<&str>) {
+    match capability.split_once('@') {
+        Some((cap, req)) if !req.is_empty() => (cap, Some(req)),
+        Some((cap, _)) => (cap, None),
+        None => (capability, None),
+    }
+}
+
+/// Check advertised versions against a semver requirement.
+/// Unversioned providers only serve unconstrained requests.
+pub fn versions_satisfy(version_req: Option<&str>, versions: &[String]) -> bool {
+    let Some(req) = version_req else {
+        return true;
+    };
+    let Ok(req) = semver::VersionReq::parse(req) else {
+        return false;
+    };
+    versions
+        .iter()
+        .filter_map(|v| semver::Version::parse(v).ok())
+        .any(|v| req.matches(&v))
 }
 
 /// Signal envelope - the universal message format
@@ -375,21 +800,28 @@ pub struct Payload {
     pub capability: String,
     #[serde(default)]
     pub args: Value,
+    /// Semver requirement on the provider's contract, e.g. `^1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
 impl Signal {
+    /// `capability` may carry a version requirement: `orbital/create@^1`
     pub fn new(
         from: impl Into<String>,
         capability: impl Into<String>,
         args: impl Serialize,
     ) -> Self {
+        let capability = capability.into();
+        let (capability, version) = split_versioned(&capability);
         Self {
             id: Uuid::new_v4().to_string(),
             from: from.into(),
             intent: Intent::Ask,
             payload: Payload {
-                capability: capability.into(),
+                capability: capability.to_string(),
                 args: serde_json::to_value(args).unwrap_or_default(),
+                version: version.map(str::to_string),
             },
             proofs: HashMap::new(),
             atlas: HashMap::new(),
@@ -415,6 +847,38 @@ impl Signal {
         self
     }
 
+    /// Sign who sent this, and when, over the id, capability and args, so
+    /// receivers can trust `from` (see [`policy`]). Set those first.
+    pub fn sign_origin(&mut self, key: &SigningKey) {
+        self.proofs.insert(
+            policy::ORIGIN_TS_PROOF.to_string(),
+            now_millis().to_string(),
+        );
+        let signature = key.sign(policy::origin_message(self).as_bytes());
+        self.proofs.insert(
+            policy::ORIGIN_PROOF.to_string(),
+            hex::encode(signature.to_bytes()),
+        );
+    }
+
+    /// Authorize an admin capability with the operator token
+    pub fn with_admin_token(self, token: impl Into<String>) -> Self {
+        self.with_proof(admin::TOKEN_PROOF, token)
+    }
+
+    /// Authorize an admin capability on `cell` with a proof signed by `key`.
+    /// Covers the id, sender, capability and args, so set those first.
+    pub fn with_admin_signature(self, key: &SigningKey, cell: &str) -> Self {
+        let ts = now_millis();
+        let signature = key.sign(admin::signing_message(&self, ts, cell).as_bytes());
+        self.with_proof(
+            admin::KEY_PROOF,
+            hex::encode(VerifyingKey::from(key).to_bytes()),
+        )
+        .with_proof(admin::TS_PROOF, ts.to_string())
+        .with_proof(admin::SIG_PROOF, hex::encode(signature.to_bytes()))
+    }
+
     pub fn is_expired(&self) -> bool {
         self.deadline_ms.map(|d| now_millis() > d).unwrap_or(false)
     }
@@ -424,6 +888,29 @@ impl Signal {
         self.steps.push(NarrativeStep::new(cell, action));
     }
 
+    /// Record a step chained to the previous one and signed with the cell's key
+    pub fn record_signed_step(
+        &mut self,
+        cell: impl Into<String>,
+        action: impl Into<String>,
+        key: &SigningKey,
+    ) {
+        let mut step = NarrativeStep::new(cell, action);
+        step.sign(self.steps.last(), key);
+        self.steps.push(step);
+    }
+
+    /// The step chain closed by a signed [`narrative::SEALED`] step counting
+    /// the steps before it - what a failure carries as its history
+    pub fn sealed_steps(&self, cell: impl Into<String>, key: &SigningKey) -> Vec<NarrativeStep> {
+        let mut steps = self.steps.clone();
+        let mut seal = NarrativeStep::new(cell, narrative::SEALED)
+            .with_data(serde_json::json!({ "steps": steps.len() }));
+        seal.sign(steps.last(), key);
+        steps.push(seal);
+        steps
+    }
+
     /// Mark cell as visited
     pub fn mark_visited(&mut self, cell_id: impl Into<String>, addr: impl Into<String>) {
         let id = cell_id.into();
@@ -564,7 +1051,7 @@ pub enum CircuitState {
 
 /// Type-erased handler for capabilities
 pub type BoxedHandler =
-    Box<dyn Fn(Value, Signal) -> futures::future::BoxFuture<'static, TraceResult> + Send + Sync>;
+    Box<dyn Fn(Value, Context) -> futures::future::BoxFuture<'static, TraceResult> + Send + Sync>;
 
 /// Handler trait for typed capabilities
 #[async_trait::async_trait]
@@ -573,7 +1060,7 @@ where
     I: DeserializeOwned + Send + 'static,
     O: Serialize + Send + 'static,
 {
-    async fn handle(&self, input: I, signal: Signal) -> Result<O, MeshError>;
+    async fn handle(&self, input: I, ctx: Context) -> Result<O, MeshError>;
 }
 
 // Helper to create boxed handlers - FIXED: Added Clone bound
@@ -581,12 +1068,12 @@ pub fn handler<I, O, F, Fut>(f: F) -> BoxedHandler
 where
     I: DeserializeOwned + Send + 'static,
     O: Serialize + Send + 'static,
-    F: Fn(I, Signal) -> Fut + Send + Sync + Clone + 'static,
+    F: Fn(I, Context) -> Fut + Send + Sync + Clone + 'static,
     Fut: std::future::Future<Output = Result<O, MeshError>> + Send + 'static,
 {
-    Box::new(move |args, signal| {
+    Box::new(move |args, ctx| {
         let f = f.clone(); // Clone the handler
-        let signal_id = signal.id.clone(); // Clone signal.id before moving
+        let signal_id = ctx.id.clone(); // Clone signal.id before moving
         Box::pin(async move {
             let input: I = match serde_json::from_value(args) {
                 Ok(i) => i,
@@ -602,7 +1089,7 @@ where
                 }
             };
             let start = Instant::now();
-            match f(input, signal).await {
+            match f(input, ctx).await {
                 Ok(output) => TraceResult::success(signal_id, output).with_latency(start.elapsed()),
                 Err(e) => TraceResult::failure(signal_id, e),
             }
@@ -610,24 +1097,220 @@ where
     })
 }
 
+/// What a handler receives: the incoming signal plus a way to call the mesh
+/// on its behalf. Child calls inherit the deadline, trace, narrative and
+/// proofs, and are linked back to the parent (like `NarrativeLedger.fork`).
+#[derive(Clone)]
+pub struct Context {
+    pub signal: Signal,
+    cell: Arc<RheoCell>,
+    children: Arc<std::sync::Mutex<Vec<ChildCall>>>,
+}
+
+impl std::ops::Deref for Context {
+    type Target = Signal;
+
+    fn deref(&self) -> &Signal {
+        &self.signal
+    }
+}
+
+impl Context {
+    pub fn new(cell: Arc<RheoCell>, signal: Signal) -> Self {
+        Self {
+            signal,
+            cell,
+            children: Arc::new(std::sync::Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn cell(&self) -> &Arc<RheoCell> {
+        &self.cell
+    }
+
+    /// Child calls made so far, in completion order
+    pub fn children(&self) -> Vec<ChildCall> {
+        self.children.lock().unwrap().clone()
+    }
+
+    /// Build a child signal of the current one
+    pub fn child_signal(&self, capability: impl Into<String>, args: impl Serialize) -> Signal {
+        let mut child = Signal::new(&self.cell.id, capability, args);
+        child.deadline_ms = self.signal.deadline_ms;
+        child.trace = self.signal.trace.clone();
+        child.proofs = self.signal.proofs.clone();
+        child.steps = self.signal.steps.clone();
+        child.record_signed_step(
+            &self.cell.id,
+            format!("FORK_FROM_{}", self.signal.id),
+            &self.cell.signing_key,
+        );
+        child.extensions.insert(
+            "_parentId".to_string(),
+            Value::String(self.signal.id.clone()),
+        );
+        if let Some(ctx) = telemetry::TraceContext::from_signal(&self.signal) {
+            ctx.inject(&mut child);
+        }
+        // The parent's origin proof names the parent, not this call
+        child.sign_origin(&self.cell.signing_key);
+        self.cell.ledger.fork(&self.signal.id, &child, "child call");
+        child
+    }
+
+    /// Call a capability as a child of the current signal
+    pub async fn call_raw(
+        &self,
+        capability: impl Into<String>,
+        args: impl Serialize,
+    ) -> TraceResult {
+        let capability = capability.into();
+        let child = self.child_signal(&capability, args);
+        let child_id = child.id.clone();
+        let start = Instant::now();
+
+        let result = self.cell.route(child).await;
+
+        self.children.lock().unwrap().push(ChildCall {
+            signal_id: child_id,
+            capability,
+            ok: result.ok,
+            duration_ms: start.elapsed().as_millis() as u64,
+            error: result.error.clone(),
+        });
+        result
+    }
+
+    /// Typed child call
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        capability: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<T, MeshError> {
+        let result = self.call_raw(capability, args).await;
+        if !result.ok {
+            return Err(result.error.unwrap_or_else(|| {
+                MeshError::new(ErrorCode::Internal, "Unknown error", "context")
+            }));
+        }
+        result.into_value()
+    }
+
+    /// Multicast as a child of the current signal; each provider is a child call
+    pub async fn ask_all(
+        &self,
+        capability: impl Into<String>,
+        args: impl Serialize,
+        timeout_ms: u64,
+    ) -> MulticastResult {
+        self.ask_all_with(capability, args, multicast::Options::new(timeout_ms))
+            .await
+    }
+
+    /// [`Context::ask_all`], finishing as `options` say
+    pub async fn ask_all_with(
+        &self,
+        capability: impl Into<String>,
+        args: impl Serialize,
+        options: multicast::Options,
+    ) -> MulticastResult {
+        let capability = capability.into();
+        let template = self.child_signal(&capability, args);
+        let child_id = template.id.clone();
+        let result = self.cell.multicast(&capability, template, options).await;
+
+        let mut children = self.children.lock().unwrap();
+        for item in result.results.iter().chain(result.failures.iter()) {
+            children.push(ChildCall {
+                signal_id: child_id.clone(),
+                capability: format!("{} -> {}", capability, item.cell_id),
+                ok: item.error.is_none(),
+                duration_ms: item.latency_ms,
+                error: item.error.clone(),
+            });
+        }
+        drop(children);
+        result
+    }
+}
+
 // ============================================================================
 // CORE CELL IMPLEMENTATION
 // ============================================================================
 
+/// Log line format for [`RheoCell::init_logging`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
 /// Configuration for a RheoCell
 #[derive(Debug, Clone)]
 pub struct CellConfig {
     pub id: String,
+    /// Orchestrator hints from Cell.toml
+    pub critical: bool,
+    pub scalable: bool,
     pub port: u16,
+    /// Interface the mesh listener binds; peers are told this address unless
+    /// it is unspecified (`0.0.0.0`), when they get 127.0.0.1
+    pub bind_host: IpAddr,
     pub seed: Option<String>,
     pub registry_dir: Option<String>,
+    /// Where `<id>.cell.json` is written while listening (off when None - the
+    /// orchestrator sets `RHEO_MANIFESTS` to [`get_manifest_dir`])
+    pub manifest_dir: Option<String>,
+    /// Kill the process named in a leftover manifest for this id before binding,
+    /// if it started no later than the manifest says (Linux only)
+    pub ghost_cleanup: bool,
+    /// Shut down on SIGTERM/SIGINT
+    pub handle_signals: bool,
+    /// How long shutdown waits for in-flight executions to finish
+    pub drain_timeout_ms: u64,
+    /// How long a result is replayed to duplicate arrivals of its signal; 0 turns
+    /// replaying off
+    pub result_cache_ttl_ms: u64,
+    /// How often `/ws` connections are pinged; two silent intervals close them
+    pub ws_heartbeat_ms: u64,
+    /// Publish `narrative/<cid>` events. They carry every routed signal's
+    /// envelope, args included, to any `/ws` client - keep off unless the
+    /// listener is private.
+    pub narrative_events: bool,
+    /// Operator token accepted for admin capabilities
+    pub admin_token: Option<String>,
+    /// Hex ed25519 public keys whose signed proofs are accepted for admin capabilities
+    pub admin_keys: Vec<String>,
+    /// Access control rules for the capabilities this cell provides (see [`policy`])
+    pub policy_file: Option<String>,
+    /// Hex ed25519 secret key, so peers can pin this cell's public key in their
+    /// policy's `[identities]` (a new key every start when None)
+    pub identity_key: Option<String>,
     pub max_concurrent: usize,
+    /// Most signals accepted in one `{"batch": [...]}` request
+    pub max_batch: usize,
+    /// How providers are ordered when forwarding (see [`balance`])
+    pub load_balancer: balance::Strategy,
+    /// Per-capability strategies as `(pattern, strategy)`; the first match wins
+    pub load_balancers: Vec<(String, balance::Strategy)>,
+    /// Query capabilities (`*` globs) worth a duplicate call to a second
+    /// provider when the first is slower than `hedge_percentile` of recent calls
+    pub hedge: Vec<String>,
+    pub hedge_percentile: u64,
     pub rpc_timeout_ms: u64,
     pub gossip_interval_ms: u64,
     pub atlas_ttl_ms: u64,
     pub enable_compression: bool,
     pub enable_tls: bool,
     pub log_level: Level,
+    pub log_format: LogFormat,
+    /// Also send log lines to this capability (e.g. `log/append`)
+    pub log_ship_to: Option<String>,
+    /// Record routed signals and results here (off when None)
+    pub journal_dir: Option<String>,
+    pub journal_max_bytes: u64,
+    pub journal_max_files: usize,
 }
 
 impl Default for CellConfig {
@@ -637,88 +1320,504 @@ impl Default for CellConfig {
                 "cell_{}",
                 Uuid::new_v4().to_string().split('-').next().unwrap()
             ),
+            critical: false,
+            scalable: false,
             port: 0,
+            bind_host: IpAddr::from([0, 0, 0, 0]),
             seed: None,
             registry_dir: Some(get_registry_dir()),
+            manifest_dir: None,
+            ghost_cleanup: false,
+            handle_signals: true,
+            drain_timeout_ms: 10_000,
+            result_cache_ttl_ms: 60_000,
+            ws_heartbeat_ms: 15_000,
+            narrative_events: false,
+            admin_token: None,
+            admin_keys: Vec::new(),
+            policy_file: None,
+            identity_key: None,
             max_concurrent: 1000,
+            max_batch: 256,
+            load_balancer: balance::Strategy::default(),
+            load_balancers: Vec::new(),
+            hedge: Vec::new(),
+            hedge_percentile: 95,
             rpc_timeout_ms: 5000,
             gossip_interval_ms: 15000,
             atlas_ttl_ms: 60000,
             enable_compression: true,
             enable_tls: false,
             log_level: Level::INFO,
+            log_format: LogFormat::Text,
+            log_ship_to: None,
+            journal_dir: None,
+            journal_max_bytes: 64 * 1024 * 1024,
+            journal_max_files: 5,
         }
     }
 }
 
-/// The core distributed cell - sovereign compute node
-pub struct RheoCell {
-    pub id: String,
-    pub addr: Arc<TokioRwLock<String>>,
-    pub port: u16,
-    pub config: CellConfig,
-
-    // Cryptographic identity
-    signing_key: SigningKey,
-    pub verifying_key: VerifyingKey,
-    pub pub_key_hex: String,
-
-    // State
-    atlas: Arc<DashMap<String, AtlasEntry>>,
-    handlers: Arc<DashMap<String, BoxedHandler>>,
-    circuits: Arc<DashMap<String, CircuitBreaker>>,
-
-    // Request deduplication
-    seen_nonces: Arc<DashMap<String, Instant>>,
-    active_executions: Arc<DashMap<String, Arc<tokio::sync::Mutex<Option<TraceResult>>>>>,
-    result_cache: Arc<DashMap<String, (TraceResult, Instant)>>,
-
-    // Metrics
-    metrics: Arc<Metrics>,
+/// A configuration value that could not be used, named by the key it came from
+/// (`mesh.port` in Cell.toml, `RHEO_PORT` in the environment, or the file path).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub key: String,
+    pub message: String,
+}
 
-    // Lifecycle
-    shutdown_tx: Option<mpsc::Sender<()>>,
-    is_shutting_down: Arc<AtomicU64>, // 0 = running, 1 = shutting down, 2 = shut down
-    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+impl ConfigError {
+    fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
 }
 
-#[derive(Default)]
-struct Metrics {
-    requests_total: AtomicU64,
-    requests_success: AtomicU64,
-    requests_failed: AtomicU64,
-    latency_sum_micros: AtomicU64,
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config `{}`: {}", self.key, self.message)
+    }
 }
 
-impl RheoCell {
-    /// Create a new cell with the given configuration
-    pub fn new(config: CellConfig) -> Arc<Self> {
-        let signing_key = SigningKey::generate(&mut OsRng);
-        let verifying_key = VerifyingKey::from(&signing_key);
-        let pub_key_hex = hex::encode(verifying_key.to_bytes());
+impl std::error::Error for ConfigError {}
+
+/// Config fields settable from Cell.toml's `[mesh]` table and their env variables
+const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("port", "RHEO_PORT"),
+    ("bind_host", "RHEO_BIND_HOST"),
+    ("seed", "RHEO_SEED"),
+    ("registry_dir", "RHEO_REGISTRY"),
+    ("manifest_dir", "RHEO_MANIFESTS"),
+    ("ghost_cleanup", "RHEO_GHOST_CLEANUP"),
+    ("handle_signals", "RHEO_HANDLE_SIGNALS"),
+    ("drain_timeout_ms", "RHEO_DRAIN_TIMEOUT_MS"),
+    ("result_cache_ttl_ms", "RHEO_RESULT_CACHE_TTL_MS"),
+    ("ws_heartbeat_ms", "RHEO_WS_HEARTBEAT_MS"),
+    ("narrative_events", "RHEO_NARRATIVE_EVENTS"),
+    ("admin_token", "RHEO_ADMIN_TOKEN"),
+    ("admin_keys", "RHEO_ADMIN_KEYS"),
+    ("policy_file", "RHEO_POLICY"),
+    ("identity_key", "RHEO_IDENTITY_KEY"),
+    ("max_concurrent", "RHEO_MAX_CONCURRENT"),
+    ("max_batch", "RHEO_MAX_BATCH"),
+    ("load_balancer", "RHEO_LOAD_BALANCER"),
+    ("hedge", "RHEO_HEDGE"),
+    ("hedge_percentile", "RHEO_HEDGE_PERCENTILE"),
+    ("rpc_timeout_ms", "RHEO_RPC_TIMEOUT_MS"),
+    ("gossip_interval_ms", "RHEO_GOSSIP_INTERVAL_MS"),
+    ("atlas_ttl_ms", "RHEO_ATLAS_TTL_MS"),
+    ("enable_compression", "RHEO_COMPRESSION"),
+    ("enable_tls", "RHEO_TLS"),
+    ("log_level", "RHEO_LOG_LEVEL"),
+    ("log_format", "RHEO_LOG_FORMAT"),
+    ("log_ship_to", "RHEO_LOG_SHIP_TO"),
+    ("journal_dir", "RHEO_JOURNAL_DIR"),
+    ("journal_max_bytes", "RHEO_JOURNAL_MAX_BYTES"),
+    ("journal_max_files", "RHEO_JOURNAL_MAX_FILES"),
+];
+
+impl CellConfig {
+    /// Defaults, then `./Cell.toml` (or `RHEO_CONFIG`) if present, then the environment.
+    /// Override individual fields in code with `CellConfig { .., ..CellConfig::load()? }`.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::default().apply_sources()
+    }
 
-        let id = if config.id.is_empty() {
-            format!("cell_{}", &pub_key_hex[..16])
+    /// Layer `./Cell.toml` (or `RHEO_CONFIG`) if present, then the environment,
+    /// over this config - fields neither sets keep their value
+    pub fn apply_sources(self) -> Result<Self, ConfigError> {
+        let path = std::env::var("RHEO_CONFIG")
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("Cell.toml"));
+        let config = if path.exists() {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| ConfigError::new(path.display().to_string(), e.to_string()))?;
+            self.apply_toml(&text)?
         } else {
-            config.id.clone()
+            self
         };
+        config.apply_env()
+    }
 
-        let cell = Arc::new(Self {
-            id: id.clone(),
-            addr: Arc::new(TokioRwLock::new(String::new())),
-            port: config.port,
-            config: config.clone(),
-            signing_key,
-            verifying_key,
-            pub_key_hex,
-            atlas: Arc::new(DashMap::new()),
-            handlers: Arc::new(DashMap::new()),
-            circuits: Arc::new(DashMap::new()),
-            seen_nonces: Arc::new(DashMap::new()),
-            active_executions: Arc::new(DashMap::new()),
-            result_cache: Arc::new(DashMap::new()),
-            metrics: Arc::new(Metrics::default()),
-            shutdown_tx: None,
+    /// Defaults, then the environment
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::default().apply_env()
+    }
+
+    /// Defaults, then the given Cell.toml
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::new(path.display().to_string(), e.to_string()))?;
+        Self::default().apply_toml(&text)
+    }
+
+    /// Layer a Cell.toml document over this config. Orchestrator keys
+    /// (`command`, `[env]`) are ignored; unknown `[mesh]` keys are errors.
+    pub fn apply_toml(mut self, text: &str) -> Result<Self, ConfigError> {
+        let doc: toml::Table = text
+            .parse()
+            .map_err(|e: toml::de::Error| ConfigError::new("Cell.toml", e.message().to_string()))?;
+
+        for key in ["id", "critical", "scalable"] {
+            if let Some(value) = doc.get(key) {
+                self.set(key, key, &toml_scalar(key, value)?)?;
+            }
+        }
+
+        match doc.get("mesh") {
+            None => {}
+            Some(toml::Value::Table(mesh)) => {
+                for (key, value) in mesh {
+                    let name = format!("mesh.{}", key);
+                    if !CONFIG_KEYS.iter().any(|(field, _)| field == key) {
+                        return Err(ConfigError::new(name, "unknown key"));
+                    }
+                    self.set(key, &name, &toml_scalar(&name, value)?)?;
+                }
+            }
+            Some(_) => return Err(ConfigError::new("mesh", "expected a table")),
+        }
+        Ok(self)
+    }
+
+    /// Layer `RHEO_*` environment variables over this config
+    pub fn apply_env(self) -> Result<Self, ConfigError> {
+        self.apply_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Layer variables from `lookup` (by `RHEO_*` name); empty values count as unset
+    pub fn apply_vars(
+        mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let get = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+
+        // The orchestrator passes RHEO_CELL_ID; an explicit RHEO_ID wins
+        for name in ["RHEO_CELL_ID", "RHEO_ID"] {
+            if let Some(value) = get(name) {
+                self.set("id", name, &value)?;
+            }
+        }
+        for (field, name) in CONFIG_KEYS {
+            if let Some(value) = get(name) {
+                self.set(field, name, &value)?;
+            }
+        }
+        Ok(self)
+    }
+
+    fn set(&mut self, field: &str, key: &str, raw: &str) -> Result<(), ConfigError> {
+        let raw = raw.trim();
+        let invalid =
+            |expected: &str| ConfigError::new(key, format!("expected {}, got {:?}", expected, raw));
+        let number = |min: u64| {
+            raw.parse::<u64>()
+                .ok()
+                .filter(|n| *n >= min)
+                .ok_or_else(|| {
+                    invalid(if min > 0 {
+                        "a positive integer"
+                    } else {
+                        "an integer"
+                    })
+                })
+        };
+        let flag = || match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(true),
+            "false" | "0" | "no" | "off" => Ok(false),
+            _ => Err(invalid("true or false")),
+        };
+
+        match field {
+            "id" if raw.is_empty() => return Err(invalid("a non-empty id")),
+            "id" => self.id = raw.to_string(),
+            "critical" => self.critical = flag()?,
+            "scalable" => self.scalable = flag()?,
+            "port" => self.port = raw.parse().map_err(|_| invalid("a port number"))?,
+            "bind_host" => self.bind_host = raw.parse().map_err(|_| invalid("an IP address"))?,
+            "seed" if !(raw.starts_with("http://") || raw.starts_with("https://")) => {
+                return Err(invalid("an http(s) URL"))
+            }
+            "seed" => self.seed = Some(raw.to_string()),
+            "registry_dir" => self.registry_dir = Some(raw.to_string()),
+            "manifest_dir" => self.manifest_dir = Some(raw.to_string()),
+            "ghost_cleanup" => self.ghost_cleanup = flag()?,
+            "handle_signals" => self.handle_signals = flag()?,
+            "drain_timeout_ms" => self.drain_timeout_ms = number(0)?,
+            "result_cache_ttl_ms" => self.result_cache_ttl_ms = number(0)?,
+            "ws_heartbeat_ms" => self.ws_heartbeat_ms = number(1)?,
+            "narrative_events" => self.narrative_events = flag()?,
+            "admin_token" => self.admin_token = Some(raw.to_string()),
+            "admin_keys" => {
+                let keys: Vec<String> = raw
+                    .split(',')
+                    .map(|k| k.trim().to_ascii_lowercase())
+                    .filter(|k| !k.is_empty())
+                    .collect();
+                if !keys
+                    .iter()
+                    .all(|k| k.len() == 64 && k.chars().all(|c| c.is_ascii_hexdigit()))
+                {
+                    return Err(invalid("comma-separated hex ed25519 public keys"));
+                }
+                self.admin_keys = keys;
+            }
+            "policy_file" => self.policy_file = Some(raw.to_string()),
+            "identity_key" if !(raw.len() == 64 && raw.chars().all(|c| c.is_ascii_hexdigit())) => {
+                return Err(invalid("a hex ed25519 secret key"))
+            }
+            "identity_key" => self.identity_key = Some(raw.to_ascii_lowercase()),
+            "max_concurrent" => self.max_concurrent = number(1)? as usize,
+            "max_batch" => self.max_batch = number(1)? as usize,
+            // `ewma, trading/*=round_robin`: a default and/or per-capability rules
+            "load_balancer" => {
+                let expected = "a strategy and/or `capability=strategy` rules";
+                self.load_balancers.clear();
+                for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                    match part.split_once('=') {
+                        Some((pattern, strategy)) => self.load_balancers.push((
+                            pattern.trim().to_string(),
+                            strategy.trim().parse().map_err(|_| invalid(expected))?,
+                        )),
+                        None => self.load_balancer = part.parse().map_err(|_| invalid(expected))?,
+                    }
+                }
+            }
+            "hedge" => {
+                self.hedge = raw
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(String::from)
+                    .collect()
+            }
+            "hedge_percentile" => match number(1)? {
+                p if p < 100 => self.hedge_percentile = p,
+                _ => return Err(invalid("a percentile from 1 to 99")),
+            },
+            "rpc_timeout_ms" => self.rpc_timeout_ms = number(1)?,
+            "gossip_interval_ms" => self.gossip_interval_ms = number(1)?,
+            "atlas_ttl_ms" => self.atlas_ttl_ms = number(1)?,
+            "enable_compression" => self.enable_compression = flag()?,
+            "enable_tls" => self.enable_tls = flag()?,
+            "log_level" => {
+                self.log_level = raw
+                    .parse()
+                    .map_err(|_| invalid("trace, debug, info, warn or error"))?
+            }
+            "log_format" => {
+                self.log_format = match raw.to_ascii_lowercase().as_str() {
+                    "text" => LogFormat::Text,
+                    "json" => LogFormat::Json,
+                    _ => return Err(invalid("text or json")),
+                }
+            }
+            "log_ship_to" => self.log_ship_to = Some(raw.to_string()),
+            "journal_dir" => self.journal_dir = Some(raw.to_string()),
+            "journal_max_bytes" => self.journal_max_bytes = number(1)?,
+            "journal_max_files" => self.journal_max_files = number(1)? as usize,
+            _ => return Err(ConfigError::new(key, "unknown key")),
+        }
+        Ok(())
+    }
+}
+
+fn toml_scalar(key: &str, value: &toml::Value) -> Result<String, ConfigError> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        toml::Value::Float(f) => Ok(f.to_string()),
+        _ => Err(ConfigError::new(
+            key,
+            "expected a string, number or boolean",
+        )),
+    }
+}
+
+/// Written to `.rheo/manifests/<id>.cell.json` while a cell is listening (matches core.ts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellManifest {
+    pub pid: u32,
+    pub version: String,
+    pub port: u16,
+    pub start_time: u64,
+    pub capabilities: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<String>,
+    pub pub_key: String,
+}
+
+/// When `describe_mesh` last ran, and what it found
+type MeshDescription = (Instant, Vec<tools::CapabilityDescriptor>);
+
+/// The core distributed cell - sovereign compute node
+pub struct RheoCell {
+    pub id: String,
+    pub addr: Arc<TokioRwLock<String>>,
+    pub port: u16,
+    pub config: CellConfig,
+
+    // Cryptographic identity
+    signing_key: SigningKey,
+    pub verifying_key: VerifyingKey,
+    pub pub_key_hex: String,
+    started_at: u64,
+
+    // State
+    atlas: Arc<DashMap<String, AtlasEntry>>,
+    /// Which atlas entry each address belongs to, so per-call updates lock one shard
+    atlas_addrs: Arc<DashMap<String, String>>,
+    /// Hash ring per sharded capability and version, rebuilt when its providers change
+    shard_rings: Arc<DashMap<String, Arc<shard::Ring>>>,
+    handlers: Arc<DashMap<String, BoxedHandler>>,
+    procedures: Arc<DashMap<String, router::ProcedureSpec>>,
+    ledger: Arc<narrative::NarrativeLedger>,
+    journal: Option<Arc<journal::Journal>>,
+    log_filter: Arc<std::sync::OnceLock<logging::FilterHandle>>,
+    span_export: Arc<std::sync::OnceLock<telemetry::SpanExport>>,
+    admin: Arc<RwLock<admin::Credentials>>,
+    policy: Arc<RwLock<policy::Policy>>,
+    circuits: Arc<DashMap<String, CircuitBreaker>>,
+    balancing: Arc<balance::Balancing>,
+    events: broadcast::Sender<ws::Event>,
+
+    // Request deduplication: duplicates of a signal join its execution while
+    // it runs, then replay its result - if their args digest matches
+    active_executions: Arc<DashMap<DedupKey, ActiveExecution>>,
+    result_cache: Arc<DashMap<DedupKey, (TraceResult, String, Instant)>>,
+
+    // Last `describe_mesh` for unauthenticated readers (see `describe_mesh_cached`)
+    mesh_description: Arc<Mutex<Option<MeshDescription>>>,
+
+    // Metrics
+    metrics: Arc<Metrics>,
+
+    // Lifecycle
+    cancel: CancellationToken, // Shutdown requested - stops the server and background tasks
+    stopped: CancellationToken, // Shutdown complete
+    ready: Arc<watch::Sender<bool>>,
+    is_shutting_down: Arc<AtomicU64>, // 0 = running, 1 = shutting down, 2 = shut down
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+#[derive(Default)]
+struct Metrics {
+    requests_total: AtomicU64,
+    requests_success: AtomicU64,
+    requests_failed: AtomicU64,
+    latency_sum_micros: AtomicU64,
+}
+
+/// What duplicate arrivals share: signal id, sender and capability. The id
+/// alone is chosen by the sender, so anyone who learned it could collect the
+/// result.
+type DedupKey = (String, String, String);
+
+/// An execution in progress: its args digest and where its result will appear
+type ActiveExecution = (String, watch::Receiver<Option<TraceResult>>);
+
+fn dedup_key(signal: &Signal) -> DedupKey {
+    (
+        signal.id.clone(),
+        signal.from.clone(),
+        signal.payload.capability.clone(),
+    )
+}
+
+/// Hex sha256 of a signal's args, compared before a result is replayed
+fn args_digest(signal: &Signal) -> String {
+    hex::encode(Sha256::digest(signal.payload.args.to_string().as_bytes()))
+}
+
+/// A signal's entry in `active_executions`, removed when the execution ends -
+/// even if it is abandoned, so waiting duplicates can take it over
+struct ExecutionSlot {
+    key: DedupKey,
+    active: Arc<DashMap<DedupKey, ActiveExecution>>,
+    tx: watch::Sender<Option<TraceResult>>,
+}
+
+impl ExecutionSlot {
+    /// Hand `result` to every duplicate waiting on this execution
+    fn finish(self, result: &TraceResult) {
+        self.tx.send_replace(Some(result.clone()));
+    }
+}
+
+impl Drop for ExecutionSlot {
+    fn drop(&mut self) {
+        self.active.remove(&self.key);
+    }
+}
+
+impl RheoCell {
+    /// Create a new cell with the given configuration
+    pub fn new(config: CellConfig) -> Arc<Self> {
+        let signing_key = config
+            .identity_key
+            .as_deref()
+            .and_then(|key| hex::decode(key).ok())
+            .and_then(|key| <[u8; 32]>::try_from(key).ok())
+            .map(|key| SigningKey::from_bytes(&key))
+            .unwrap_or_else(|| SigningKey::generate(&mut OsRng));
+        let verifying_key = VerifyingKey::from(&signing_key);
+        let pub_key_hex = hex::encode(verifying_key.to_bytes());
+
+        let id = if config.id.is_empty() {
+            format!("cell_{}", &pub_key_hex[..16])
+        } else {
+            config.id.clone()
+        };
+
+        let journal = config.journal_dir.as_ref().and_then(|dir| {
+            journal::Journal::open(dir, &id, config.journal_max_bytes, config.journal_max_files)
+                .map(Arc::new)
+                .map_err(|e| warn!(error = %e, dir = %dir, "Signal journal disabled"))
+                .ok()
+        });
+
+        let policy = policy::Policy::load(config.policy_file.as_deref()).unwrap_or_else(|e| {
+            error!(error = %e, "Policy failed to load - denying every capability");
+            policy::Policy::deny_all()
+        });
+
+        let cell = Arc::new(Self {
+            id: id.clone(),
+            addr: Arc::new(TokioRwLock::new(String::new())),
+            port: config.port,
+            config: config.clone(),
+            signing_key,
+            verifying_key,
+            pub_key_hex,
+            started_at: now_millis(),
+            atlas: Arc::new(DashMap::new()),
+            atlas_addrs: Arc::new(DashMap::new()),
+            shard_rings: Arc::new(DashMap::new()),
+            handlers: Arc::new(DashMap::new()),
+            procedures: Arc::new(DashMap::new()),
+            ledger: Arc::new(narrative::NarrativeLedger::new()),
+            journal,
+            log_filter: Arc::new(std::sync::OnceLock::new()),
+            span_export: Arc::new(std::sync::OnceLock::new()),
+            admin: Arc::new(RwLock::new(admin::Credentials::from_config(&config))),
+            policy: Arc::new(RwLock::new(policy)),
+            circuits: Arc::new(DashMap::new()),
+            balancing: Arc::new(balance::Balancing::from_config(&config)),
+            events: broadcast::channel(1024).0,
+            active_executions: Arc::new(DashMap::new()),
+            result_cache: Arc::new(DashMap::new()),
+            mesh_description: Arc::new(Mutex::new(None)),
+            metrics: Arc::new(Metrics::default()),
+            cancel: CancellationToken::new(),
+            stopped: CancellationToken::new(),
+            ready: Arc::new(watch::channel(false).0),
             is_shutting_down: Arc::new(AtomicU64::new(0)),
             tasks: Arc::new(Mutex::new(Vec::new())),
         });
@@ -789,6 +1888,16 @@ impl RheoCell {
             }),
         );
 
+        let cell = Arc::clone(self);
+        self.handlers.insert(
+            "mesh/describe".to_string(),
+            Box::new(move |_args, signal| {
+                let cell = Arc::clone(&cell);
+                let signal_id = signal.id.clone();
+                Box::pin(async move { TraceResult::success(signal_id, cell.describe_local()) })
+            }),
+        );
+
         let cell = Arc::clone(self);
         self.handlers.insert(
             "mesh/gossip".to_string(),
@@ -813,6 +1922,7 @@ impl RheoCell {
                     if !incoming_atlas.is_empty() {
                         cell.merge_atlas(incoming_atlas, true);
                     }
+                    cell.refresh_self_entry().await;
 
                     // Return our atlas in TypeScript-compatible format
                     let our_atlas: HashMap<String, AtlasEntry> = cell
@@ -873,12 +1983,187 @@ impl RheoCell {
                 })
             }),
         );
+
+        let cell = Arc::clone(self);
+        self.handlers.insert(
+            "cell/set_log_level".to_string(),
+            Box::new(move |args, signal| {
+                let cell = Arc::clone(&cell);
+                let signal_id = signal.id.clone();
+                Box::pin(async move {
+                    let level = args
+                        .get("level")
+                        .and_then(|l| l.as_str())
+                        .and_then(|l| l.parse::<Level>().ok());
+                    let Some(level) = level else {
+                        return TraceResult::failure(
+                            signal_id,
+                            MeshError::new(
+                                ErrorCode::ValidationFailed,
+                                "Expected { level: trace | debug | info | warn | error }",
+                                &cell.id,
+                            ),
+                        );
+                    };
+                    if !cell.set_log_level(level) {
+                        return TraceResult::failure(
+                            signal_id,
+                            MeshError::new(
+                                ErrorCode::HandlerError,
+                                "Logging was not initialized by this cell",
+                                &cell.id,
+                            ),
+                        );
+                    }
+                    TraceResult::success(
+                        signal_id,
+                        serde_json::json!({ "level": level.to_string() }),
+                    )
+                })
+            }),
+        );
+
+        // Only admin credentials, the policy and the log level take effect without a
+        // restart. Cell.toml and the environment are layered over the config the cell
+        // was built with, so credentials given in code survive unless overridden.
+        let cell = Arc::clone(self);
+        self.handlers.insert(
+            "cell/reload_config".to_string(),
+            Box::new(move |_args, signal| {
+                let cell = Arc::clone(&cell);
+                let signal_id = signal.id.clone();
+                Box::pin(async move {
+                    let config = match cell.config.clone().apply_sources() {
+                        Ok(config) => config,
+                        Err(e) => {
+                            return TraceResult::failure(
+                                signal_id,
+                                MeshError::new(
+                                    ErrorCode::ValidationFailed,
+                                    e.to_string(),
+                                    &cell.id,
+                                ),
+                            )
+                        }
+                    };
+                    if let Err(e) = cell.reload_policy() {
+                        return TraceResult::failure(
+                            signal_id,
+                            MeshError::new(ErrorCode::ValidationFailed, e.to_string(), &cell.id),
+                        );
+                    }
+                    let rules = cell.policy.read().unwrap().rules.len();
+                    let mut admin = cell.admin.write().unwrap();
+                    *admin = admin::Credentials::from_config(&config).remembering(&admin);
+                    drop(admin);
+                    let log_level = cell
+                        .set_log_level(config.log_level)
+                        .then(|| config.log_level.to_string());
+                    info!(cell_id = %cell.id, "Reloaded config");
+                    TraceResult::success(
+                        signal_id,
+                        serde_json::json!({
+                            "adminToken": config.admin_token.is_some(),
+                            "adminKeys": config.admin_keys.len(),
+                            "policyRules": rules,
+                            "logLevel": log_level,
+                        }),
+                    )
+                })
+            }),
+        );
+
+        let cell = Arc::clone(self);
+        self.handlers.insert(
+            "cell/evict_peer".to_string(),
+            Box::new(move |args, signal| {
+                let cell = Arc::clone(&cell);
+                let signal_id = signal.id.clone();
+                Box::pin(async move {
+                    let Some(id) = args.get("id").and_then(|v| v.as_str()) else {
+                        return TraceResult::failure(
+                            signal_id,
+                            MeshError::new(
+                                ErrorCode::ValidationFailed,
+                                "Expected { id }",
+                                &cell.id,
+                            ),
+                        );
+                    };
+                    if id == cell.id {
+                        return TraceResult::failure(
+                            signal_id,
+                            MeshError::new(
+                                ErrorCode::ValidationFailed,
+                                "A cell cannot evict itself",
+                                &cell.id,
+                            ),
+                        );
+                    }
+                    // Tombstoned rather than removed, so stale gossip can't bring it straight back
+                    let evicted = match cell.atlas.get_mut(id) {
+                        Some(mut entry) => {
+                            entry.leaving = true;
+                            entry.last_seen = now_millis();
+                            cell.circuits.remove(&entry.addr);
+                            true
+                        }
+                        None => false,
+                    };
+                    if evicted {
+                        warn!(cell_id = %cell.id, peer = %id, "Evicted peer");
+                    }
+                    TraceResult::success(
+                        signal_id,
+                        serde_json::json!({ "id": id, "evicted": evicted }),
+                    )
+                })
+            }),
+        );
+
+        let cell = Arc::clone(self);
+        self.handlers.insert(
+            "cell/contract".to_string(),
+            Box::new(move |args, signal| {
+                let cell = Arc::clone(&cell);
+                let signal_id = signal.id.clone();
+                Box::pin(async move {
+                    let contract = |spec: &router::ProcedureSpec| {
+                        serde_json::json!({
+                            "input": spec.input_schema,
+                            "output": spec.output_schema,
+                            "meta": {
+                                "type": if spec.is_mutation { "mutation" } else { "query" },
+                                "version": spec.version,
+                                "compatibility": spec.compatibility,
+                            },
+                        })
+                    };
+
+                    // { cap } for one capability (TS router shape), otherwise all of them
+                    let value = match args.get("cap").and_then(|c| c.as_str()) {
+                        Some(cap) => cell
+                            .procedures
+                            .get(cap)
+                            .map(|spec| contract(&spec))
+                            .unwrap_or(Value::Null),
+                        None => Value::Object(
+                            cell.procedures
+                                .iter()
+                                .map(|e| (e.key().clone(), contract(e.value())))
+                                .collect(),
+                        ),
+                    };
+                    TraceResult::success(signal_id, value)
+                })
+            }),
+        );
     }
 
     /// Register a capability handler - FIXED: Added Clone bound
     pub fn provide<F, I, O>(&self, capability: impl Into<String>, handler: F)
     where
-        F: Fn(I, Signal) -> futures::future::BoxFuture<'static, Result<O, MeshError>>
+        F: Fn(I, Context) -> futures::future::BoxFuture<'static, Result<O, MeshError>>
             + Send
             + Sync
             + Clone
@@ -887,9 +2172,9 @@ impl RheoCell {
         O: Serialize + Send + 'static,
     {
         let cap = capability.into();
-        let boxed: BoxedHandler = Box::new(move |args, signal| {
+        let boxed: BoxedHandler = Box::new(move |args, ctx| {
             let handler = handler.clone(); // Clone the handler
-            let signal_id = signal.id.clone(); // Clone signal.id before moving
+            let signal_id = ctx.id.clone(); // Clone signal.id before moving
             Box::pin(async move {
                 let input: I = match serde_json::from_value(args) {
                     Ok(i) => i,
@@ -905,7 +2190,7 @@ impl RheoCell {
                     }
                 };
 
-                match handler(input, signal).await {
+                match handler(input, ctx).await {
                     Ok(output) => TraceResult::success(signal_id, output),
                     Err(e) => TraceResult::failure(signal_id, e),
                 }
@@ -916,40 +2201,357 @@ impl RheoCell {
         debug!(cell_id = %self.id, "Registered capability");
     }
 
-    /// Start the cell and begin listening
-    pub async fn listen(self: Arc<Self>) -> Result<SocketAddr, std::io::Error> {
-        // Try to bind to the configured port, or find an available one
-        let listener = if self.port == 0 {
-            TcpListener::bind("0.0.0.0:0").await?
-        } else {
-            match TcpListener::bind(format!("0.0.0.0:{}", self.port)).await {
-                Ok(l) => l,
-                Err(_) => {
-                    warn!(port = self.port, "Port in use, finding alternative");
-                    TcpListener::bind("0.0.0.0:0").await?
-                }
-            }
-        };
+    /// Register every procedure of a typed router, publishing its schemas
+    pub fn use_router(&self, router: router::Router) {
+        let (handlers, specs) = router.into_parts();
+        for (cap, spec) in specs {
+            self.procedures.insert(cap, spec);
+        }
+        for (cap, handler) in handlers {
+            self.handlers.insert(cap, handler);
+        }
+        debug!(cell_id = %self.id, "Registered router");
+    }
 
-        let addr = listener.local_addr()?;
-        let port = addr.port();
-        let addr_str = format!("http://127.0.0.1:{}", port);
+    /// Append a signed, hash-chained step for this cell
+    fn sign_step(&self, signal: &mut Signal, action: &str) {
+        signal.record_signed_step(&self.id, action, &self.signing_key);
+    }
 
+    /// `signal`'s steps sealed by this cell
+    fn sealed_steps(&self, signal: &Signal) -> Vec<NarrativeStep> {
+        signal.sealed_steps(&self.id, &self.signing_key)
+    }
+
+    /// Walk a step chain, checking signatures against atlas public keys
+    pub fn verify_steps(&self, steps: &[NarrativeStep]) -> narrative::StepVerification {
+        narrative::verify_steps(steps, |cell| {
+            if cell == self.id {
+                return Some(self.pub_key_hex.clone());
+            }
+            self.atlas
+                .get(cell)
+                .map(|e| e.pub_key.clone())
+                .filter(|k| !k.is_empty())
+        })
+    }
+
+    /// This cell's shard of the narrative ledger
+    pub fn ledger(&self) -> &narrative::NarrativeLedger {
+        &self.ledger
+    }
+
+    /// Published contract for a locally provided capability
+    pub fn procedure_spec(&self, capability: &str) -> Option<router::ProcedureSpec> {
+        self.procedures.get(capability).map(|s| s.clone())
+    }
+
+    /// Register a handler under a versioned contract (mirrors `provideContract` in core.ts)
+    pub fn provide_contract<F, I, O>(&self, contract: Contract, handler: F)
+    where
+        F: Fn(I, Context) -> futures::future::BoxFuture<'static, Result<O, MeshError>>
+            + Send
+            + Sync
+            + Clone
+            + 'static,
+        I: DeserializeOwned + Send + 'static,
+        O: Serialize + Send + 'static,
+    {
+        // Enforce the published schemas, as a router procedure would
+        let mut procedure = if contract.query {
+            router::Procedure::query(handler)
+        } else {
+            router::Procedure::mutation(handler)
+        };
+        let schema = |side: &str, schema: &Option<Value>| {
+            let compiled = router::ContractSchema::compile(schema.clone()?);
+            if let Err(e) = &compiled {
+                warn!(capability = %contract.capability, side, error = %e, "Contract schema not enforced");
+            }
+            compiled.ok()
+        };
+        if let Some(input) = schema("input", &contract.input_schema) {
+            procedure = procedure.with_input_schema(input);
+        }
+        if let Some(output) = schema("output", &contract.output_schema) {
+            procedure = procedure.with_output_schema(output);
+        }
+        self.procedures.insert(
+            contract.capability.clone(),
+            router::ProcedureSpec::from(&contract),
+        );
+        self.handlers
+            .insert(contract.capability, procedure.into_boxed());
+    }
+
+    /// Attach a description to a capability registered with `provide`
+    pub fn describe(&self, capability: impl Into<String>, description: impl Into<String>) {
+        self.procedures
+            .entry(capability.into())
+            .or_insert_with(|| router::ProcedureSpec {
+                // Plain handlers declare nothing, so assume side effects
+                is_mutation: true,
+                ..Default::default()
+            })
+            .description = Some(description.into());
+    }
+
+    /// Route a capability registered with `provide` by `args.<field>`
+    pub fn shard_by(&self, capability: impl Into<String>, field: impl Into<String>) {
+        self.procedures
+            .entry(capability.into())
+            .or_insert_with(|| router::ProcedureSpec {
+                is_mutation: true,
+                ..Default::default()
+            })
+            .shard_key = Some(field.into());
+    }
+
+    /// The hash ring over the providers of a sharded capability in our view of
+    /// the atlas, reused until they change
+    fn shard_ring_for(&self, capability: &str, version_req: Option<&str>) -> Arc<shard::Ring> {
+        let mut members: Vec<String> = self
+            .atlas
+            .iter()
+            .filter(|e| {
+                e.value().serves(capability, version_req)
+                    && !e.value().addr.starts_with("client://")
+            })
+            .filter_map(|e| e.value().id.clone())
+            .collect();
+        members.sort();
+        let key = format!("{}@{}", capability, version_req.unwrap_or("*"));
+        if let Some(ring) = self.shard_rings.get(&key) {
+            if ring.members() == members {
+                return Arc::clone(&ring);
+            }
+        }
+        let ring = Arc::new(shard::Ring::new(members));
+        self.shard_rings.insert(key, Arc::clone(&ring));
+        ring
+    }
+
+    /// Providers of a sharded capability, starting with the one owning `key`
+    /// in our view of the atlas
+    fn shard_successors(
+        &self,
+        capability: &str,
+        version_req: Option<&str>,
+        key: &str,
+    ) -> Vec<String> {
+        self.shard_ring_for(capability, version_req).successors(key)
+    }
+
+    /// The shard key of a signal, if its capability declares one
+    fn shard_key_of(&self, signal: &Signal) -> Option<String> {
+        let cap = &signal.payload.capability;
+        let field = self
+            .procedures
+            .get(cap)
+            .and_then(|s| s.shard_key.clone())
+            .or_else(|| {
+                self.atlas.iter().find_map(|e| {
+                    e.value()
+                        .cap_info
+                        .get(cap)
+                        .and_then(|i| i.shard_key.clone())
+                })
+            })?;
+        shard::key_of(&signal.payload.args, &field)
+    }
+
+    fn owns_shard_for(&self, capability: &str, version_req: Option<&str>, key: &str) -> bool {
+        self.shard_ring_for(capability, version_req)
+            .is_owner(&self.id, key)
+    }
+
+    /// Whether calls with this shard key land here - e.g. to mint ids this
+    /// instance will keep receiving. True while we are the only provider.
+    pub fn owns_shard(&self, capability: &str, key: &str) -> bool {
+        self.owns_shard_for(capability, None, key)
+    }
+
+    /// The ring calls to `capability` are sharded on, for testing many keys
+    /// against one view of the atlas (see [`Self::owns_shard`])
+    pub fn shard_ring(&self, capability: &str) -> Arc<shard::Ring> {
+        self.shard_ring_for(capability, None)
+    }
+
+    /// Describe every locally provided capability (what `mesh/describe` returns)
+    pub fn describe_local(&self) -> Vec<tools::CapabilityDescriptor> {
+        let mut descriptors: Vec<tools::CapabilityDescriptor> = self
+            .handlers
+            .iter()
+            .map(|e| {
+                let spec = self.procedures.get(e.key()).map(|s| s.clone());
+                tools::CapabilityDescriptor::new(e.key().clone(), spec, &self.id)
+            })
+            .collect();
+        descriptors.sort_by(|a, b| a.capability.cmp(&b.capability));
+        descriptors
+    }
+
+    /// Aggregate `mesh/describe` across the atlas. Peers that don't implement it
+    /// (older TS cells) still contribute their capability names.
+    pub async fn describe_mesh(self: &Arc<Self>) -> Vec<tools::CapabilityDescriptor> {
+        let peers: Vec<AtlasEntry> = self
+            .atlas
+            .iter()
+            .filter(|e| {
+                e.key() != &self.id
+                    && !e.value().addr.starts_with("client://")
+                    && !e.value().leaving
+            })
+            .map(|e| e.value().clone())
+            .collect();
+
+        let lookups = peers.into_iter().map(|peer| {
+            let cell = Arc::clone(self);
+            async move {
+                let signal = Signal::new(&cell.id, "mesh/describe", ());
+                let result = cell.rpc(&peer.addr, signal).await;
+                let provider = peer.id.clone().unwrap_or_else(|| peer.addr.clone());
+                match result.into_value::<Vec<tools::CapabilityDescriptor>>() {
+                    Ok(descriptors) => descriptors,
+                    Err(_) => peer
+                        .caps
+                        .iter()
+                        .map(|cap| tools::CapabilityDescriptor::new(cap.clone(), None, &provider))
+                        .collect(),
+                }
+            }
+        });
+
+        let mut merged: HashMap<String, tools::CapabilityDescriptor> = HashMap::new();
+        for descriptor in self
+            .describe_local()
+            .into_iter()
+            .chain(join_all(lookups).await.into_iter().flatten())
         {
-            let mut addr_lock = self.addr.write().await;
-            *addr_lock = addr_str.clone();
+            match merged.get(&descriptor.capability) {
+                // Prefer the richest description of a capability
+                Some(existing) if existing.input_schema.is_some() => {}
+                _ => {
+                    merged.insert(descriptor.capability.clone(), descriptor);
+                }
+            }
         }
 
-        info!(cell_id = %self.id, addr = %addr_str, "🟢 Rheo Cell online");
+        let mut descriptors: Vec<_> = merged.into_values().collect();
+        descriptors.sort_by(|a, b| a.capability.cmp(&b.capability));
+        descriptors
+    }
 
-        // Add self to atlas
-        let self_entry = AtlasEntry::new(
+    /// [`RheoCell::describe_mesh`] for unauthenticated endpoints: at most one
+    /// fan-out per [`DESCRIBE_CACHE_TTL`], however many readers ask meanwhile
+    pub async fn describe_mesh_cached(self: &Arc<Self>) -> Vec<tools::CapabilityDescriptor> {
+        let mut cached = self.mesh_description.lock().await;
+        if let Some((at, descriptors)) = cached.as_ref() {
+            if at.elapsed() < DESCRIBE_CACHE_TTL {
+                return descriptors.clone();
+            }
+        }
+        let descriptors = self.describe_mesh().await;
+        *cached = Some((Instant::now(), descriptors.clone()));
+        descriptors
+    }
+
+    /// Versions and shard keys advertised for locally provided capabilities
+    fn capability_info(&self) -> HashMap<String, CapInfo> {
+        self.procedures
+            .iter()
+            .filter(|e| {
+                let spec = e.value();
+                spec.version.is_some() || spec.shard_key.is_some() || !spec.is_mutation
+            })
+            .map(|e| {
+                let info = CapInfo {
+                    versions: e.value().versions(),
+                    shard_key: e.value().shard_key.clone(),
+                    query: !e.value().is_mutation,
+                };
+                (e.key().clone(), info)
+            })
+            .collect()
+    }
+
+    /// Rebuild our own atlas entry from the live handler registry
+    async fn refresh_self_entry(&self) {
+        let addr = self.addr.read().await.clone();
+        if addr.is_empty() {
+            return;
+        }
+        let mut entry = AtlasEntry::new(
             self.id.clone(),
-            addr_str.clone(),
+            addr,
             self.handlers.iter().map(|e| e.key().clone()).collect(),
         )
         .with_pub_key(self.pub_key_hex.clone());
-        self.atlas.insert(self.id.clone(), self_entry);
+        entry.cap_info = self.capability_info();
+        entry.max_batch = Some(self.config.max_batch);
+        // Keep advertising the tombstone while draining
+        entry.leaving = self.is_shutting_down.load(Ordering::SeqCst) > 0;
+        self.atlas.insert(self.id.clone(), entry);
+    }
+
+    /// Start the cell and begin listening
+    pub async fn listen(self: Arc<Self>) -> Result<CellHandle, std::io::Error> {
+        let previous = self.read_manifest();
+        if self.config.ghost_cleanup {
+            if let Some(ghost) = &previous {
+                self.kill_ghost(ghost).await;
+            }
+        }
+
+        // Come back on the port we had if we restarted quickly, so peers' atlases stay valid
+        let port = match &previous {
+            Some(m)
+                if self.port == 0
+                    && self
+                        .manifest_age()
+                        .is_some_and(|age| age < Duration::from_secs(30))
+                    && std::env::var("RHEO_FORCE_RANDOM_PORT").as_deref() != Ok("true") =>
+            {
+                m.port
+            }
+            _ => self.port,
+        };
+
+        // Try to bind to the configured port, or find an available one
+        let host = self.config.bind_host;
+        let listener = if port == 0 {
+            TcpListener::bind((host, 0)).await?
+        } else {
+            match TcpListener::bind((host, port)).await {
+                Ok(l) => l,
+                Err(_) => {
+                    warn!(port = port, "Port in use, finding alternative");
+                    TcpListener::bind((host, 0)).await?
+                }
+            }
+        };
+
+        let addr = listener.local_addr()?;
+        let port = addr.port();
+        let advertised = if host.is_unspecified() {
+            IpAddr::from([127, 0, 0, 1])
+        } else {
+            host
+        };
+        let addr_str = format!("http://{}", SocketAddr::new(advertised, port));
+
+        {
+            let mut addr_lock = self.addr.write().await;
+            *addr_lock = addr_str.clone();
+        }
+
+        info!(cell_id = %self.id, addr = %addr_str, "🟢 Rheo Cell online");
+
+        self.ledger.register_cell(&self.id, &addr_str);
+
+        // Add self to atlas
+        self.refresh_self_entry().await;
+        self.save_manifest(port).await;
 
         // Start background tasks
         self.start_background_tasks().await;
@@ -957,16 +2559,9 @@ impl RheoCell {
         // Build and serve the HTTP router
         let app = self.build_router();
 
-        // Store server handle for graceful shutdown
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
-        unsafe {
-            // This is safe because we control the Arc lifetime
-            let cell_ptr = Arc::into_raw(self.clone()) as *mut RheoCell;
-            (*cell_ptr).shutdown_tx = Some(shutdown_tx);
-        }
-
+        let cancel = self.cancel.clone();
         let server = axum::serve(listener, app).with_graceful_shutdown(async move {
-            let _ = shutdown_rx.recv().await;
+            cancel.cancelled().await;
             info!("Received shutdown signal, stopping server");
         });
 
@@ -978,7 +2573,20 @@ impl RheoCell {
 
         self.tasks.lock().await.push(server_handle);
 
-        Ok(addr)
+        if self.config.handle_signals {
+            let cell = Arc::clone(&self);
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = cell.cancel.cancelled() => {}
+                    signal = shutdown_signal() => {
+                        info!(cell_id = %cell.id, signal, "Received signal, shutting down");
+                        cell.shutdown().await;
+                    }
+                }
+            });
+        }
+
+        Ok(CellHandle { cell: self, addr })
     }
 
     fn build_router(self: &Arc<Self>) -> Router {
@@ -988,6 +2596,8 @@ impl RheoCell {
             .route("/", post(handle_signal))
             .route("/atlas", get(handle_atlas).post(handle_atlas)) // <-- CHANGED: added .get()
             .route("/health", get(handle_health))
+            .route("/openapi.json", get(handle_openapi))
+            .route("/ws", get(handle_ws))
             .with_state(cell);
 
         if self.config.enable_compression {
@@ -1007,11 +2617,10 @@ impl RheoCell {
         let gossip_handle = tokio::spawn(async move {
             let mut interval = interval(Duration::from_millis(cell.config.gossip_interval_ms));
             loop {
-                interval.tick().await;
-                if cell.is_shutting_down.load(Ordering::SeqCst) > 0 {
-                    break;
+                tokio::select! {
+                    _ = cell.cancel.cancelled() => break,
+                    _ = interval.tick() => cell.gossip().await,
                 }
-                cell.gossip().await;
             }
         });
         self.tasks.lock().await.push(gossip_handle);
@@ -1021,11 +2630,10 @@ impl RheoCell {
         let cleanup_handle = tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(30));
             loop {
-                interval.tick().await;
-                if cell.is_shutting_down.load(Ordering::SeqCst) > 0 {
-                    break;
+                tokio::select! {
+                    _ = cell.cancel.cancelled() => break,
+                    _ = interval.tick() => cell.cleanup().await,
                 }
-                cell.cleanup().await;
             }
         });
         self.tasks.lock().await.push(cleanup_handle);
@@ -1034,18 +2642,37 @@ impl RheoCell {
         if let Some(seed) = &self.config.seed {
             let cell = Arc::clone(self);
             let seed = seed.clone();
-            tokio::spawn(async move {
-                sleep(Duration::from_millis(100)).await;
-                cell.bootstrap_from_seed(&seed).await;
+            let bootstrap_handle = tokio::spawn(async move {
+                tokio::select! {
+                    _ = cell.cancel.cancelled() => {}
+                    _ = async {
+                        sleep(Duration::from_millis(100)).await;
+                        cell.bootstrap_from_seed(&seed).await;
+                    } => {}
+                }
+                cell.ready.send_replace(true);
             });
+            self.tasks.lock().await.push(bootstrap_handle);
+        } else {
+            self.ready.send_replace(true);
         }
     }
 
     async fn gossip(&self) {
+        // Capabilities registered after listen() must reach the atlas too
+        self.refresh_self_entry().await;
+        if let Some(port) = self.listening_port().await {
+            self.save_manifest(port).await;
+        }
+
         let peers: Vec<AtlasEntry> = self
             .atlas
             .iter()
-            .filter(|e| e.key() != &self.id && !e.value().addr.starts_with("client://"))
+            .filter(|e| {
+                e.key() != &self.id
+                    && !e.value().addr.starts_with("client://")
+                    && !e.value().leaving
+            })
             .map(|e| e.value().clone())
             .collect();
 
@@ -1069,15 +2696,59 @@ impl RheoCell {
         let targets: Vec<_> = peers
             .into_iter()
             .filter(|e| e.id.as_ref() != Some(&self.id)) // Compare Option with Some(String)
-            .take(2)
+            .choose_multiple(&mut rand::thread_rng(), 2);
+
+        // Awaited, so shutdown cancels them with the gossip task
+        let cell = Arc::new(self.clone());
+        join_all(
+            targets
+                .iter()
+                .map(|peer| cell.rpc(&peer.addr, signal.clone())),
+        )
+        .await;
+    }
+
+    /// Gossip our tombstone to every peer so they stop routing to us
+    async fn announce_departure(self: &Arc<Self>) {
+        let Some(mut entry) = self.atlas.get(&self.id).map(|e| e.value().clone()) else {
+            return;
+        };
+        entry.leaving = true;
+        entry.last_seen = now_millis();
+        self.atlas.insert(self.id.clone(), entry.clone());
+
+        let peers: Vec<String> = self
+            .atlas
+            .iter()
+            .filter(|e| {
+                e.key() != &self.id
+                    && !e.value().addr.starts_with("client://")
+                    && !e.value().leaving
+            })
+            .map(|e| e.value().addr.clone())
             .collect();
+        let args = serde_json::json!({ "atlas": { self.id.clone(): entry } });
+        let announcements = peers.iter().map(|addr| {
+            let signal = Signal::new(&self.id, "mesh/gossip", &args);
+            timeout(Duration::from_secs(1), self.rpc_raw(addr, signal))
+        });
+        join_all(announcements).await;
+        debug!(cell_id = %self.id, peers = peers.len(), "Announced departure");
+    }
 
-        for peer in targets {
-            let cell = Arc::new(self.clone());
-            let signal = signal.clone();
-            tokio::spawn(async move {
-                let _ = cell.rpc(&peer.addr, signal).await;
-            });
+    /// Wait for in-flight executions to finish, up to `drain_timeout_ms`
+    async fn drain(&self) {
+        let deadline = Instant::now() + Duration::from_millis(self.config.drain_timeout_ms);
+        while !self.active_executions.is_empty() {
+            if Instant::now() >= deadline {
+                warn!(
+                    cell_id = %self.id,
+                    in_flight = self.active_executions.len(),
+                    "Drain timed out, abandoning in-flight executions"
+                );
+                return;
+            }
+            sleep(Duration::from_millis(20)).await;
         }
     }
 
@@ -1098,16 +2769,19 @@ impl RheoCell {
             .collect();
 
         for id in to_remove {
-            self.atlas.remove(&id);
+            if let Some((_, entry)) = self.atlas.remove(&id) {
+                self.atlas_addrs
+                    .remove_if(&entry.addr, |_, owner| *owner == id);
+            }
         }
 
-        // Clean old nonces
-        self.seen_nonces
-            .retain(|_, v| now.duration_since(*v) < Duration::from_secs(60));
+        // Forensics are only needed shortly after a failure
+        self.ledger.prune(Duration::from_secs(300));
 
         // Clean old cache entries
+        let cache_ttl = Duration::from_millis(self.config.result_cache_ttl_ms);
         self.result_cache
-            .retain(|_, (r, t)| r.ok || now.duration_since(*t) < Duration::from_secs(10));
+            .retain(|_, (_, _, t)| now.duration_since(*t) < cache_ttl);
     }
 
     async fn bootstrap_from_seed(&self, seed: &str) {
@@ -1195,6 +2869,217 @@ impl RheoCell {
 
     /// The core routing logic
     pub async fn route(self: &Arc<Self>, mut signal: Signal) -> TraceResult {
+        let start = Instant::now();
+        // Calls are authorized where they run, before anything records the signal
+        let denied = self
+            .authorize_admin(&mut signal)
+            .or_else(|| self.check_policy(&signal));
+        // Gossip and health chatter would drown out the traffic worth replaying
+        let journal = self
+            .journal
+            .as_ref()
+            .filter(|_| !signal.payload.capability.starts_with("mesh/"))
+            .map(|journal| (journal, signal.clone()));
+
+        // Every log line while routing carries the signal it belongs to
+        let span = if signal.payload.capability == "mesh/gossip" {
+            tracing::error_span!(
+                "mesh.route",
+                cid = %signal.id,
+                capability = %signal.payload.capability
+            )
+        } else {
+            telemetry::hop_span(&mut signal, telemetry::Hop::Route, &self.id)
+        };
+        let capability = signal.payload.capability.clone();
+        let result = match denied {
+            Some(denied) => denied,
+            None => self.route_signal(signal).instrument(span.clone()).await,
+        };
+        telemetry::record_result(&span, &result);
+        self.publish_narrative(&capability, &result);
+
+        if let Some((journal, arrived)) = journal {
+            journal.record(&self.id, &arrived, &result, start.elapsed());
+        }
+        result
+    }
+
+    /// Route a signal that arrived over the network. One claiming to come
+    /// from this cell must carry our origin proof - we'd otherwise vouch for it.
+    pub async fn route_inbound(self: &Arc<Self>, signal: Signal) -> TraceResult {
+        if signal.from == self.id && !policy::verify_origin(&signal, &self.pub_key_hex) {
+            warn!(cell_id = %self.id, capability = %signal.payload.capability, "Rejected inbound signal claiming our id");
+            return TraceResult::failure(
+                signal.id.clone(),
+                MeshError::new(
+                    ErrorCode::Unauthorized,
+                    format!(
+                        "Signal claims to come from {} without its origin proof",
+                        self.id
+                    ),
+                    &self.id,
+                ),
+            );
+        }
+        self.route(signal).await
+    }
+
+    /// Push an event to every `/ws` connection subscribed to `topic`; returns
+    /// how many connections were listening
+    pub fn publish(&self, topic: impl Into<String>, data: impl Serialize) -> usize {
+        let event = ws::Event {
+            topic: topic.into(),
+            data: serde_json::to_value(data).unwrap_or_default(),
+            timestamp: now_millis(),
+        };
+        self.events.send(event).unwrap_or(0)
+    }
+
+    /// Receive everything `publish` sends (what `/ws` connections filter by topic)
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ws::Event> {
+        self.events.subscribe()
+    }
+
+    /// `narrative/<cid>` for each routed signal, while anyone is listening and
+    /// `narrative_events` is on
+    fn publish_narrative(&self, capability: &str, result: &TraceResult) {
+        if !self.config.narrative_events
+            || self.events.receiver_count() == 0
+            || capability.starts_with("mesh/")
+        {
+            return;
+        }
+        let update = serde_json::json!({
+            "cid": result.cid,
+            "capability": capability,
+            "ok": result.ok,
+            "latencyMicros": result.latency_micros,
+            "envelope": self.ledger.get(&result.cid),
+        });
+        self.publish(format!("narrative/{}", result.cid), update);
+    }
+
+    /// Check and strip the admin proofs of a locally handled admin capability,
+    /// recording the decision in the signal's narrative. Returns the failure
+    /// to answer with when the call is denied.
+    fn authorize_admin(&self, signal: &mut Signal) -> Option<TraceResult> {
+        let cap = signal.payload.capability.clone();
+        if !admin::is_admin(&cap) || !self.handlers.contains_key(&cap) {
+            return None;
+        }
+        let verdict = self.admin.read().unwrap().authorize(signal, &self.id);
+        admin::redact(signal);
+
+        match verdict {
+            Ok(principal) => {
+                info!(cell_id = %self.id, capability = %cap, principal = %principal, "Admin call authorized");
+                self.sign_step(signal, &format!("ADMIN_AUTHORIZED:{}", principal));
+                let reason =
+                    serde_json::json!({ "capability": cap, "principal": principal.to_string() })
+                        .to_string();
+                self.ledger
+                    .wrap(signal, &self.id, "ADMIN_AUTHORIZED", Some(&reason));
+                None
+            }
+            Err(message) => {
+                warn!(cell_id = %self.id, capability = %cap, from = %signal.from, reason = %message, "Admin call denied");
+                self.sign_step(signal, "ADMIN_DENIED");
+                let reason = serde_json::json!({
+                    "capability": cap,
+                    "from": signal.from,
+                    "reason": message,
+                })
+                .to_string();
+                let envelope = self
+                    .ledger
+                    .wrap(signal, &self.id, "ADMIN_DENIED", Some(&reason));
+                Some(TraceResult::failure(
+                    signal.id.clone(),
+                    MeshError::new(
+                        ErrorCode::Unauthorized,
+                        format!("{} requires admin authorization: {}", cap, message),
+                        &self.id,
+                    )
+                    .with_history(self.sealed_steps(signal))
+                    .with_envelope(envelope),
+                ))
+            }
+        }
+    }
+
+    /// Sign signals we originate, once. Only for signals built here - inbound
+    /// ones go through [`RheoCell::route_inbound`] first.
+    fn sign_origin(&self, signal: &mut Signal) {
+        if signal.from == self.id && !signal.proofs.contains_key(policy::ORIGIN_PROOF) {
+            signal.sign_origin(&self.signing_key);
+        }
+    }
+
+    /// `signal.from`, if its fresh origin proof checks out against the key the
+    /// policy pins for it. Atlas keys arrive by unauthenticated gossip, so they
+    /// never vouch for a caller.
+    pub fn verified_caller(&self, signal: &Signal) -> Option<String> {
+        let pinned = self
+            .policy
+            .read()
+            .unwrap()
+            .identities
+            .get(&signal.from)
+            .cloned();
+        let key = pinned.or_else(|| (signal.from == self.id).then(|| self.pub_key_hex.clone()))?;
+        policy::verify_origin(signal, &key).then(|| signal.from.clone())
+    }
+
+    /// Replace the access control policy
+    pub fn set_policy(&self, policy: policy::Policy) {
+        info!(cell_id = %self.id, rules = policy.rules.len(), "Policy updated");
+        *self.policy.write().unwrap() = policy;
+    }
+
+    /// Re-read `policy_file`, keeping the current policy if it doesn't load -
+    /// or if there is no file, as when the policy was set in code
+    pub fn reload_policy(&self) -> Result<(), ConfigError> {
+        if let Some(path) = self.config.policy_file.as_deref() {
+            self.set_policy(policy::Policy::load(Some(path))?);
+        }
+        Ok(())
+    }
+
+    /// Evaluate the policy for a locally handled capability. Returns the
+    /// failure to answer with when a rule denies the call.
+    fn check_policy(&self, signal: &Signal) -> Option<TraceResult> {
+        let cap = &signal.payload.capability;
+        if !self.handlers.contains_key(cap) {
+            return None;
+        }
+        let caller = self.verified_caller(signal);
+        let policy = self.policy.read().unwrap();
+        let rule = policy.denies(cap, caller.as_deref())?;
+
+        warn!(cell_id = %self.id, capability = %cap, from = %signal.from, verified = caller.is_some(), rule = %rule.name, "Policy denied call");
+        let details = serde_json::json!({
+            "rule": rule,
+            "caller": signal.from,
+            "verified": caller.is_some(),
+        });
+        let reason = details.to_string();
+        let envelope = self
+            .ledger
+            .wrap(signal, &self.id, "POLICY_DENIED", Some(&reason));
+        Some(TraceResult::failure(
+            signal.id.clone(),
+            MeshError::new(
+                ErrorCode::Unauthorized,
+                format!("{} denied by policy rule '{}'", cap, rule.name),
+                &self.id,
+            )
+            .with_details(details)
+            .with_envelope(envelope),
+        ))
+    }
+
+    async fn route_signal(self: &Arc<Self>, mut signal: Signal) -> TraceResult {
         let start = Instant::now();
         let _cid = signal.id.clone();
 
@@ -1208,67 +3093,122 @@ impl RheoCell {
         }
 
         // Check for shutdown
-        if self.is_shutting_down.load(Ordering::SeqCst) > 0 {
+        // While draining, only our own in-flight work may still route through us
+        let state = self.is_shutting_down.load(Ordering::SeqCst);
+        if state > 1 || (state == 1 && signal.from != self.id) {
             return TraceResult::failure(
                 signal.id.clone(),
                 MeshError::new(ErrorCode::NotReady, "Cell is shutting down", &self.id),
             );
         }
 
-        // Deduplication check
-        if self.seen_nonces.contains_key(&signal.id) {
-            return TraceResult::success(
-                signal.id.clone(),
-                serde_json::json!({"_meshStatus": "DUPLICATE_ARRIVAL"}),
-            );
-        }
-        self.seen_nonces.insert(signal.id.clone(), Instant::now());
-
-        // Loop prevention
+        // Loop prevention - before deduplication, or a signal that came back
+        // around would wait on its own execution
         if signal.visited_cell_ids.contains(&self.id) {
+            let envelope = self.ledger.wrap(
+                &signal,
+                &self.id,
+                "LOOP_DETECTED",
+                Some("Signal already visited this cell"),
+            );
             return TraceResult::failure(
                 signal.id.clone(),
                 MeshError::new(ErrorCode::LoopDetected, "Signal loop detected", &self.id)
                     .with_trace(signal.trace.clone())
-                    .with_history(signal.steps.clone()),
+                    .with_history(self.sealed_steps(&signal))
+                    .with_envelope(envelope),
             );
         }
 
+        // Deduplication: a retried or flooded signal gets the original result.
+        // The same id, sender and capability with other args is not a retry.
+        let key = dedup_key(&signal);
+        let digest = args_digest(&signal);
+        let cache_ttl = Duration::from_millis(self.config.result_cache_ttl_ms);
+        // Mesh answers are fresh state, not the outcome of an operation
+        let cacheable = !cache_ttl.is_zero() && !signal.payload.capability.starts_with("mesh/");
+        let conflict = || {
+            warn!(cid = %signal.id, from = %signal.from, "Signal id reused with different args");
+            TraceResult::failure(
+                signal.id.clone(),
+                MeshError::new(
+                    ErrorCode::Conflict,
+                    format!("Signal {} was already sent with different args", signal.id),
+                    &self.id,
+                ),
+            )
+        };
+        let execution = loop {
+            let live = |cached: &(TraceResult, String, Instant)| cached.2.elapsed() < cache_ttl;
+            if let Some(cached) = self.result_cache.get(&key).filter(|c| live(c)) {
+                if cached.1 != digest {
+                    return conflict();
+                }
+                debug!(cid = %signal.id, "Duplicate arrival, replaying cached result");
+                return cached.0.clone();
+            }
+            let mut running = match self.active_executions.entry(key.clone()) {
+                dashmap::mapref::entry::Entry::Vacant(slot) => {
+                    let (tx, rx) = watch::channel(None);
+                    slot.insert((digest.clone(), rx));
+                    break ExecutionSlot {
+                        key,
+                        active: Arc::clone(&self.active_executions),
+                        tx,
+                    };
+                }
+                dashmap::mapref::entry::Entry::Occupied(slot) if slot.get().0 != digest => {
+                    return conflict();
+                }
+                dashmap::mapref::entry::Entry::Occupied(slot) => slot.get().1.clone(),
+            };
+            debug!(cid = %signal.id, "Duplicate arrival, joining in-flight execution");
+            let joined = running
+                .wait_for(Option::is_some)
+                .await
+                .ok()
+                .and_then(|result| result.clone());
+            if let Some(result) = joined {
+                return result;
+            }
+            // The original execution was abandoned - take it over
+        };
+
         // Record narrative
-        signal.record_step(&self.id, "RECEIVED");
+        self.sign_step(&mut signal, "RECEIVED");
         signal.mark_visited(&self.id, &*self.addr.read().await);
         signal.hops += 1;
         signal.trace.push(format!("{}:{}", self.id, now_millis()));
-
-        // Request joining - check if already executing
-        let execution_key = format!("{}:{}", signal.id, signal.payload.capability);
-        if let Some(existing) = self.active_executions.get(&execution_key) {
-            let guard = existing.lock().await;
-            if let Some(result) = guard.as_ref() {
-                return result.clone();
-            }
-        }
-
-        // Create execution slot
-        let execution_slot = Arc::new(tokio::sync::Mutex::new(None));
-        self.active_executions
-            .insert(execution_key.clone(), Arc::clone(&execution_slot));
+        let reason = serde_json::json!({ "capability": signal.payload.capability }).to_string();
+        self.ledger
+            .wrap(&signal, &self.id, "RECEIVED_SIGNAL", Some(&reason));
 
         // Execute
-        let result = self.execute(signal).await;
+        let mut result = self.execute(signal).await;
 
-        // Store result
-        {
-            let mut guard = execution_slot.lock().await;
-            *guard = Some(result.clone());
+        // Failures carry our narrative, already merged with any remote envelope
+        if let Some(error) = result.error.as_mut() {
+            if let Some(envelope) = self.ledger.get(&result.cid) {
+                error.envelope = Some(Box::new(envelope));
+            }
+            if let Some(history) = &error.history {
+                error.integrity = Some(self.verify_steps(history));
+            }
         }
-        self.active_executions.remove(&execution_key);
 
-        // Cache successful results briefly
-        if result.ok {
-            self.result_cache
-                .insert(result.cid.clone(), (result.clone(), Instant::now()));
+        // Remember the outcome for later duplicates, unless the call never got
+        // anywhere or timed out and a retry deserves another chance
+        let retryable = result
+            .error
+            .as_ref()
+            .is_some_and(|e| e.code.is_routing_failure() || e.code == ErrorCode::Timeout);
+        if cacheable && !retryable {
+            self.result_cache.insert(
+                execution.key.clone(),
+                (result.clone(), digest, Instant::now()),
+            );
         }
+        execution.finish(&result);
 
         // Update metrics
         self.metrics.requests_total.fetch_add(1, Ordering::SeqCst);
@@ -1288,11 +3228,72 @@ impl RheoCell {
         let cap = &signal.payload.capability;
         let _cid = signal.id.clone();
 
+        let version_req = signal.payload.version.as_deref();
+        if let Some(req) = version_req {
+            if semver::VersionReq::parse(req).is_err() {
+                return TraceResult::failure(
+                    signal.id.clone(),
+                    MeshError::new(
+                        ErrorCode::ValidationFailed,
+                        format!("Invalid version requirement '{}' for {}", req, cap),
+                        &self.id,
+                    ),
+                );
+            }
+        }
+        let local_versions = self
+            .procedures
+            .get(cap)
+            .map(|s| s.versions())
+            .unwrap_or_default();
+
+        // Sharded calls run on the owner - or wherever a peer's ring sent them,
+        // so differing atlas views can't bounce a call back and forth
+        let sharded_elsewhere = self.shard_key_of(&signal).is_some_and(|key| {
+            let routed_here = format!("SHARD_ROUTE:{}", self.id);
+            !signal.steps.iter().any(|s| s.action == routed_here)
+                && !self.owns_shard_for(cap, version_req, &key)
+        });
+
         // Check local handlers
-        if let Some(handler) = self.handlers.get(cap) {
-            signal.record_step(&self.id, "LOCAL_HANDLER");
+        if let Some(handler) = self
+            .handlers
+            .get(cap)
+            .filter(|_| versions_satisfy(version_req, &local_versions) && !sharded_elsewhere)
+        {
+            self.sign_step(&mut signal, "LOCAL_HANDLER");
+            let reason = serde_json::json!({ "capability": signal.payload.capability }).to_string();
+            self.ledger
+                .wrap(&signal, &self.id, "LOCAL_HANDLER", Some(&reason));
+            let snapshot = signal.clone();
+            let started = now_millis();
+
             let args = signal.payload.args.clone();
-            let result = handler(args, signal).await;
+            let ctx = Context::new(Arc::clone(self), signal);
+            let children = Arc::clone(&ctx.children);
+            let mut result = handler(args, ctx).await;
+
+            self.ledger.record_timing(
+                &snapshot.id,
+                "LOCAL_HANDLER",
+                &self.id,
+                started,
+                now_millis(),
+                None,
+            );
+
+            // Attach the handler's call tree to its failure
+            if let Some(error) = result.error.as_mut() {
+                if error.children.is_empty() {
+                    error.children = children.lock().unwrap().clone();
+                }
+                if error.history.is_none() {
+                    error.history = Some(self.sealed_steps(&snapshot));
+                }
+                let reason = serde_json::json!({ "error": error.message }).to_string();
+                self.ledger
+                    .wrap(&snapshot, &self.id, "HANDLER_EXCEPTION", Some(&reason));
+            }
             return result;
         }
 
@@ -1301,36 +3302,64 @@ impl RheoCell {
     }
 
     async fn forward_to_peer(self: &Arc<Self>, mut signal: Signal) -> TraceResult {
+        let span = telemetry::hop_span(&mut signal, telemetry::Hop::Forward, &self.id);
+        let result = self
+            .forward_via_peers(signal)
+            .instrument(span.clone())
+            .await;
+        telemetry::record_result(&span, &result);
+        result
+    }
+
+    async fn forward_via_peers(self: &Arc<Self>, mut signal: Signal) -> TraceResult {
         let cap = signal.payload.capability.clone();
+        let version_req = signal.payload.version.clone();
         let cid = signal.id.clone();
         let my_addr = self.addr.read().await.clone();
+        let (providers, shard_order) = self.select_providers(&signal, &my_addr);
+        let mut tried = 0;
+        if let Some(delay) = self.hedge_delay(&cap, &providers, shard_order.is_some()) {
+            self.sign_step(&mut signal, "P2P_ROUTE");
+            let (result, attempted) = self
+                .hedged_rpc(&providers[0], &providers[1], &signal, delay)
+                .await;
+            if result.ok
+                || result
+                    .error
+                    .as_ref()
+                    .map(|e| e.code == ErrorCode::LoopDetected || !e.code.is_routing_failure())
+                    .unwrap_or(false)
+            {
+                return result;
+            }
+            for provider in &providers[..attempted] {
+                self.circuits
+                    .entry(provider.addr.clone())
+                    .or_insert_with(|| CircuitBreaker::new(3, 30000))
+                    .record_failure();
+            }
+            tried = attempted;
+        }
+        for (i, provider) in providers.iter().enumerate().take(3).skip(tried) {
+            self.sign_step(
+                &mut signal,
+                if i == 0 { "P2P_ROUTE" } else { "P2P_FAILOVER" },
+            );
+            if let (Some(_), Some(id)) = (&shard_order, &provider.id) {
+                self.sign_step(&mut signal, &format!("SHARD_ROUTE:{}", id));
+            }
 
-        // Find providers - FIXED: Use &String for contains
-        let providers: Vec<AtlasEntry> = self
-            .atlas
-            .iter()
-            .filter(|e| {
-                let entry = e.value();
-                entry.caps.contains(&cap) &&
-                entry.addr != my_addr &&
-                // Check Option<String> against Vec<String>
-                entry.id.as_ref().map_or(true, |id| !signal.visited_cell_ids.contains(id)) &&
-                !entry.addr.starts_with("client://")
-            })
-            .map(|e| e.value().clone())
-            .collect();
-
-        // Try direct routing first
-        for (i, provider) in providers.iter().take(3).enumerate() {
-            signal.record_step(&self.id, if i == 0 { "P2P_ROUTE" } else { "P2P_FAILOVER" });
-
+            let started = Instant::now();
             let result = self.rpc(&provider.addr, signal.clone()).await;
+            if result.ok {
+                self.balancing.record_call(&cap, started.elapsed());
+            }
 
             if result.ok
                 || result
                     .error
                     .as_ref()
-                    .map(|e| e.code == ErrorCode::LoopDetected)
+                    .map(|e| e.code == ErrorCode::LoopDetected || !e.code.is_routing_failure())
                     .unwrap_or(false)
             {
                 return result;
@@ -1355,7 +3384,8 @@ impl RheoCell {
                     entry.addr != my_addr &&
                     // Check Option<String> against Vec<String>
                     entry.id.as_ref().map_or(true, |id| !signal.visited_cell_ids.contains(id)) &&
-                    !providers.iter().any(|p| p.id == entry.id)
+                    !providers.iter().any(|p| p.id == entry.id) &&
+                    !entry.leaving
                 })
                 .map(|e| e.value().clone())
                 .take(2)
@@ -1382,12 +3412,29 @@ impl RheoCell {
         // Try seed as last resort
         if let Some(seed) = &self.config.seed {
             if !signal.visited_addrs.contains(seed) {
-                signal.record_step(&self.id, "SEED_FALLBACK");
+                self.sign_step(&mut signal, "SEED_FALLBACK");
                 return self.rpc(seed, signal).await;
             }
         }
 
         // Not found
+        let reason = serde_json::json!({ "capability": cap, "atlasSize": self.atlas.len() });
+        self.ledger.wrap(
+            &signal,
+            &self.id,
+            "P2P_NO_ROUTE_FAILURE",
+            Some(&reason.to_string()),
+        );
+        let requested = match &version_req {
+            Some(req) => format!("{}@{}", cap, req),
+            None => cap.clone(),
+        };
+        let available_versions: Vec<String> = self
+            .atlas
+            .iter()
+            .filter_map(|e| e.value().cap_info.get(&cap).map(|i| i.versions.clone()))
+            .flatten()
+            .collect();
         let atlas_count = self.atlas.len();
         let known_caps: Vec<String> = self
             .atlas
@@ -1402,17 +3449,22 @@ impl RheoCell {
                 ErrorCode::NotFound,
                 format!(
             "Routing Failed: Cap '{}' not found. Atlas has {} peers. Known caps nearby: {:?}",
-            cap, atlas_count, known_caps
+            requested, atlas_count, known_caps
         ),
                 &self.id,
             )
-            .with_trace(signal.trace)
-            .with_history(signal.steps),
+            .with_details(serde_json::json!({
+                "capability": cap,
+                "versionRequirement": version_req,
+                "availableVersions": available_versions,
+            }))
+            .with_history(self.sealed_steps(&signal))
+            .with_trace(signal.trace),
         )
     }
 
     /// RPC to another cell
-    pub async fn rpc(self: &Arc<Self>, addr: &str, signal: Signal) -> TraceResult {
+    pub async fn rpc(self: &Arc<Self>, addr: &str, mut signal: Signal) -> TraceResult {
         // Check circuit breaker
         if let Some(circuit) = self.circuits.get(addr) {
             if circuit.is_open() {
@@ -1423,8 +3475,53 @@ impl RheoCell {
             }
         }
 
+        // Gossip would only churn the ledger
+        let narrate = signal.payload.capability != "mesh/gossip";
+        let cid = signal.id.clone();
+        let started = now_millis();
+        if narrate {
+            let reason = serde_json::json!({ "target": addr }).to_string();
+            self.ledger
+                .wrap(&signal, &self.id, "RPC_ATTEMPT", Some(&reason));
+        }
+
+        let span = if narrate {
+            telemetry::hop_span(&mut signal, telemetry::Hop::Rpc, &self.id)
+        } else {
+            tracing::Span::none()
+        };
+        span.record("peer", addr);
+
+        // Mesh chatter would make every peer look fast
+        let in_flight = (!signal.payload.capability.starts_with("mesh/"))
+            .then(|| self.balancing.stats.begin(addr));
         let start = Instant::now();
-        let result = self.rpc_raw(addr, signal).await;
+        let result = self.rpc_raw(addr, signal).instrument(span.clone()).await;
+        telemetry::record_result(&span, &result);
+        if let Some(in_flight) = in_flight {
+            // A provider that can't answer is as slow as the timeout
+            let routing_failure = result
+                .error
+                .as_ref()
+                .is_some_and(|e| e.code.is_routing_failure());
+            let sample = if routing_failure {
+                start
+                    .elapsed()
+                    .max(Duration::from_millis(self.config.rpc_timeout_ms))
+            } else {
+                start.elapsed()
+            };
+            in_flight.finish(sample);
+            self.note_latency(addr);
+        }
+
+        if narrate {
+            self.ledger
+                .record_timing(&cid, "RPC", &self.id, started, now_millis(), Some(addr));
+            if let Some(envelope) = result.error.as_ref().and_then(|e| e.envelope.as_ref()) {
+                self.ledger.merge(envelope);
+            }
+        }
 
         // Update circuit breaker
         if let Some(circuit) = self.circuits.get(addr) {
@@ -1438,8 +3535,132 @@ impl RheoCell {
         result.with_latency(start.elapsed())
     }
 
-    async fn rpc_raw(&self, addr: &str, signal: Signal) -> TraceResult {
+    /// Remote providers for `signal`, best first: the shard owner and its ring
+    /// successors (also returned), or else as the load balancer ranks them
+    fn select_providers(
+        &self,
+        signal: &Signal,
+        my_addr: &str,
+    ) -> (Vec<AtlasEntry>, Option<Vec<String>>) {
+        let cap = &signal.payload.capability;
+        let version_req = signal.payload.version.as_deref();
+
+        // Find providers - FIXED: Use &String for contains
+        let mut providers: Vec<AtlasEntry> = self
+            .atlas
+            .iter()
+            .filter(|e| {
+                let entry = e.value();
+                entry.serves(cap, version_req) &&
+                entry.addr != my_addr &&
+                // Check Option<String> against Vec<String>
+                entry.id.as_ref().map_or(true, |id| !signal.visited_cell_ids.contains(id)) &&
+                !entry.addr.starts_with("client://")
+            })
+            .map(|e| e.value().clone())
+            .collect();
+
+        // Try direct routing first: the shard owner and its ring successors,
+        // or else the best providers by load
+        let shard_order = self
+            .shard_key_of(signal)
+            .map(|key| self.shard_successors(cap, version_req, &key));
+        match &shard_order {
+            Some(order) => {
+                providers.retain(|p| p.id.as_ref().is_some_and(|id| order.contains(id)));
+                providers.sort_by_key(|p| order.iter().position(|id| Some(id) == p.id.as_ref()));
+            }
+            None => self.balancing.rank(cap, &mut providers, &self.circuits),
+        }
+        (providers, shard_order)
+    }
+
+    /// When to hedge a call: the configured percentile of recent latencies, if
+    /// the capability opted in and both leading providers declare it a query
+    fn hedge_delay(&self, cap: &str, providers: &[AtlasEntry], sharded: bool) -> Option<Duration> {
+        if sharded || providers.len() < 2 || !self.config.hedge.iter().any(|p| policy::glob(p, cap))
+        {
+            return None;
+        }
+        // A duplicate mutation would run twice
+        let queries = providers[..2]
+            .iter()
+            .all(|p| p.cap_info.get(cap).is_some_and(|i| i.query));
+        if !queries {
+            return None;
+        }
+        self.balancing
+            .call_percentile(cap, self.config.hedge_percentile)
+    }
+
+    /// Ask `primary`, and `backup` too if `primary` hasn't answered after
+    /// `delay`. The first success wins and the other call is dropped.
+    /// Returns the result and how many providers were asked.
+    async fn hedged_rpc(
+        self: &Arc<Self>,
+        primary: &AtlasEntry,
+        backup: &AtlasEntry,
+        signal: &Signal,
+        delay: Duration,
+    ) -> (TraceResult, usize) {
+        let cap = &signal.payload.capability;
+        let started = Instant::now();
+        let first = self.rpc(&primary.addr, signal.clone());
+        tokio::pin!(first);
+        if let Ok(result) = timeout(delay, &mut first).await {
+            if result.ok {
+                self.balancing.record_call(cap, started.elapsed());
+            }
+            return (result, 1);
+        }
+
+        debug!(capability = %cap, primary = %primary.addr, backup = %backup.addr, delay_ms = delay.as_millis() as u64, "Hedging slow call");
+        let mut hedge = signal.clone();
+        self.sign_step(&mut hedge, "P2P_HEDGE");
+        let second = self.rpc(&backup.addr, hedge);
+        tokio::pin!(second);
+
+        let result = tokio::select! {
+            result = &mut first => if result.ok { result } else { second.await },
+            result = &mut second => if result.ok { result } else { first.await },
+        };
+        // A lower bound on the primary's latency when the hedge won
+        if result.ok {
+            self.balancing.record_call(cap, started.elapsed());
+        }
+        (result, 2)
+    }
+
+    /// Show our latency measurement for `addr` in the atlas
+    fn note_latency(&self, addr: &str) {
+        let Some(latency) = self.balancing.stats.latency(addr) else {
+            return;
+        };
+        // Copy the id out first - holding the index guard while locking the
+        // atlas could deadlock against a merge doing the reverse
+        let Some(id) = self.atlas_addrs.get(addr).map(|id| id.clone()) else {
+            return;
+        };
+        if let Some(mut entry) = self.atlas.get_mut(&id) {
+            if entry.addr == addr {
+                entry.latency_ms = Some(latency.as_millis() as u64);
+            }
+        }
+    }
+
+    /// Use `balancer` for capabilities matching `pattern` (`*` globs), ahead of
+    /// the strategies from config
+    pub fn set_load_balancer(
+        &self,
+        pattern: impl Into<String>,
+        balancer: Arc<dyn balance::LoadBalancer>,
+    ) {
+        self.balancing.set(pattern, balancer);
+    }
+
+    async fn rpc_raw(&self, addr: &str, mut signal: Signal) -> TraceResult {
         let cid = signal.id.clone();
+        self.sign_origin(&mut signal);
 
         let client = match reqwest::Client::builder()
             .timeout(Duration::from_millis(self.config.rpc_timeout_ms))
@@ -1459,7 +3680,12 @@ impl RheoCell {
             }
         };
 
-        let response = match client.post(addr).json(&signal).send().await {
+        let mut request = client.post(addr).json(&signal);
+        if let Some(ctx) = telemetry::TraceContext::from_signal(&signal) {
+            request = request.header(telemetry::TRACEPARENT_HEADER, ctx.to_traceparent());
+        }
+
+        let response = match request.send().await {
             Ok(r) => r,
             Err(e) if e.is_timeout() => {
                 return TraceResult::failure(
@@ -1512,11 +3738,199 @@ impl RheoCell {
         }
     }
 
+    /// Make many calls at once. Calls whose chosen provider is the same remote
+    /// cell share `{"batch": [...]}` round trips of up to its `max_batch`;
+    /// calls handled here, to cells that don't take batches, or whose batch
+    /// can't be delivered, are routed one by one, as are calls a batch
+    /// couldn't route. Results come back in call order.
+    pub async fn ask_batch<C, A>(
+        self: &Arc<Self>,
+        calls: impl IntoIterator<Item = (C, A)>,
+    ) -> Vec<TraceResult>
+    where
+        C: Into<String>,
+        A: Serialize,
+    {
+        let my_addr = self.addr.read().await.clone();
+        let mut singles = Vec::new();
+        let mut batches: HashMap<String, (usize, Vec<(usize, Signal)>)> = HashMap::new();
+        for (i, (capability, args)) in calls.into_iter().enumerate() {
+            let mut signal =
+                Signal::new(&self.id, capability, args).with_deadline(Duration::from_secs(10));
+            signal.sign_origin(&self.signing_key);
+            let cap = &signal.payload.capability;
+            let provider = if self.handlers.contains_key(cap) || cap.starts_with("mesh/") {
+                None
+            } else {
+                self.select_providers(&signal, &my_addr)
+                    .0
+                    .into_iter()
+                    .next()
+            };
+            match provider.and_then(|p| Some((p.max_batch.filter(|n| *n > 0)?, p.addr))) {
+                Some((limit, addr)) => batches
+                    .entry(addr)
+                    .or_insert_with(|| (limit, Vec::new()))
+                    .1
+                    .push((i, signal)),
+                None => singles.push((i, signal)),
+            }
+        }
+
+        let singles = singles
+            .into_iter()
+            .map(|(i, signal)| async move { vec![(i, self.route(signal).await)] });
+        let batches = batches
+            .into_iter()
+            .flat_map(|(addr, (limit, group))| {
+                let chunks: Vec<Vec<_>> = group.chunks(limit).map(<[_]>::to_vec).collect();
+                chunks.into_iter().map(move |chunk| (addr.clone(), chunk))
+            })
+            .map(|(addr, chunk)| async move {
+                let (indices, signals): (Vec<_>, Vec<_>) = chunk.into_iter().unzip();
+                let results = match self.rpc_batch(&addr, signals.clone()).await {
+                    Ok(results) => results.into_iter().map(Some).collect(),
+                    Err(error) => {
+                        debug!(provider = %addr, error = %error, "Batch failed, routing its signals one by one");
+                        vec![None; signals.len()]
+                    }
+                };
+                // What the batch couldn't route gets the failover a single call has
+                let routed = signals.into_iter().zip(results).map(|(signal, result)| async move {
+                    match result {
+                        Some(result)
+                            if result.ok
+                                || !result
+                                    .error
+                                    .as_ref()
+                                    .is_some_and(|e| e.code.is_routing_failure()) =>
+                        {
+                            result
+                        }
+                        _ => self.route(signal).await,
+                    }
+                });
+                indices.into_iter().zip(join_all(routed).await).collect::<Vec<_>>()
+            });
+        let groups = futures::future::join(join_all(singles), join_all(batches)).await;
+
+        let mut results: Vec<(usize, TraceResult)> =
+            groups.0.into_iter().chain(groups.1).flatten().collect();
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Deliver `signals` to `addr` in one request. Only an unreachable peer
+    /// counts against its circuit - one that answers without taking the batch
+    /// (too large, or it doesn't speak batches) is still healthy.
+    async fn rpc_batch(
+        self: &Arc<Self>,
+        addr: &str,
+        mut signals: Vec<Signal>,
+    ) -> Result<Vec<TraceResult>, MeshError> {
+        let circuit_open = self.circuits.get(addr).is_some_and(|c| c.is_open());
+        if circuit_open {
+            return Err(MeshError::new(
+                ErrorCode::CircuitOpen,
+                "Circuit breaker open",
+                addr,
+            ));
+        }
+        for signal in &mut signals {
+            self.sign_origin(signal);
+            self.sign_step(signal, "P2P_ROUTE");
+            if self.shard_key_of(signal).is_some() {
+                if let Some(owner) = self.atlas.iter().find(|e| e.value().addr == addr) {
+                    self.sign_step(signal, &format!("SHARD_ROUTE:{}", owner.key()));
+                }
+            }
+            let reason = serde_json::json!({ "target": addr, "batch": true }).to_string();
+            self.ledger
+                .wrap(signal, &self.id, "RPC_ATTEMPT", Some(&reason));
+        }
+
+        let sent = signals.len();
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(self.config.rpc_timeout_ms))
+            .build()
+            .map_err(|e| {
+                MeshError::new(
+                    ErrorCode::RpcFail,
+                    format!("Client build failed: {}", e),
+                    addr,
+                )
+            })?;
+        let response = match client
+            .post(addr)
+            .json(&serde_json::json!({ "batch": signals }))
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                self.circuits
+                    .entry(addr.to_string())
+                    .or_insert_with(|| CircuitBreaker::new(3, 30000))
+                    .record_failure();
+                let code = if e.is_timeout() {
+                    ErrorCode::RpcTimeout
+                } else {
+                    ErrorCode::RpcUnreachable
+                };
+                return Err(MeshError::new(
+                    code,
+                    format!("Batch RPC failed: {}", e),
+                    addr,
+                ));
+            }
+        };
+
+        let status = response.status();
+        let declined = |message: String| MeshError::new(ErrorCode::RpcFail, message, addr);
+        if status == reqwest::StatusCode::PAYLOAD_TOO_LARGE {
+            return Err(declined(format!(
+                "Batch of {} signals is over the peer's limit",
+                sent
+            )));
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| declined(format!("Batch not taken ({}): {}", status, e)))?;
+        let results: Vec<TraceResult> = body
+            .get("results")
+            .cloned()
+            .and_then(|results| serde_json::from_value(results).ok())
+            .ok_or_else(|| declined(format!("Batch not taken ({})", status)))?;
+        if results.len() != sent {
+            return Err(declined(format!(
+                "{} results for {} signals",
+                results.len(),
+                sent
+            )));
+        }
+        if let Some(circuit) = self.circuits.get(addr) {
+            circuit.record_success();
+        }
+        Ok(results)
+    }
+
     /// Ask mesh with exponential backoff retry
     pub async fn ask_mesh(
         self: &Arc<Self>,
         capability: impl Into<String>,
         args: impl Serialize,
+    ) -> TraceResult {
+        self.ask_mesh_with_proofs(capability, args, HashMap::new())
+            .await
+    }
+
+    /// `ask_mesh` carrying `proofs` (like core.ts `askMesh(cap, args, proofs)`)
+    pub async fn ask_mesh_with_proofs(
+        self: &Arc<Self>,
+        capability: impl Into<String>,
+        args: impl Serialize,
+        proofs: HashMap<String, String>,
     ) -> TraceResult {
         let capability = capability.into();
         let start = Instant::now();
@@ -1524,8 +3938,10 @@ impl RheoCell {
         let mut delay = Duration::from_millis(100);
 
         loop {
-            let signal =
+            let mut signal =
                 Signal::new(&self.id, &capability, &args).with_deadline(Duration::from_secs(10));
+            signal.proofs = proofs.clone();
+            signal.sign_origin(&self.signing_key);
 
             let result = self.route(signal).await;
 
@@ -1559,25 +3975,57 @@ impl RheoCell {
         capability: impl Into<String>,
         args: impl Serialize,
         timeout_ms: u64,
+    ) -> MulticastResult {
+        self.ask_all_with(capability, args, multicast::Options::new(timeout_ms))
+            .await
+    }
+
+    /// Multicast that returns once `options.completion` is satisfied; calls
+    /// still outstanding then are cancelled
+    pub async fn ask_all_with(
+        self: &Arc<Self>,
+        capability: impl Into<String>,
+        args: impl Serialize,
+        options: multicast::Options,
     ) -> MulticastResult {
         let capability = capability.into();
+        let template = Signal::new(&self.id, &capability, args);
+        self.multicast(&capability, template, options).await
+    }
 
+    /// Send `template` to every reachable remote provider of `capability`
+    async fn multicast(
+        self: &Arc<Self>,
+        capability: &str,
+        template: Signal,
+        options: multicast::Options,
+    ) -> MulticastResult {
+        let capability = capability.to_string();
+        let timeout_ms = options.timeout_ms;
+        let my_addr = self.addr.read().await.clone();
         let providers: Vec<AtlasEntry> = self
             .atlas
             .iter()
-            .filter(|e| e.value().caps.contains(&capability))
+            .filter(|e| {
+                let entry = e.value();
+                entry.caps.contains(&capability)
+                    && !entry.leaving
+                    && entry.id.as_deref() != Some(self.id.as_str())
+                    && entry.addr != my_addr
+                    && !entry.addr.starts_with("client://")
+                    && !self.circuits.get(&entry.addr).is_some_and(|c| c.is_open())
+            })
             .map(|e| e.value().clone())
             .collect();
+        let total = providers.len();
 
-        let futures: Vec<_> = providers
+        let mut pending: FuturesUnordered<_> = providers
             .into_iter()
             .map(|provider| {
                 let cell = Arc::clone(self);
-                let capability = capability.clone();
-                let args = &args;
+                let signal = template.clone();
                 async move {
                     let start = Instant::now();
-                    let signal = Signal::new(&cell.id, &capability, args);
                     let provider_id = provider.id.clone(); // Clone before moving
 
                     // FIXED: Handle timeout properly
@@ -1590,6 +4038,18 @@ impl RheoCell {
                         Ok(result) => {
                             let cell_id =
                                 provider_id.clone().unwrap_or_else(|| "unknown".to_string());
+                            // rpc already counted it against an existing circuit
+                            if result
+                                .error
+                                .as_ref()
+                                .is_some_and(|e| e.code.is_routing_failure())
+                            {
+                                if let dashmap::mapref::entry::Entry::Vacant(slot) =
+                                    cell.circuits.entry(provider.addr.clone())
+                                {
+                                    slot.insert(CircuitBreaker::new(3, 30000)).record_failure();
+                                }
+                            }
                             if result.ok {
                                 MulticastItem {
                                     cell_id,
@@ -1625,7 +4085,14 @@ impl RheoCell {
             })
             .collect();
 
-        let results = join_all(futures).await;
+        let mut results = Vec::with_capacity(total);
+        while let Some(item) = pending.next().await {
+            results.push(item);
+            let succeeded = results.iter().filter(|r| r.error.is_none()).count();
+            if options.completion.is_done(succeeded, results.len(), total) {
+                break;
+            }
+        }
 
         MulticastResult {
             results: results
@@ -1662,6 +4129,21 @@ impl RheoCell {
                 continue;
             }
 
+            // Departures and restarts are ordered by last_seen, so stale gossip can
+            // neither resurrect a departed cell nor bury a restarted one.
+            // Copy out of the guard - inserting while holding it deadlocks the shard.
+            let existing = self
+                .atlas
+                .get(&key_id)
+                .map(|e| (e.last_seen, e.leaving, e.latency_ms));
+            if let Some((last_seen, leaving, _)) = existing {
+                if leaving != entry.leaving && entry.last_seen <= last_seen {
+                    continue;
+                }
+            }
+
+            // Latency is what we measured, not what the sender did
+            entry.latency_ms = existing.and_then(|(_, _, latency)| latency);
             entry.last_gossiped = now;
             if via_gossip {
                 entry.gossip_hop_count = std::cmp::min(entry.gossip_hop_count + 1, 3);
@@ -1670,27 +4152,33 @@ impl RheoCell {
                 entry.last_seen = now;
             }
 
-            match self.atlas.get(&key_id) {
-                Some(existing) if entry.last_seen <= existing.last_seen && !via_gossip => {}
+            match existing {
+                Some((last_seen, _, _)) if entry.last_seen <= last_seen && !via_gossip => {}
                 _ => {
+                    self.atlas_addrs.insert(entry.addr.clone(), key_id.clone());
                     self.atlas.insert(key_id, entry);
                 }
             }
         }
     }
 
-    /// Graceful shutdown
+    /// Draining shutdown - from any clone. Announces departure, refuses new
+    /// signals, waits for in-flight executions, then stops. Returns once stopped.
     pub async fn shutdown(&self) {
         if self.is_shutting_down.swap(1, Ordering::SeqCst) > 0 {
-            return; // Already shutting down
+            self.stopped.cancelled().await; // Already shutting down
+            return;
         }
 
         info!(cell_id = %self.id, "Initiating graceful shutdown...");
 
-        // Signal server to stop
-        if let Some(tx) = &self.shutdown_tx {
-            let _ = tx.send(()).await;
-        }
+        // Peers stop routing here; anything already on its way gets NOT_READY and fails over
+        let cell = Arc::new(self.clone());
+        cell.announce_departure().await;
+        self.drain().await;
+
+        // Stop the server and background tasks
+        self.cancel.cancel();
 
         // Wait for tasks with timeout
         let mut tasks = self.tasks.lock().await;
@@ -1703,59 +4191,290 @@ impl RheoCell {
             }
         }
 
+        if let Some(journal) = self.journal.clone() {
+            let _ = tokio::task::spawn_blocking(move || journal.flush()).await;
+        }
+        if let Some(spans) = self.span_export.get().cloned() {
+            let _ = tokio::task::spawn_blocking(move || spans.flush()).await;
+        }
+        self.remove_manifest();
         self.is_shutting_down.store(2, Ordering::SeqCst);
+        self.stopped.cancel();
         info!(cell_id = %self.id, "Shutdown complete");
     }
 
-    /// Create a type-safe mesh proxy
-    pub fn mesh_proxy(self: &Arc<Self>) -> MeshProxy {
-        MeshProxy {
-            cell: Arc::clone(self),
+    /// Install the process-wide log subscriber from this cell's config:
+    /// `log_level` (overridable per target via `RUST_LOG`), `log_format`,
+    /// span export from `RHEO_TRACE_EXPORT`, and shipping to `log_ship_to`.
+    pub fn init_logging(self: &Arc<Self>) -> Result<(), tracing_subscriber::util::TryInitError> {
+        use tracing_subscriber::util::SubscriberInitExt;
+
+        let setup = self.adopt_logging();
+        setup.dispatch.clone().try_init()?;
+        setup.report();
+        Ok(())
+    }
+
+    /// Like [`RheoCell::init_logging`], but only for the current thread and
+    /// until the guard is dropped
+    pub fn scoped_logging(self: &Arc<Self>) -> tracing::dispatcher::DefaultGuard {
+        let setup = self.adopt_logging();
+        let guard = tracing::dispatcher::set_default(&setup.dispatch);
+        setup.report();
+        guard
+    }
+
+    fn adopt_logging(self: &Arc<Self>) -> logging::Setup {
+        let setup = logging::setup(self);
+        let _ = self.log_filter.set(setup.filter.clone());
+        if let Some(spans) = &setup.spans {
+            let _ = self.span_export.set(spans.clone());
         }
+        setup
     }
-}
 
-/// Result of a multicast operation
-#[derive(Debug, Clone)]
-pub struct MulticastResult {
-    pub results: Vec<MulticastItem>,
-    pub failures: Vec<MulticastItem>,
-}
+    /// Change the log level at runtime. False if this cell didn't init logging.
+    pub fn set_log_level(&self, level: Level) -> bool {
+        self.log_filter
+            .get()
+            .is_some_and(|handle| handle.reload(logging::filter(level)).is_ok())
+    }
 
-#[derive(Debug, Clone)]
-pub struct MulticastItem {
-    pub cell_id: String,
-    pub result: Option<Value>,
-    pub latency_ms: u64,
-    pub error: Option<MeshError>,
-}
+    fn manifest_path(&self) -> Option<PathBuf> {
+        self.config
+            .manifest_dir
+            .as_ref()
+            .map(|dir| PathBuf::from(dir).join(format!("{}.cell.json", self.id)))
+    }
 
-/// Type-safe mesh proxy for ergonomic API usage
-pub struct MeshProxy {
-    cell: Arc<RheoCell>,
-}
+    fn read_manifest(&self) -> Option<CellManifest> {
+        let text = std::fs::read_to_string(self.manifest_path()?).ok()?;
+        serde_json::from_str(&text).ok()
+    }
 
-impl MeshProxy {
-    pub async fn call<T: DeserializeOwned>(
-        &self,
-        capability: impl Into<String>,
-        args: impl Serialize,
-    ) -> Result<T, MeshError> {
-        let result = self.cell.ask_mesh(capability, args).await;
-        if !result.ok {
-            return Err(result
-                .error
-                .unwrap_or_else(|| MeshError::new(ErrorCode::Internal, "Unknown error", "proxy")));
-        }
-        result.into_value()
+    fn manifest_age(&self) -> Option<Duration> {
+        std::fs::metadata(self.manifest_path()?)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+    }
+
+    async fn listening_port(&self) -> Option<u16> {
+        let addr = self.addr.read().await;
+        addr.rsplit(':').next()?.parse().ok()
+    }
+
+    /// Write (or refresh) our manifest for the orchestrator
+    async fn save_manifest(&self, port: u16) {
+        let Some(path) = self.manifest_path() else {
+            return;
+        };
+        if self.is_shutting_down.load(Ordering::SeqCst) > 0 {
+            return;
+        }
+        let mut capabilities: Vec<String> = self.handlers.iter().map(|e| e.key().clone()).collect();
+        capabilities.sort();
+        let manifest = CellManifest {
+            pid: std::process::id(),
+            version: binary_version().to_string(),
+            port,
+            start_time: self.started_at,
+            capabilities,
+            seed: self.config.seed.clone(),
+            pub_key: self.pub_key_hex.clone(),
+        };
+        let text = serde_json::to_string_pretty(&manifest).unwrap_or_default();
+        let written = tokio::task::spawn_blocking(move || {
+            path.parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(&path, text))
+                .map_err(|e| (e, path))
+        })
+        .await;
+        if let Ok(Err((e, path))) = written {
+            warn!(error = %e, path = %path.display(), "Failed to write cell manifest");
+        }
+    }
+
+    /// Remove our manifest - only if it is still ours, a newer instance may own it
+    fn remove_manifest(&self) {
+        if let (Some(path), Some(manifest)) = (self.manifest_path(), self.read_manifest()) {
+            if manifest.pid == std::process::id() && manifest.pub_key == self.pub_key_hex {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    /// Kill a previous instance of this cell that is still holding on
+    async fn kill_ghost(&self, ghost: &CellManifest) {
+        let pid = ghost.pid;
+        if pid == std::process::id() || pid == 0 {
+            return;
+        }
+        #[cfg(unix)]
+        {
+            // A recycled pid belongs to a process that started after the ghost did
+            match process_started_at(pid) {
+                None => return, // Already gone, or no way to tell it's the ghost
+                Some(started) if started > ghost.start_time + GHOST_START_SLACK_MS => {
+                    info!(cell_id = %self.id, pid, "Manifest pid now belongs to another process");
+                    return;
+                }
+                Some(_) => {}
+            }
+            let pid = pid as libc::pid_t;
+            warn!(cell_id = %self.id, pid, "💀 Killing ghost cell from stale manifest");
+            // SAFETY: kill has no memory-safety preconditions; the start-time
+            // check above makes sure pid is still the manifest's process
+            unsafe { libc::kill(pid, libc::SIGKILL) };
+            // Give the kernel a moment to release its port
+            for _ in 0..20 {
+                // SAFETY: signal 0 only probes whether pid still exists
+                if unsafe { libc::kill(pid, 0) } != 0 {
+                    break;
+                }
+                sleep(Duration::from_millis(50)).await;
+            }
+        }
+        #[cfg(not(unix))]
+        warn!(cell_id = %self.id, pid, "Ghost cleanup is only supported on unix");
+    }
+
+    /// Create a type-safe mesh proxy
+    pub fn mesh_proxy(self: &Arc<Self>) -> MeshProxy {
+        MeshProxy {
+            cell: Arc::clone(self),
+        }
+    }
+}
+
+/// A listening cell, returned by [`RheoCell::listen`]
+#[derive(Clone)]
+pub struct CellHandle {
+    cell: Arc<RheoCell>,
+    addr: SocketAddr,
+}
+
+impl CellHandle {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn cell(&self) -> &Arc<RheoCell> {
+        &self.cell
+    }
+
+    /// Resolves once the cell has bootstrapped from its seed (or given up trying)
+    pub async fn ready(&self) {
+        let mut ready = self.cell.ready.subscribe();
+        let _ = ready.wait_for(|r| *r).await;
+    }
+
+    pub async fn shutdown(&self) {
+        self.cell.shutdown().await;
+    }
+
+    /// Wait until the cell stops: `shutdown()` on any handle or clone, or SIGTERM/SIGINT
+    pub async fn join(self) {
+        self.cell.cancel.cancelled().await;
+        self.cell.shutdown().await;
+    }
+}
+
+/// Resolves with the name of the first SIGTERM/SIGINT received
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => tokio::select! {
+                _ = term.recv() => "SIGTERM",
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+            },
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+/// Result of a multicast operation
+#[derive(Debug, Clone)]
+pub struct MulticastResult {
+    pub results: Vec<MulticastItem>,
+    pub failures: Vec<MulticastItem>,
+}
+
+impl MulticastResult {
+    /// Combine the successful answers, in arrival order
+    pub fn reduce<R: multicast::Reducer>(&self, reducer: R) -> R::Output {
+        reducer.reduce(&self.results)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MulticastItem {
+    pub cell_id: String,
+    pub result: Option<Value>,
+    pub latency_ms: u64,
+    pub error: Option<MeshError>,
+}
+
+/// Type-safe mesh proxy for ergonomic API usage
+pub struct MeshProxy {
+    cell: Arc<RheoCell>,
+}
+
+impl MeshProxy {
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        capability: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<T, MeshError> {
+        let result = self.cell.ask_mesh(capability, args).await;
+        if !result.ok {
+            return Err(result
+                .error
+                .unwrap_or_else(|| MeshError::new(ErrorCode::Internal, "Unknown error", "proxy")));
+        }
+        result.into_value()
+    }
+}
+
+// HTTP Handlers
+/// What `POST /` accepts: one signal, or `{"batch": [...]}` of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Inbound {
+    Batch { batch: Vec<Signal> },
+    Single(Box<Signal>),
+}
+
+async fn handle_signal(
+    State(cell): State<Arc<RheoCell>>,
+    headers: HeaderMap,
+    Json(inbound): Json<Inbound>,
+) -> impl IntoResponse {
+    let mut signal = match inbound {
+        Inbound::Single(signal) => *signal,
+        Inbound::Batch { batch } => return handle_batch(cell, batch).await,
+    };
+
+    // The header is authoritative - the body may carry a stale context
+    if let Some(ctx) = headers
+        .get(telemetry::TRACEPARENT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(telemetry::TraceContext::parse)
+    {
+        ctx.inject(&mut signal);
     }
-}
 
-// HTTP Handlers
-async fn handle_signal(
-    State(cell): State<Arc<RheoCell>>,
-    Json(signal): Json<Signal>,
-) -> impl IntoResponse {
     // Helpful for debugging unreachable issues
     debug!(
         capability = %signal.payload.capability,
@@ -1763,7 +4482,7 @@ async fn handle_signal(
         "Incoming signal"
     );
 
-    let result = cell.route(signal).await;
+    let result = cell.route_inbound(signal).await;
 
     // WRAP the result in a "result" key for TS compatibility
     (
@@ -1772,6 +4491,32 @@ async fn handle_signal(
     )
 }
 
+/// Route every signal of a batch concurrently; results keep the batch order
+async fn handle_batch(cell: Arc<RheoCell>, batch: Vec<Signal>) -> (StatusCode, Json<Value>) {
+    if batch.len() > cell.config.max_batch {
+        let error = MeshError::new(
+            ErrorCode::ValidationFailed,
+            format!(
+                "Batch of {} signals exceeds the limit of {}",
+                batch.len(),
+                cell.config.max_batch
+            ),
+            &cell.id,
+        );
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(serde_json::json!({ "error": error })),
+        );
+    }
+    debug!(signals = batch.len(), "Incoming batch");
+
+    let results = join_all(batch.into_iter().map(|signal| cell.route_inbound(signal))).await;
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "results": results })),
+    )
+}
+
 async fn handle_atlas(State(cell): State<Arc<RheoCell>>) -> impl IntoResponse {
     let atlas: HashMap<String, AtlasEntry> = cell
         .atlas
@@ -1793,6 +4538,29 @@ async fn handle_health(State(cell): State<Arc<RheoCell>>) -> impl IntoResponse {
     (StatusCode::OK, Json(health))
 }
 
+/// `GET /openapi.json` - local capabilities, or the whole atlas with `?scope=mesh`
+async fn handle_openapi(
+    State(cell): State<Arc<RheoCell>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let descriptors = match query.get("scope").map(String::as_str) {
+        Some("mesh") => cell.describe_mesh_cached().await,
+        _ => cell.describe_local(),
+    };
+    // A cell itself only takes signal envelopes on `POST /` - these paths are the gateway's
+    let document = openapi::OpenApi::new(format!("{} capabilities", cell.id))
+        .behind_gateway()
+        .document(&descriptors);
+    (StatusCode::OK, Json(document))
+}
+
+async fn handle_ws(
+    State(cell): State<Arc<RheoCell>>,
+    upgrade: axum::extract::WebSocketUpgrade,
+) -> impl IntoResponse {
+    upgrade.on_upgrade(move |socket| ws::serve(cell, socket))
+}
+
 // Utility functions
 fn now_millis() -> u64 {
     SystemTime::now()
@@ -1812,14 +4580,28 @@ impl Clone for RheoCell {
             signing_key: SigningKey::from_bytes(&self.signing_key.to_bytes()),
             verifying_key: self.verifying_key,
             pub_key_hex: self.pub_key_hex.clone(),
+            started_at: self.started_at,
             atlas: Arc::clone(&self.atlas),
+            atlas_addrs: Arc::clone(&self.atlas_addrs),
+            shard_rings: Arc::clone(&self.shard_rings),
             handlers: Arc::clone(&self.handlers),
+            procedures: Arc::clone(&self.procedures),
+            ledger: Arc::clone(&self.ledger),
+            journal: self.journal.clone(),
+            log_filter: Arc::clone(&self.log_filter),
+            span_export: Arc::clone(&self.span_export),
+            admin: Arc::clone(&self.admin),
+            policy: Arc::clone(&self.policy),
             circuits: Arc::clone(&self.circuits),
-            seen_nonces: Arc::clone(&self.seen_nonces),
+            balancing: Arc::clone(&self.balancing),
+            events: self.events.clone(),
             active_executions: Arc::clone(&self.active_executions),
             result_cache: Arc::clone(&self.result_cache),
+            mesh_description: Arc::clone(&self.mesh_description),
             metrics: Arc::clone(&self.metrics),
-            shutdown_tx: None, // Don't clone sender
+            cancel: self.cancel.clone(),
+            stopped: self.stopped.clone(),
+            ready: Arc::clone(&self.ready),
             is_shutting_down: Arc::clone(&self.is_shutting_down),
             tasks: Arc::clone(&self.tasks),
         }
@@ -1837,6 +4619,11 @@ pub mod router {
     /// Schema trait for validation
     pub trait Schema: Send + Sync {
         fn parse(&self, value: Value) -> Result<Value, MeshError>;
+
+        /// JSON Schema document describing the values this schema accepts
+        fn json_schema(&self) -> Option<Value> {
+            None
+        }
     }
 
     /// JSON schema validator
@@ -1865,16 +4652,117 @@ pub mod router {
         }
     }
 
+    /// [`JsonSchema`] that also publishes the JSON Schema of `T` in `cell/contract`
+    pub struct PublishedSchema<T> {
+        _phantom: PhantomData<T>,
+    }
+
+    impl<T> Default for PublishedSchema<T> {
+        fn default() -> Self {
+            Self {
+                _phantom: PhantomData,
+            }
+        }
+    }
+
+    impl<T: DeserializeOwned + schemars::JsonSchema + Send + Sync> Schema for PublishedSchema<T> {
+        fn parse(&self, value: Value) -> Result<Value, MeshError> {
+            JsonSchema::<T>::default().parse(value)
+        }
+
+        fn json_schema(&self) -> Option<Value> {
+            serde_json::to_value(schemars::schema_for!(T)).ok()
+        }
+    }
+
+    /// Validator for a JSON Schema document, such as a [`Contract`]'s
+    pub struct ContractSchema {
+        schema: Value,
+        validator: jsonschema::Validator,
+    }
+
+    impl ContractSchema {
+        pub fn compile(schema: Value) -> Result<Self, String> {
+            let validator =
+                jsonschema::validator_for(&schema).map_err(|e| format!("Invalid schema: {}", e))?;
+            Ok(Self { schema, validator })
+        }
+    }
+
+    impl Schema for ContractSchema {
+        fn parse(&self, value: Value) -> Result<Value, MeshError> {
+            self.validator.validate(&value).map_err(|e| {
+                MeshError::new(
+                    ErrorCode::ValidationFailed,
+                    format!("Schema validation: {} at '{}'", e, e.instance_path),
+                    "schema",
+                )
+            })?;
+            Ok(value)
+        }
+
+        fn json_schema(&self) -> Option<Value> {
+            Some(self.schema.clone())
+        }
+    }
+
+    /// Published contract of a procedure - what `cell/contract` returns
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ProcedureSpec {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub input_schema: Option<Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub output_schema: Option<Value>,
+        pub is_mutation: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub version: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub compatibility: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub shard_key: Option<String>,
+    }
+
+    impl ProcedureSpec {
+        /// Contract versions served: the primary version followed by compatible ones
+        pub fn versions(&self) -> Vec<String> {
+            self.version
+                .iter()
+                .chain(self.compatibility.iter())
+                .cloned()
+                .collect()
+        }
+    }
+
+    impl From<&Contract> for ProcedureSpec {
+        fn from(contract: &Contract) -> Self {
+            Self {
+                description: contract.description.clone(),
+                input_schema: contract.input_schema.clone(),
+                output_schema: contract.output_schema.clone(),
+                is_mutation: !contract.query,
+                version: Some(contract.version.clone()),
+                compatibility: contract.compatibility.clone(),
+                shard_key: contract.shard_key.clone(),
+            }
+        }
+    }
+
     /// Procedure definition - FIXED: Added Clone bound
     pub struct Procedure<I, O> {
         input_schema: Option<Box<dyn Schema>>,
         output_schema: Option<Box<dyn Schema>>,
         handler: Box<
-            dyn Fn(I, Signal) -> futures::future::BoxFuture<'static, Result<O, MeshError>>
+            dyn Fn(I, Context) -> futures::future::BoxFuture<'static, Result<O, MeshError>>
                 + Send
                 + Sync,
         >,
         is_mutation: bool,
+        version: Option<String>,
+        description: Option<String>,
+        shard_key: Option<String>,
     }
 
     impl<I, O> Procedure<I, O>
@@ -1884,7 +4772,7 @@ pub mod router {
     {
         pub fn query<F, Fut>(handler: F) -> Self
         where
-            F: Fn(I, Signal) -> Fut + Send + Sync + 'static,
+            F: Fn(I, Context) -> Fut + Send + Sync + 'static,
             Fut: std::future::Future<Output = Result<O, MeshError>> + Send + 'static,
         {
             Self {
@@ -1892,12 +4780,15 @@ pub mod router {
                 output_schema: None,
                 handler: Box::new(move |i, s| Box::pin(handler(i, s))),
                 is_mutation: false,
+                version: None,
+                description: None,
+                shard_key: None,
             }
         }
 
         pub fn mutation<F, Fut>(handler: F) -> Self
         where
-            F: Fn(I, Signal) -> Fut + Send + Sync + 'static,
+            F: Fn(I, Context) -> Fut + Send + Sync + 'static,
             Fut: std::future::Future<Output = Result<O, MeshError>> + Send + 'static,
         {
             Self {
@@ -1905,6 +4796,9 @@ pub mod router {
                 output_schema: None,
                 handler: Box::new(move |i, s| Box::pin(handler(i, s))),
                 is_mutation: true,
+                version: None,
+                description: None,
+                shard_key: None,
             }
         }
 
@@ -1913,13 +4807,80 @@ pub mod router {
             self
         }
 
-        // FIXED: Share handler across calls
+        pub fn with_output_schema<S: Schema + 'static>(mut self, schema: S) -> Self {
+            self.output_schema = Some(Box::new(schema));
+            self
+        }
+
+        /// Validate and publish both sides of the contract from the Rust types
+        pub fn with_schemas(self) -> Self
+        where
+            I: schemars::JsonSchema + Sync,
+            O: DeserializeOwned + schemars::JsonSchema + Sync,
+        {
+            self.with_input_schema(PublishedSchema::<I>::default())
+                .with_output_schema(PublishedSchema::<O>::default())
+        }
+
+        /// Semver version of this procedure's contract, advertised in the atlas
+        pub fn with_version(mut self, version: impl Into<String>) -> Self {
+            self.version = Some(version.into());
+            self
+        }
+
+        /// Human/LLM-readable summary published by `mesh/describe`
+        pub fn describe(mut self, description: impl Into<String>) -> Self {
+            self.description = Some(description.into());
+            self
+        }
+
+        /// Route calls by `args.<field>` (see [`shard`])
+        pub fn shard_by(mut self, field: impl Into<String>) -> Self {
+            self.shard_key = Some(field.into());
+            self
+        }
+
+        pub fn is_mutation(&self) -> bool {
+            self.is_mutation
+        }
+
+        pub fn spec(&self) -> ProcedureSpec {
+            ProcedureSpec {
+                description: self.description.clone(),
+                input_schema: self.input_schema.as_ref().and_then(|s| s.json_schema()),
+                output_schema: self.output_schema.as_ref().and_then(|s| s.json_schema()),
+                is_mutation: self.is_mutation,
+                version: self.version.clone(),
+                compatibility: Vec::new(),
+                shard_key: self.shard_key.clone(),
+            }
+        }
+
         pub fn into_boxed(self) -> BoxedHandler {
+            self.into_parts().0
+        }
+
+        // FIXED: Share handler across calls
+        pub fn into_parts(self) -> (BoxedHandler, ProcedureSpec) {
+            let spec = self.spec();
             let handler = Arc::new(self.handler);
-            Box::new(move |args, signal| {
+            let input_schema: Option<Arc<dyn Schema>> = self.input_schema.map(Arc::from);
+            let output_schema: Option<Arc<dyn Schema>> = self.output_schema.map(Arc::from);
+
+            let boxed: BoxedHandler = Box::new(move |args, ctx| {
                 let handler = Arc::clone(&handler);
-                let signal_id = signal.id.clone();
+                let input_schema = input_schema.clone();
+                let output_schema = output_schema.clone();
+                let signal_id = ctx.id.clone();
                 Box::pin(async move {
+                    let args = match &input_schema {
+                        Some(schema) => match schema.parse(args) {
+                            Ok(v) => v,
+                            Err(e) => return TraceResult::failure(signal_id, e),
+                        },
+                        None => args,
+                    };
+
                     let input: I = match serde_json::from_value(args) {
                         Ok(i) => i,
                         Err(e) => {
@@ -1934,24 +4895,60 @@ pub mod router {
                         }
                     };
 
-                    match handler(input, signal).await {
-                        Ok(output) => TraceResult::success(signal_id, output),
-                        Err(e) => TraceResult::failure(signal_id, e),
+                    let output = match handler(input, ctx).await {
+                        Ok(output) => output,
+                        Err(e) => return TraceResult::failure(signal_id, e),
+                    };
+
+                    let value = match serde_json::to_value(output) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return TraceResult::failure(
+                                signal_id,
+                                MeshError::new(
+                                    ErrorCode::OutputValidationFailed,
+                                    format!("Output serialization: {}", e),
+                                    "procedure",
+                                ),
+                            );
+                        }
+                    };
+
+                    if let Some(schema) = &output_schema {
+                        if let Err(e) = schema.parse(value.clone()) {
+                            return TraceResult::failure(
+                                signal_id,
+                                MeshError::new(
+                                    ErrorCode::OutputValidationFailed,
+                                    format!("Output violates schema: {}", e.message),
+                                    "procedure",
+                                ),
+                            );
+                        }
                     }
+
+                    TraceResult::success(signal_id, value)
                 })
-            })
+            });
+
+            (boxed, spec)
         }
     }
 
+    /// Procedure contracts keyed by capability
+    pub type Specs = HashMap<String, ProcedureSpec>;
+
     /// Router builder for organizing capabilities
     pub struct Router {
         handlers: HashMap<String, BoxedHandler>,
+        specs: Specs,
     }
 
     impl Router {
         pub fn new() -> Self {
             Self {
                 handlers: HashMap::new(),
+                specs: HashMap::new(),
             }
         }
 
@@ -1960,7 +4957,10 @@ pub mod router {
             I: DeserializeOwned + Send + 'static,
             O: Serialize + Send + 'static,
         {
-            self.handlers.insert(path.into(), proc.into_boxed());
+            let path = path.into();
+            let (handler, spec) = proc.into_parts();
+            self.handlers.insert(path.clone(), handler);
+            self.specs.insert(path, spec);
             self
         }
 
@@ -1970,12 +4970,24 @@ pub mod router {
                 self.handlers
                     .insert(format!("{}/{}", prefix, path), handler);
             }
+            for (path, spec) in router.specs {
+                self.specs.insert(format!("{}/{}", prefix, path), spec);
+            }
             self
         }
 
+        /// Published contracts, keyed by capability
+        pub fn specs(&self) -> &Specs {
+            &self.specs
+        }
+
         pub fn into_handlers(self) -> HashMap<String, BoxedHandler> {
             self.handlers
         }
+
+        pub fn into_parts(self) -> (HashMap<String, BoxedHandler>, Specs) {
+            (self.handlers, self.specs)
+        }
     }
 
     impl Default for Router {
@@ -1986,205 +4998,5995 @@ pub mod router {
 }
 
 // ============================================================================
-// TRADING-SPECIFIC EXTENSIONS
+// CAPABILITY INTROSPECTION & LLM TOOLS
 // ============================================================================
 
-pub mod trading {
+pub mod tools {
     use super::*;
-    use serde::{Deserialize, Serialize};
 
-    /// High-performance order type
+    /// One capability as seen by `mesh/describe`
     #[derive(Debug, Clone, Serialize, Deserialize)]
-    pub struct Order {
-        pub id: String,
-        pub symbol: String,
-        pub side: Side,
-        pub price: f64,
-        pub quantity: f64,
-        pub timestamp: u64,
-        pub client_id: String,
+    #[serde(rename_all = "camelCase")]
+    pub struct CapabilityDescriptor {
+        pub capability: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub input_schema: Option<Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub output_schema: Option<Value>,
+        pub is_mutation: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub version: Option<String>,
+        pub provider: String,
     }
 
-    #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-    pub enum Side {
-        Buy,
-        Sell,
-    }
+    impl CapabilityDescriptor {
+        pub fn new(
+            capability: impl Into<String>,
+            spec: Option<router::ProcedureSpec>,
+            provider: impl Into<String>,
+        ) -> Self {
+            let spec = spec.unwrap_or(router::ProcedureSpec {
+                // Nothing declared - don't let agents assume it's side-effect free
+                is_mutation: true,
+                ..Default::default()
+            });
+            Self {
+                capability: capability.into(),
+                description: spec.description,
+                input_schema: spec.input_schema,
+                output_schema: spec.output_schema,
+                is_mutation: spec.is_mutation,
+                version: spec.version,
+                provider: provider.into(),
+            }
+        }
 
-    /// Market data tick
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    pub struct Tick {
-        pub symbol: String,
-        pub bid: f64,
-        pub ask: f64,
-        pub last: f64,
-        pub volume: f64,
-        pub timestamp: u64,
+        /// Mesh plumbing (`mesh/*`, `cell/*`) that agents shouldn't drive
+        pub fn is_internal(&self) -> bool {
+            self.capability.starts_with("mesh/") || self.capability.starts_with("cell/")
+        }
+
+        /// Function-calling name: `orbital/create` -> `orbital__create`. Lossy -
+        /// a tool list names its tools with [`tool_names`], which keeps them apart.
+        pub fn tool_name(&self) -> String {
+            tool_name(&self.capability)
+        }
+
+        /// Tool parameters must be an object schema; scalar inputs are wrapped
+        /// as `{ "input": ... }` and unwrapped again by `args_from_tool_call`.
+        /// The wrapped schema's definitions move up to the wrapper, where its
+        /// `#/definitions/...` references resolve.
+        pub fn parameters(&self) -> Value {
+            match &self.input_schema {
+                Some(schema) if schema_is_object(schema) => strip_schema_meta(schema),
+                Some(schema) => {
+                    let mut input = strip_schema_meta(schema);
+                    let mut wrapper = serde_json::json!({
+                        "type": "object",
+                        "required": ["input"],
+                    });
+                    if let Some(input) = input.as_object_mut() {
+                        for key in ["definitions", "$defs"] {
+                            if let Some(definitions) = input.remove(key) {
+                                wrapper[key] = definitions;
+                            }
+                        }
+                    }
+                    wrapper["properties"] = serde_json::json!({ "input": input });
+                    wrapper
+                }
+                None => serde_json::json!({ "type": "object", "additionalProperties": true }),
+            }
+        }
+
+        /// Turn tool-call arguments back into capability args
+        pub fn args_from_tool_call(&self, arguments: Value) -> Value {
+            match &self.input_schema {
+                Some(schema) if !schema_is_object(schema) => {
+                    arguments.get("input").cloned().unwrap_or(Value::Null)
+                }
+                _ => arguments,
+            }
+        }
+
+        fn tool_description(&self) -> String {
+            let mut text = self
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Mesh capability {}", self.capability));
+            if let Some(version) = &self.version {
+                text.push_str(&format!(" (v{})", version));
+            }
+            if self.is_mutation {
+                text.push_str(" [mutation]");
+            }
+            text
+        }
     }
 
-    /// Position tracking
-    #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-    pub struct Position {
-        pub symbol: String,
-        pub quantity: f64,
-        pub avg_entry: f64,
-        pub unrealized_pnl: f64,
-        pub realized_pnl: f64,
+    pub fn tool_name(capability: &str) -> String {
+        capability
+            .replace('/', "__")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(64)
+            .collect()
     }
 
-    /// Risk limits
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    pub struct RiskLimits {
-        pub max_position: f64,
-        pub max_order_size: f64,
-        pub max_daily_loss: f64,
-        pub max_drawdown: f64,
+    /// Names for the tools `descriptors` offer (internal ones are left out),
+    /// paired with their descriptor. Capabilities whose [`tool_name`] collides
+    /// (`a/b` and `a__b`, or long names cut at 64) each get a suffix hashed
+    /// from the capability, so every name is unique and stable.
+    pub fn tool_names(
+        descriptors: &[CapabilityDescriptor],
+    ) -> Vec<(String, &CapabilityDescriptor)> {
+        let offered: Vec<_> = descriptors.iter().filter(|d| !d.is_internal()).collect();
+        let mut claimants: HashMap<String, Vec<&str>> = HashMap::new();
+        for d in &offered {
+            let claimed = claimants.entry(d.tool_name()).or_default();
+            if !claimed.contains(&d.capability.as_str()) {
+                claimed.push(&d.capability);
+            }
+        }
+        offered
+            .into_iter()
+            .map(|d| {
+                let name = d.tool_name();
+                if claimants[&name].len() < 2 {
+                    return (name, d);
+                }
+                let digest = hex::encode(Sha256::digest(d.capability.as_bytes()));
+                let base: String = name.chars().take(64 - 9).collect();
+                (format!("{}_{}", base, &digest[..8]), d)
+            })
+            .collect()
     }
 
-    /// Trading cell with specialized handlers
-    pub struct TradingCell {
-        cell: Arc<RheoCell>,
-        orders: Arc<DashMap<String, Order>>,
-        positions: Arc<DashMap<String, Position>>,
-        risk_limits: Arc<RwLock<RiskLimits>>,
+    /// Find the descriptor a model's tool call refers to, by its name in
+    /// [`tool_names`]
+    pub fn find_tool<'a>(
+        descriptors: &'a [CapabilityDescriptor],
+        name: &str,
+    ) -> Option<&'a CapabilityDescriptor> {
+        tool_names(descriptors)
+            .into_iter()
+            .find(|(tool, _)| tool == name)
+            .map(|(_, d)| d)
     }
 
-    impl TradingCell {
-        pub fn new(cell: Arc<RheoCell>, risk_limits: RiskLimits) -> Self {
-            let trading = Self {
-                cell: Arc::clone(&cell),
-                orders: Arc::new(DashMap::new()),
-                positions: Arc::new(DashMap::new()),
-                risk_limits: Arc::new(RwLock::new(risk_limits)),
-            };
+    /// OpenAI-style `tools` array (`{"type":"function","function":{...}}`)
+    pub fn to_openai_tools(descriptors: &[CapabilityDescriptor]) -> Vec<Value> {
+        tool_names(descriptors)
+            .into_iter()
+            .map(|(name, d)| {
+                serde_json::json!({
+                    "type": "function",
+                    "function": {
+                        "name": name,
+                        "description": d.tool_description(),
+                        "parameters": d.parameters(),
+                    }
+                })
+            })
+            .collect()
+    }
 
-            trading.register_handlers();
-            trading
+    /// Anthropic-style `tools` array (`{"name","description","input_schema"}`)
+    pub fn to_anthropic_tools(descriptors: &[CapabilityDescriptor]) -> Vec<Value> {
+        tool_names(descriptors)
+            .into_iter()
+            .map(|(name, d)| {
+                serde_json::json!({
+                    "name": name,
+                    "description": d.tool_description(),
+                    "input_schema": d.parameters(),
+                })
+            })
+            .collect()
+    }
+
+    fn schema_is_object(schema: &Value) -> bool {
+        schema.get("type").and_then(|t| t.as_str()) == Some("object")
+    }
+
+    fn strip_schema_meta(schema: &Value) -> Value {
+        let mut schema = schema.clone();
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$schema");
+            obj.remove("title");
         }
+        schema
+    }
+}
 
-        fn register_handlers(&self) {
-            let orders = Arc::clone(&self.orders);
-            self.cell
-                .provide("trading/place_order", move |order: Order, _signal| {
-                    let orders = Arc::clone(&orders);
-                    Box::pin(async move {
-                        // Validate order
-                        if order.quantity <= 0.0 || order.price <= 0.0 {
-                            return Err(MeshError::new(
-                                ErrorCode::ValidationFailed,
-                                "Invalid order parameters",
-                                "trading",
-                            ));
-                        }
+// ============================================================================
+// MULTICAST
+// ============================================================================
 
-                        orders.insert(order.id.clone(), order.clone());
-                        Ok(serde_json::json!({
-                            "status": "accepted",
-                            "order_id": order.id
-                        }))
-                    })
-                });
+/// Options and reducers for [`RheoCell::ask_all_with`]
+pub mod multicast {
+    use super::*;
+    use std::marker::PhantomData;
 
-            let positions = Arc::clone(&self.positions);
-            self.cell
-                .provide("trading/get_position", move |symbol: String, _signal| {
-                    let positions = Arc::clone(&positions);
-                    Box::pin(async move {
-                        let pos = positions.get(&symbol).map(|p| p.clone());
-                        Ok(pos.unwrap_or_default())
-                    })
-                });
+    /// When a multicast stops waiting for providers
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Completion {
+        /// Every provider answers or times out
+        All,
+        /// The first `n` answers, successful or not
+        First(usize),
+        /// `n` successful answers, or as soon as that is out of reach
+        Quorum(usize),
+    }
 
-            let cell = Arc::clone(&self.cell);
-            self.cell
-                .provide("trading/market_data", move |_args: (), _signal| {
-                    let cell = Arc::clone(&cell);
-                    Box::pin(async move {
-                        // Multicast to all market data providers
-                        let result = cell.ask_all("marketdata/tick", (), 100).await;
-                        let ticks: Vec<Tick> = result
-                            .results
-                            .into_iter()
-                            .filter_map(|item| {
-                                item.result.and_then(|v| serde_json::from_value(v).ok())
-                            })
-                            .collect();
-                        Ok(ticks)
-                    })
-                });
+    impl Completion {
+        pub(crate) fn is_done(&self, succeeded: usize, answered: usize, total: usize) -> bool {
+            match *self {
+                Completion::All => answered == total,
+                Completion::First(n) => answered >= n,
+                Completion::Quorum(n) => succeeded >= n || succeeded + (total - answered) < n,
+            }
         }
+    }
 
-        pub async fn place_order(&self, order: Order) -> Result<Value, MeshError> {
-            self.cell
-                .mesh_proxy()
-                .call("trading/place_order", order)
-                .await
+    #[derive(Debug, Clone, Copy)]
+    pub struct Options {
+        pub timeout_ms: u64,
+        pub completion: Completion,
+    }
+
+    impl Options {
+        pub fn new(timeout_ms: u64) -> Self {
+            Self {
+                timeout_ms,
+                completion: Completion::All,
+            }
         }
 
-        pub async fn get_position(&self, symbol: impl Into<String>) -> Result<Position, MeshError> {
-            self.cell
-                .mesh_proxy()
-                .call("trading/get_position", symbol.into())
-                .await
+        pub fn with_first(mut self, n: usize) -> Self {
+            self.completion = Completion::First(n);
+            self
+        }
+
+        pub fn with_quorum(mut self, n: usize) -> Self {
+            self.completion = Completion::Quorum(n);
+            self
+        }
+
+        /// Stop at the first successful answer
+        pub fn with_first_success(self) -> Self {
+            self.with_quorum(1)
+        }
+    }
+
+    /// Folds the successful answers of a multicast into one value
+    pub trait Reducer {
+        type Output;
+        fn reduce(self, items: &[MulticastItem]) -> Self::Output;
+    }
+
+    /// Answers that decode as `T`; the others are skipped
+    fn decoded<T: DeserializeOwned>(items: &[MulticastItem]) -> impl Iterator<Item = T> + '_ {
+        items
+            .iter()
+            .filter_map(|item| serde_json::from_value(item.result.clone()?).ok())
+    }
+
+    /// Every answer, as a list
+    pub struct Merge<T>(PhantomData<T>);
+
+    pub fn merge<T: DeserializeOwned>() -> Merge<T> {
+        Merge(PhantomData)
+    }
+
+    impl<T: DeserializeOwned> Reducer for Merge<T> {
+        type Output = Vec<T>;
+        fn reduce(self, items: &[MulticastItem]) -> Vec<T> {
+            decoded(items).collect()
+        }
+    }
+
+    /// The most common answer and how many providers gave it; ties go to
+    /// the answer that arrived first
+    pub struct Vote<T>(PhantomData<T>);
+
+    pub fn vote<T: DeserializeOwned>() -> Vote<T> {
+        Vote(PhantomData)
+    }
+
+    impl<T: DeserializeOwned> Reducer for Vote<T> {
+        type Output = Option<(T, usize)>;
+        fn reduce(self, items: &[MulticastItem]) -> Option<(T, usize)> {
+            let mut tally: Vec<(&Value, usize)> = Vec::new();
+            for value in items.iter().filter_map(|item| item.result.as_ref()) {
+                match tally.iter_mut().find(|(v, _)| *v == value) {
+                    Some((_, count)) => *count += 1,
+                    None => tally.push((value, 1)),
+                }
+            }
+            tally
+                .into_iter()
+                .filter_map(|(value, count)| Some((T::deserialize(value).ok()?, count)))
+                .reduce(|best, next| if next.1 > best.1 { next } else { best })
+        }
+    }
+
+    /// The answer with the smallest key; ties go to the earliest
+    pub struct MinBy<T, F>(F, PhantomData<T>);
+
+    pub fn min_by_key<T, K, F>(key: F) -> MinBy<T, F>
+    where
+        T: DeserializeOwned,
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        MinBy(key, PhantomData)
+    }
+
+    impl<T: DeserializeOwned, K: Ord, F: Fn(&T) -> K> Reducer for MinBy<T, F> {
+        type Output = Option<T>;
+        fn reduce(self, items: &[MulticastItem]) -> Option<T> {
+            decoded(items).min_by_key(|t| (self.0)(t))
+        }
+    }
+
+    /// The answer with the largest key; ties go to the earliest
+    pub struct MaxBy<T, F>(F, PhantomData<T>);
+
+    pub fn max_by_key<T, K, F>(key: F) -> MaxBy<T, F>
+    where
+        T: DeserializeOwned,
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        MaxBy(key, PhantomData)
+    }
+
+    impl<T: DeserializeOwned, K: Ord, F: Fn(&T) -> K> Reducer for MaxBy<T, F> {
+        type Output = Option<T>;
+        fn reduce(self, items: &[MulticastItem]) -> Option<T> {
+            decoded(items).reduce(|best, next| {
+                if (self.0)(&next) > (self.0)(&best) {
+                    next
+                } else {
+                    best
+                }
+            })
         }
     }
 }
 
 // ============================================================================
-// EXAMPLE USAGE
+// CONSISTENT HASHING
 // ============================================================================
 
-#[cfg(test)]
-mod tests {
+/// Sticky routing for capabilities that declare a shard key (`shard_by`):
+/// providers are placed on a hash ring by cell id, and a call goes to the first
+/// provider after the hash of its key. When providers join or leave only the
+/// keys next to them move.
+pub mod shard {
     use super::*;
 
-    #[tokio::test]
-    async fn test_cell_creation() {
-        let cell = RheoCell::new(CellConfig::default());
-        let addr = cell.listen().await.unwrap();
-        assert!(addr.port() > 0);
+    /// Ring points per provider, to spread keys evenly
+    pub const VNODES: usize = 64;
+
+    /// The shard key in `args`: a dotted field path, or `""` for the args
+    /// themselves. Strings are used as-is, other values as JSON.
+    pub fn key_of(args: &Value, field: &str) -> Option<String> {
+        let value = field
+            .split('.')
+            .filter(|f| !f.is_empty())
+            .try_fold(args, |value, f| value.get(f))?;
+        match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
 
-        // Test ping
-        let result = cell.ask_mesh("mesh/ping", ()).await;
-        assert!(result.ok);
+    /// Stable across processes and languages, unlike `DefaultHasher`
+    fn hash(text: &str) -> u64 {
+        let digest = Sha256::digest(text.as_bytes());
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
 
-        cell.shutdown().await;
+    pub struct Ring {
+        members: Vec<String>,
+        points: Vec<(u64, String)>,
     }
 
-    #[tokio::test]
-    async fn test_cell_communication() {
-        let cell1 = RheoCell::new(CellConfig {
-            id: "cell_1".to_string(),
-            ..Default::default()
-        });
-        let addr1 = cell1.listen().await.unwrap();
+    impl Ring {
+        pub fn new(members: impl IntoIterator<Item = String>) -> Self {
+            let mut members: Vec<String> = members.into_iter().collect();
+            members.sort();
+            members.dedup();
+            let mut points: Vec<(u64, String)> = members
+                .iter()
+                .flat_map(|id| {
+                    (0..VNODES).map(move |v| (hash(&format!("{}#{}", id, v)), id.clone()))
+                })
+                .collect();
+            points.sort();
+            Self { members, points }
+        }
 
-        // Register custom handler
-        cell1.provide("test/echo", |msg: String, _| {
-            Box::pin(async move { Ok(format!("echo: {}", msg)) })
-        });
+        /// Member ids, sorted
+        pub fn members(&self) -> &[String] {
+            &self.members
+        }
 
-        // Wait for convergence
-        sleep(Duration::from_millis(100)).await;
+        /// Whether `id` owns `key`, or the ring is empty
+        pub fn is_owner(&self, id: &str, key: &str) -> bool {
+            self.owner(key).is_none_or(|owner| owner == id)
+        }
 
-        let cell2 = RheoCell::new(CellConfig {
-            id: "cell_2".to_string(),
-            seed: Some(format!("http://127.0.0.1:{}", addr1.port())),
-            ..Default::default()
-        });
-        cell2.listen().await.unwrap();
+        pub fn owner(&self, key: &str) -> Option<&str> {
+            let at = self.points.partition_point(|(point, _)| *point < hash(key));
+            self.points
+                .get(at)
+                .or_else(|| self.points.first())
+                .map(|(_, id)| id.as_str())
+        }
 
-        // Wait for bootstrap
-        sleep(Duration::from_millis(500)).await;
+        /// Every member once, in ring order from the owner of `key` - the
+        /// order to fail over in
+        pub fn successors(&self, key: &str) -> Vec<String> {
+            let at = self.points.partition_point(|(point, _)| *point < hash(key));
+            let mut order: Vec<String> = Vec::new();
+            for (_, id) in self.points[at..].iter().chain(&self.points[..at]) {
+                if !order.contains(id) {
+                    order.push(id.clone());
+                }
+            }
+            order
+        }
+    }
+}
 
-        // Test cross-cell call
-        let result: Result<String, MeshError> =
-            cell2.mesh_proxy().call("test/echo", "hello world").await;
+// ============================================================================
+// LOAD BALANCING
+// ============================================================================
 
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "echo: hello world");
+/// Ordering the providers of a capability. Every RPC feeds [`PeerStats`]
+/// (outstanding requests and an EWMA of latency per address); a
+/// [`LoadBalancer`] ranks candidates from it, and providers with an open
+/// circuit are always tried last.
+pub mod balance {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use std::sync::atomic::AtomicUsize;
 
-        cell1.shutdown().await;
-        cell2.shutdown().await;
+    /// Weight of the newest latency sample
+    const EWMA_WEIGHT: f64 = 0.3;
+
+    /// Orders the providers of a capability best-first; forwarding tries up
+    /// to three of them in that order
+    pub trait LoadBalancer: Send + Sync {
+        fn rank(&self, capability: &str, providers: &mut [AtlasEntry], stats: &PeerStats);
+    }
+
+    /// Built-in strategies, named in config as `round_robin`, `random`,
+    /// `least_outstanding` and `ewma`
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum Strategy {
+        RoundRobin,
+        Random,
+        LeastOutstanding,
+        #[default]
+        Ewma,
+    }
+
+    impl std::str::FromStr for Strategy {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s.to_ascii_lowercase().replace('-', "_").as_str() {
+                "round_robin" => Ok(Strategy::RoundRobin),
+                "random" => Ok(Strategy::Random),
+                "least_outstanding" => Ok(Strategy::LeastOutstanding),
+                "ewma" => Ok(Strategy::Ewma),
+                other => Err(format!("unknown load balancer {}", other)),
+            }
+        }
+    }
+
+    impl fmt::Display for Strategy {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Strategy::RoundRobin => write!(f, "round_robin"),
+                Strategy::Random => write!(f, "random"),
+                Strategy::LeastOutstanding => write!(f, "least_outstanding"),
+                Strategy::Ewma => write!(f, "ewma"),
+            }
+        }
+    }
+
+    impl Strategy {
+        pub fn balancer(self) -> Arc<dyn LoadBalancer> {
+            match self {
+                Strategy::RoundRobin => Arc::new(RoundRobin::default()),
+                Strategy::Random => Arc::new(Random),
+                Strategy::LeastOutstanding => Arc::new(LeastOutstanding),
+                Strategy::Ewma => Arc::new(Ewma),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct Peer {
+        outstanding: AtomicU64,
+        /// 0 until the first sample
+        ewma_micros: AtomicU64,
+    }
+
+    /// What this cell has measured about each peer address
+    #[derive(Default)]
+    pub struct PeerStats {
+        peers: DashMap<String, Peer>,
+    }
+
+    impl PeerStats {
+        pub fn outstanding(&self, addr: &str) -> u64 {
+            self.peers
+                .get(addr)
+                .map_or(0, |p| p.outstanding.load(Ordering::SeqCst))
+        }
+
+        /// Smoothed latency, once there has been a sample
+        pub fn latency(&self, addr: &str) -> Option<Duration> {
+            self.peers
+                .get(addr)
+                .map(|p| p.ewma_micros.load(Ordering::SeqCst))
+                .filter(|micros| *micros > 0)
+                .map(Duration::from_micros)
+        }
+
+        /// Count a request as outstanding until the returned guard is finished or dropped
+        pub fn begin(&self, addr: &str) -> InFlight<'_> {
+            self.peers
+                .entry(addr.to_string())
+                .or_default()
+                .outstanding
+                .fetch_add(1, Ordering::SeqCst);
+            InFlight {
+                stats: self,
+                addr: addr.to_string(),
+            }
+        }
+
+        pub fn record(&self, addr: &str, sample: Duration) {
+            let sample = (sample.as_micros() as u64).max(1);
+            let peer = self.peers.entry(addr.to_string()).or_default();
+            let _ = peer
+                .ewma_micros
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |ewma| {
+                    Some(if ewma == 0 {
+                        sample
+                    } else {
+                        (EWMA_WEIGHT * sample as f64 + (1.0 - EWMA_WEIGHT) * ewma as f64) as u64
+                    })
+                });
+        }
+    }
+
+    /// An outstanding request (see [`PeerStats::begin`])
+    pub struct InFlight<'a> {
+        stats: &'a PeerStats,
+        addr: String,
+    }
+
+    impl InFlight<'_> {
+        pub fn finish(self, latency: Duration) {
+            self.stats.record(&self.addr, latency);
+        }
+    }
+
+    impl Drop for InFlight<'_> {
+        fn drop(&mut self) {
+            if let Some(peer) = self.stats.peers.get(&self.addr) {
+                peer.outstanding.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Take turns, per capability
+    #[derive(Default)]
+    pub struct RoundRobin {
+        next: DashMap<String, AtomicUsize>,
+    }
+
+    impl LoadBalancer for RoundRobin {
+        fn rank(&self, capability: &str, providers: &mut [AtlasEntry], _stats: &PeerStats) {
+            if providers.is_empty() {
+                return;
+            }
+            // Atlas iteration order isn't stable, so turns follow the addresses
+            providers.sort_by(|a, b| a.addr.cmp(&b.addr));
+            let turn = self
+                .next
+                .entry(capability.to_string())
+                .or_default()
+                .fetch_add(1, Ordering::SeqCst);
+            let len = providers.len();
+            providers.rotate_left(turn % len);
+        }
+    }
+
+    pub struct Random;
+
+    impl LoadBalancer for Random {
+        fn rank(&self, _capability: &str, providers: &mut [AtlasEntry], _stats: &PeerStats) {
+            providers.shuffle(&mut rand::thread_rng());
+        }
+    }
+
+    /// Fewest requests in flight first; ties are broken at random
+    pub struct LeastOutstanding;
+
+    impl LoadBalancer for LeastOutstanding {
+        fn rank(&self, _capability: &str, providers: &mut [AtlasEntry], stats: &PeerStats) {
+            providers.shuffle(&mut rand::thread_rng());
+            providers.sort_by_key(|p| stats.outstanding(&p.addr));
+        }
+    }
+
+    /// Lowest smoothed latency, scaled by the requests already waiting on it.
+    /// Providers never measured go first so they get a sample.
+    pub struct Ewma;
+
+    impl LoadBalancer for Ewma {
+        fn rank(&self, _capability: &str, providers: &mut [AtlasEntry], stats: &PeerStats) {
+            providers.shuffle(&mut rand::thread_rng());
+            providers.sort_by_key(|p| {
+                stats.latency(&p.addr).map_or(0, |latency| {
+                    latency.as_micros() as u64 * (stats.outstanding(&p.addr) + 1)
+                })
+            });
+        }
+    }
+
+    /// Recent successful call latencies kept per capability for hedging
+    const CALL_WINDOW: usize = 128;
+    /// Too few samples make for a meaningless percentile
+    const MIN_CALL_SAMPLES: usize = 10;
+
+    /// The balancer for each capability: the first matching rule, else the default
+    pub struct Balancing {
+        default: Arc<dyn LoadBalancer>,
+        rules: RwLock<Vec<(String, Arc<dyn LoadBalancer>)>>,
+        pub stats: PeerStats,
+        calls: DashMap<String, std::collections::VecDeque<u64>>,
+    }
+
+    impl Balancing {
+        pub fn from_config(config: &CellConfig) -> Self {
+            Self {
+                default: config.load_balancer.balancer(),
+                rules: RwLock::new(
+                    config
+                        .load_balancers
+                        .iter()
+                        .map(|(pattern, strategy)| (pattern.clone(), strategy.balancer()))
+                        .collect(),
+                ),
+                stats: PeerStats::default(),
+                calls: DashMap::new(),
+            }
+        }
+
+        /// Remember how long a successful call to `capability` took
+        pub fn record_call(&self, capability: &str, elapsed: Duration) {
+            let micros = elapsed.as_micros() as u64;
+            let mut window = self.calls.entry(capability.to_string()).or_default();
+            if window.len() == CALL_WINDOW {
+                window.pop_front();
+            }
+            window.push_back(micros);
+        }
+
+        /// Latency below which `percentile`% of recent calls finished
+        pub fn call_percentile(&self, capability: &str, percentile: u64) -> Option<Duration> {
+            let window = self.calls.get(capability)?;
+            if window.len() < MIN_CALL_SAMPLES {
+                return None;
+            }
+            let mut sorted: Vec<u64> = window.iter().copied().collect();
+            sorted.sort_unstable();
+            let index = (sorted.len() * percentile as usize / 100).min(sorted.len() - 1);
+            Some(Duration::from_micros(sorted[index]))
+        }
+
+        /// Put `balancer` ahead of every existing rule
+        pub fn set(&self, pattern: impl Into<String>, balancer: Arc<dyn LoadBalancer>) {
+            let pattern = pattern.into();
+            let mut rules = self.rules.write().unwrap();
+            rules.retain(|(p, _)| p != &pattern);
+            rules.insert(0, (pattern, balancer));
+        }
+
+        pub fn rank(
+            &self,
+            capability: &str,
+            providers: &mut [AtlasEntry],
+            circuits: &DashMap<String, CircuitBreaker>,
+        ) {
+            let balancer = self
+                .rules
+                .read()
+                .unwrap()
+                .iter()
+                .find(|(pattern, _)| policy::glob(pattern, capability))
+                .map(|(_, balancer)| Arc::clone(balancer))
+                .unwrap_or_else(|| Arc::clone(&self.default));
+            balancer.rank(capability, providers, &self.stats);
+            // Stable, so the balancer's order holds among the healthy ones
+            providers.sort_by_key(|p| circuits.get(&p.addr).is_some_and(|c| c.is_open()));
+        }
+    }
+}
+
+// ============================================================================
+// OPENAPI
+// ============================================================================
+
+/// OpenAPI 3.1 documents for capabilities called over HTTP as
+/// `POST {base_path}/<capability>` (how the gateway exposes them): the body is
+/// the capability's args, the answer `{ok, cid, value}` or `{ok, cid, error}`.
+pub mod openapi {
+    use super::*;
+
+    /// Where the gateway listens unless `RHEO_GATEWAY_PORT` says otherwise
+    pub const DEFAULT_GATEWAY_URL: &str = "http://localhost:8080";
+
+    pub struct OpenApi {
+        title: String,
+        version: String,
+        base_path: String,
+        servers: Vec<Value>,
+    }
+
+    impl OpenApi {
+        pub fn new(title: impl Into<String>) -> Self {
+            Self {
+                title: title.into(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                base_path: "/api".to_string(),
+                servers: Vec::new(),
+            }
+        }
+
+        /// Point clients at the gateway: a document served by a cell, which only
+        /// accepts signal envelopes itself
+        pub fn behind_gateway(mut self) -> Self {
+            self.servers.push(serde_json::json!({
+                "url": "{gateway}",
+                "description": "The mesh gateway - cells only accept signal envelopes on POST /",
+                "variables": { "gateway": { "default": DEFAULT_GATEWAY_URL } },
+            }));
+            self
+        }
+
+        pub fn with_version(mut self, version: impl Into<String>) -> Self {
+            self.version = version.into();
+            self
+        }
+
+        pub fn with_base_path(mut self, base_path: impl Into<String>) -> Self {
+            self.base_path = base_path.into().trim_end_matches('/').to_string();
+            self
+        }
+
+        /// One `POST` operation per public capability; mesh plumbing is left out
+        pub fn document(&self, descriptors: &[tools::CapabilityDescriptor]) -> Value {
+            let mut schemas = serde_json::Map::new();
+            schemas.insert("MeshError".to_string(), mesh_error_schema());
+
+            let mut paths = serde_json::Map::new();
+            for (operation_id, descriptor) in tools::tool_names(descriptors) {
+                let path = format!("{}/{}", self.base_path, descriptor.capability);
+                let operation = operation(descriptor, operation_id, &mut schemas);
+                paths.insert(path, serde_json::json!({ "post": operation }));
+            }
+
+            let mut document = serde_json::json!({
+                "openapi": "3.1.0",
+                "info": { "title": self.title, "version": self.version },
+                "paths": paths,
+                "components": { "schemas": schemas },
+            });
+            if !self.servers.is_empty() {
+                document["servers"] = Value::Array(self.servers.clone());
+            }
+            document
+        }
+    }
+
+    fn operation(
+        descriptor: &tools::CapabilityDescriptor,
+        operation_id: String,
+        schemas: &mut serde_json::Map<String, Value>,
+    ) -> Value {
+        let tag = descriptor.capability.split('/').next().unwrap_or_default();
+        let input = descriptor
+            .input_schema
+            .as_ref()
+            .map(|s| hoist_definitions(s, tag, schemas))
+            .unwrap_or_else(|| serde_json::json!({}));
+        let output = descriptor
+            .output_schema
+            .as_ref()
+            .map(|s| hoist_definitions(s, tag, schemas))
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        let mut responses = serde_json::Map::new();
+        responses.insert(
+            "200".to_string(),
+            serde_json::json!({
+                "description": "Capability result",
+                "content": { "application/json": { "schema": {
+                    "type": "object",
+                    "required": ["ok", "value"],
+                    "properties": {
+                        "ok": { "const": true },
+                        "cid": { "type": "string" },
+                        "value": output,
+                    },
+                }}},
+            }),
+        );
+        for (status, codes) in error_statuses() {
+            let names: Vec<Value> = codes
+                .iter()
+                .map(|c| serde_json::to_value(c).unwrap_or_default())
+                .collect();
+            let listed: Vec<String> = codes.iter().map(|c| c.to_string()).collect();
+            responses.insert(
+                status.to_string(),
+                serde_json::json!({
+                    "description": listed.join(", "),
+                    "content": { "application/json": { "schema": {
+                        "type": "object",
+                        "required": ["ok", "error"],
+                        "properties": {
+                            "ok": { "const": false },
+                            "cid": { "type": "string" },
+                            "error": { "allOf": [
+                                { "$ref": "#/components/schemas/MeshError" },
+                                { "properties": { "code": { "enum": names } } },
+                            ]},
+                        },
+                    }}},
+                }),
+            );
+        }
+
+        let mut operation = serde_json::json!({
+            "operationId": operation_id,
+            "tags": [tag],
+            "requestBody": {
+                "required": descriptor.input_schema.is_some(),
+                "content": { "application/json": { "schema": input } },
+            },
+            "responses": responses,
+            "x-rheo-capability": descriptor.capability,
+            "x-rheo-mutation": descriptor.is_mutation,
+            "x-rheo-provider": descriptor.provider,
+        });
+        if let Some(description) = &descriptor.description {
+            operation["summary"] = Value::String(description.clone());
+        }
+        if let Some(version) = &descriptor.version {
+            operation["x-rheo-version"] = Value::String(version.clone());
+        }
+        operation
+    }
+
+    /// Error codes grouped by the HTTP status they surface as
+    fn error_statuses() -> std::collections::BTreeMap<u16, Vec<ErrorCode>> {
+        let mut statuses = std::collections::BTreeMap::new();
+        for code in ErrorCode::ALL {
+            statuses
+                .entry(code.http_status())
+                .or_insert_with(Vec::new)
+                .push(code);
+        }
+        statuses
+    }
+
+    fn mesh_error_schema() -> Value {
+        let codes: Vec<Value> = ErrorCode::ALL
+            .iter()
+            .map(|c| serde_json::to_value(c).unwrap_or_default())
+            .collect();
+        serde_json::json!({
+            "type": "object",
+            "required": ["code", "message"],
+            "properties": {
+                "code": { "enum": codes },
+                "message": { "type": "string" },
+                "from": { "type": "string" },
+                "trace": { "type": "array", "items": { "type": "string" } },
+                "timestamp": { "type": "integer" },
+                "details": {},
+            },
+        })
+    }
+
+    /// Move schemars `definitions` into `components/schemas` and repoint the refs.
+    /// A definition whose name is taken by a different schema (another
+    /// capability's type of the same name, or `MeshError`) is renamed
+    /// `<tag>_<name>`, numbered further if that is taken too.
+    fn hoist_definitions(
+        schema: &Value,
+        tag: &str,
+        schemas: &mut serde_json::Map<String, Value>,
+    ) -> Value {
+        let mut schema = schema.clone();
+        let definitions = match schema.as_object_mut() {
+            Some(obj) => {
+                obj.remove("$schema");
+                match obj.remove("definitions") {
+                    Some(Value::Object(definitions)) => definitions,
+                    _ => serde_json::Map::new(),
+                }
+            }
+            None => serde_json::Map::new(),
+        };
+        let tag: String = tag
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        // Renaming one definition changes the refs of those using it, which
+        // may then clash in turn - repeat until nothing new is renamed
+        let mut renames: HashMap<String, String> = HashMap::new();
+        loop {
+            let mut taken: Vec<String> = renames.values().cloned().collect();
+            let mut renamed = false;
+            for (name, definition) in &definitions {
+                if renames.contains_key(name) {
+                    continue;
+                }
+                let mut definition = definition.clone();
+                rewrite_refs(&mut definition, &renames);
+                if schemas
+                    .get(name)
+                    .is_some_and(|existing| *existing != definition)
+                {
+                    let mut candidate = format!("{}_{}", tag, name);
+                    let mut n = 2;
+                    while taken.contains(&candidate)
+                        || definitions.contains_key(&candidate)
+                        || schemas
+                            .get(&candidate)
+                            .is_some_and(|existing| *existing != definition)
+                    {
+                        candidate = format!("{}_{}_{}", tag, name, n);
+                        n += 1;
+                    }
+                    taken.push(candidate.clone());
+                    renames.insert(name.clone(), candidate);
+                    renamed = true;
+                }
+            }
+            if !renamed {
+                break;
+            }
+        }
+
+        for (name, mut definition) in definitions {
+            rewrite_refs(&mut definition, &renames);
+            let name = renames.get(&name).cloned().unwrap_or(name);
+            schemas.insert(name, definition);
+        }
+        rewrite_refs(&mut schema, &renames);
+        schema
+    }
+
+    fn rewrite_refs(value: &mut Value, renames: &HashMap<String, String>) {
+        match value {
+            Value::Object(obj) => {
+                for (key, child) in obj.iter_mut() {
+                    match child {
+                        Value::String(target) if key == "$ref" => {
+                            if let Some(name) = target.strip_prefix("#/definitions/") {
+                                let name = renames.get(name).map_or(name, String::as_str);
+                                *target = format!("#/components/schemas/{}", name);
+                            }
+                        }
+                        _ => rewrite_refs(child, renames),
+                    }
+                }
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|item| rewrite_refs(item, renames)),
+            _ => {}
+        }
+    }
+}
+
+// ============================================================================
+// WEBSOCKET TRANSPORT
+// ============================================================================
+
+/// `/ws`: signals, their results and pushed events multiplexed over one socket,
+/// as JSON text frames tagged by `type`.
+///
+/// Client -> cell: `signal {signal}` (answered by `result {id, result}` keyed by
+/// `Signal::id`, in completion order), `subscribe {topic}` / `unsubscribe {topic}`
+/// (`*` globs, e.g. `narrative/*` when [`CellConfig::narrative_events`] is on),
+/// and `ping {ts}`.
+/// Cell -> client: `hello {cellId, heartbeatMs}` on connect, `event {topic, data,
+/// timestamp}`, `lagged {missed}` when the client fell behind, `pong`, `error`.
+///
+/// Heartbeats: the cell sends a WebSocket ping every `heartbeatMs` and closes
+/// with 4000 once two intervals pass without hearing anything; browsers answer
+/// pings on their own. Clients should likewise treat two silent intervals as a
+/// dead socket. Shutdown closes with 1001.
+///
+/// Reconnects start a fresh session: subscribe again and resend unanswered
+/// signals with their original ids - completed ones are answered from the
+/// result cache instead of running twice. Events published while disconnected
+/// are not replayed.
+pub mod ws {
+    use super::*;
+    use axum::extract::ws::{CloseFrame, Message, WebSocket};
+    use futures::{SinkExt, StreamExt};
+
+    /// Close code for a connection that stopped answering heartbeats
+    pub const CLOSE_HEARTBEAT_TIMEOUT: u16 = 4000;
+    /// Close code when the cell shuts down
+    pub const CLOSE_GOING_AWAY: u16 = 1001;
+
+    /// Something published to subscribers (see [`RheoCell::publish`])
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Event {
+        pub topic: String,
+        pub data: Value,
+        pub timestamp: u64,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "camelCase")]
+    pub enum ClientFrame {
+        Signal {
+            signal: Box<Signal>,
+        },
+        Subscribe {
+            topic: String,
+        },
+        Unsubscribe {
+            topic: String,
+        },
+        Ping {
+            #[serde(default)]
+            ts: u64,
+        },
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "camelCase")]
+    pub enum ServerFrame {
+        Hello {
+            #[serde(rename = "cellId")]
+            cell_id: String,
+            #[serde(rename = "heartbeatMs")]
+            heartbeat_ms: u64,
+        },
+        Result {
+            id: String,
+            result: Box<TraceResult>,
+        },
+        Event(Event),
+        Subscribed {
+            topic: String,
+        },
+        Unsubscribed {
+            topic: String,
+        },
+        Lagged {
+            missed: u64,
+        },
+        Pong {
+            ts: u64,
+        },
+        Error {
+            message: String,
+        },
+    }
+
+    impl ServerFrame {
+        fn into_message(self) -> Message {
+            Message::Text(serde_json::to_string(&self).unwrap_or_default())
+        }
+    }
+
+    fn close(code: u16, reason: &'static str) -> Message {
+        Message::Close(Some(CloseFrame {
+            code,
+            reason: reason.into(),
+        }))
+    }
+
+    /// Run one connection until either side closes it
+    pub(crate) async fn serve(cell: Arc<RheoCell>, socket: WebSocket) {
+        let (mut sink, mut stream) = socket.split();
+        let (results_tx, mut results) = mpsc::channel::<ServerFrame>(256);
+        let mut events = cell.subscribe_events();
+        let mut topics: Vec<String> = Vec::new();
+
+        let heartbeat_every = Duration::from_millis(cell.config.ws_heartbeat_ms);
+        let mut heartbeat = interval(heartbeat_every);
+        heartbeat.tick().await;
+        let mut last_heard = Instant::now();
+
+        let hello = ServerFrame::Hello {
+            cell_id: cell.id.clone(),
+            heartbeat_ms: cell.config.ws_heartbeat_ms,
+        };
+        if sink.send(hello.into_message()).await.is_err() {
+            return;
+        }
+        debug!(cell_id = %cell.id, "WebSocket connected");
+
+        let mut goodbye = None;
+        loop {
+            let outgoing = tokio::select! {
+                _ = cell.cancel.cancelled() => {
+                    goodbye = Some(close(CLOSE_GOING_AWAY, "cell shutting down"));
+                    break;
+                }
+                incoming = stream.next() => {
+                    last_heard = Instant::now();
+                    let text = match incoming {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                        Some(Ok(_)) => continue, // pings, pongs, binary
+                    };
+                    match serde_json::from_str::<ClientFrame>(&text) {
+                        Ok(ClientFrame::Signal { signal }) => {
+                            let cell = Arc::clone(&cell);
+                            let results_tx = results_tx.clone();
+                            tokio::spawn(async move {
+                                let id = signal.id.clone();
+                                let result = cell.route_inbound(*signal).await;
+                                let result = Box::new(result);
+                                let _ = results_tx.send(ServerFrame::Result { id, result }).await;
+                            });
+                            continue;
+                        }
+                        Ok(ClientFrame::Subscribe { topic }) => {
+                            if !topics.contains(&topic) {
+                                topics.push(topic.clone());
+                            }
+                            ServerFrame::Subscribed { topic }
+                        }
+                        Ok(ClientFrame::Unsubscribe { topic }) => {
+                            topics.retain(|t| t != &topic);
+                            ServerFrame::Unsubscribed { topic }
+                        }
+                        Ok(ClientFrame::Ping { ts }) => ServerFrame::Pong { ts },
+                        Err(e) => ServerFrame::Error { message: format!("Bad frame: {}", e) },
+                    }
+                }
+                Some(frame) = results.recv() => frame,
+                event = events.recv() => match event {
+                    Ok(event) if topics.iter().any(|t| policy::glob(t, &event.topic)) => {
+                        ServerFrame::Event(event)
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => ServerFrame::Lagged { missed },
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = heartbeat.tick() => {
+                    if last_heard.elapsed() > heartbeat_every * 2 {
+                        debug!(cell_id = %cell.id, "WebSocket missed heartbeats");
+                        goodbye = Some(close(CLOSE_HEARTBEAT_TIMEOUT, "heartbeat timeout"));
+                        break;
+                    }
+                    if sink.send(Message::Ping(Vec::new())).await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            if sink.send(outgoing.into_message()).await.is_err() {
+                break;
+            }
+        }
+        // Finish the close handshake so the peer sees the code, not a reset
+        if let Some(goodbye) = goodbye {
+            if sink.send(goodbye).await.is_ok() {
+                let _ = timeout(Duration::from_secs(1), async {
+                    while let Some(Ok(message)) = stream.next().await {
+                        if matches!(message, Message::Close(_)) {
+                            break;
+                        }
+                    }
+                })
+                .await;
+            }
+        }
+        debug!(cell_id = %cell.id, "WebSocket closed");
+    }
+}
+
+// ============================================================================
+// NARRATIVE LEDGER
+// ============================================================================
+
+/// Port of `NarrativeLedger` from core.ts. Envelopes serialize in the same
+/// shape, so they travel on `MeshError::envelope` (`_envelope`) between Rust
+/// and TS cells. Signal snapshots stay raw JSON so TS envelopes round-trip untouched.
+pub mod narrative {
+    use super::*;
+    use base64::Engine as _;
+
+    const MAX_ANCESTRY_DEPTH: usize = 100;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct NarrativeEnvelope {
+        pub current: Value,
+        pub ancestry: Vec<AncestryEntry>,
+        #[serde(default)]
+        pub children: Vec<String>,
+        #[serde(default)]
+        pub timings: Vec<TimingEntry>,
+        #[serde(default)]
+        pub integrity: Vec<IntegrityEntry>,
+    }
+
+    impl NarrativeEnvelope {
+        pub fn signal_id(&self) -> Option<&str> {
+            self.current.get("id").and_then(Value::as_str)
+        }
+
+        fn hops(&self) -> u64 {
+            self.current
+                .get("_hops")
+                .and_then(Value::as_u64)
+                .unwrap_or(0)
+        }
+
+        fn last_activity(&self) -> u64 {
+            self.ancestry.iter().map(|a| a.timestamp).max().unwrap_or(0)
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AncestryEntry {
+        pub signal_id: String,
+        pub timestamp: u64,
+        pub cell_id: String,
+        pub cell_addr: String,
+        pub action: String,
+        /// The complete signal as it was at this moment
+        pub signal_snapshot: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub delta: Option<SignalDelta>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SignalDelta {
+        pub changed_fields: Vec<String>,
+        pub previous_values: serde_json::Map<String, Value>,
+        pub reason: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TimingEntry {
+        pub phase: String,
+        pub cell_id: String,
+        pub start_time: u64,
+        pub end_time: u64,
+        pub duration_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub blocking_on: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct IntegrityEntry {
+        pub timestamp: u64,
+        pub cell_id: String,
+        pub hash: String,
+        pub signature: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ExecutionPath {
+        pub signal_id: String,
+        pub total_steps: usize,
+        pub total_duration_ms: u64,
+        pub steps: Vec<ExecutionStep>,
+        pub children: Vec<String>,
+        pub final_state: Value,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ExecutionStep {
+        pub step_number: usize,
+        pub timestamp: u64,
+        pub cell_id: String,
+        pub cell_addr: String,
+        pub action: String,
+        pub signal_state: Value,
+        pub changes: Option<StepChanges>,
+        pub timing: Option<TimingEntry>,
+        pub integrity: Option<IntegrityEntry>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StepChanges {
+        pub fields: Vec<String>,
+        pub previous_values: serde_json::Map<String, Value>,
+        pub reason: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ForensicReport {
+        pub signal_id: String,
+        pub generated_at: u64,
+        pub summary: ReportSummary,
+        pub execution_path: ExecutionPath,
+        pub failure_analysis: FailureAnalysis,
+        pub timing_breakdown: TimingAnalysis,
+        pub integrity_check: IntegrityResult,
+        pub reproduction: Reproduction,
+        pub raw: RawReport,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ReportSummary {
+        pub total_hops: usize,
+        pub total_duration_ms: u64,
+        pub cells_visited: Vec<String>,
+        pub failure_point: Option<FailurePoint>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FailurePoint {
+        pub step: usize,
+        pub cell: String,
+        pub action: String,
+        pub timestamp: u64,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FailureAnalysis {
+        #[serde(rename = "type")]
+        pub kind: String,
+        pub description: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub details: Option<Value>,
+        pub likely_causes: Vec<String>,
+        pub recommendations: Vec<String>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TimingAnalysis {
+        pub total_time: u64,
+        pub by_phase: Vec<PhaseTiming>,
+        pub bottlenecks: Vec<TimingEntry>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PhaseTiming {
+        pub phase: String,
+        pub count: usize,
+        pub total_ms: u64,
+        pub avg_ms: f64,
+        pub max_ms: u64,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct IntegrityResult {
+        /// `VALID` or `COMPROMISED`
+        pub overall: String,
+        pub checks: Vec<IntegrityCheck>,
+        pub tampered_steps: Vec<usize>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct IntegrityCheck {
+        pub step: usize,
+        pub cell: String,
+        pub timestamp: u64,
+        pub hash_matches: bool,
+        pub claimed_hash: String,
+        pub computed_hash: String,
+        pub signature_valid: bool,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Reproduction {
+        pub can_replay: bool,
+        pub initial_signal: Option<Value>,
+        pub replay_script: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RawReport {
+        pub envelope: Value,
+        pub error_context: Option<Value>,
+    }
+
+    /// Append-only signal history. Every cell keeps a shard of the global narrative.
+    #[derive(Default)]
+    pub struct NarrativeLedger {
+        entries: DashMap<String, NarrativeEnvelope>,
+        cell_addrs: DashMap<String, String>,
+    }
+
+    impl NarrativeLedger {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Address reported for a cell in ancestry entries
+        pub fn register_cell(&self, cell_id: impl Into<String>, addr: impl Into<String>) {
+            self.cell_addrs.insert(cell_id.into(), addr.into());
+        }
+
+        pub fn get(&self, signal_id: &str) -> Option<NarrativeEnvelope> {
+            self.entries.get(signal_id).map(|e| e.clone())
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        /// Create or extend the envelope for a signal with a snapshot of it.
+        /// Mesh chatter (gossip, health) gets a one-step envelope that isn't kept.
+        pub fn wrap(
+            &self,
+            signal: &Signal,
+            cell_id: &str,
+            action: &str,
+            reason: Option<&str>,
+        ) -> NarrativeEnvelope {
+            let mut snapshot = serde_json::to_value(signal).unwrap_or_default();
+            // Gossiped atlas views would dwarf the rest of the snapshot
+            if let Some(fields) = snapshot.as_object_mut() {
+                fields.remove("atlas");
+            }
+            let timestamp = now_millis();
+
+            if signal.payload.capability.starts_with("mesh/") {
+                return NarrativeEnvelope {
+                    current: snapshot.clone(),
+                    ancestry: vec![AncestryEntry {
+                        signal_id: signal.id.clone(),
+                        timestamp,
+                        cell_id: cell_id.to_string(),
+                        cell_addr: self.cell_addr(cell_id),
+                        action: action.to_string(),
+                        signal_snapshot: snapshot.clone(),
+                        delta: None,
+                    }],
+                    children: Vec::new(),
+                    timings: Vec::new(),
+                    integrity: vec![compute_integrity(&snapshot, cell_id, timestamp)],
+                };
+            }
+
+            let mut envelope =
+                self.entries
+                    .entry(signal.id.clone())
+                    .or_insert_with(|| NarrativeEnvelope {
+                        current: snapshot.clone(),
+                        ancestry: Vec::new(),
+                        children: Vec::new(),
+                        timings: Vec::new(),
+                        integrity: Vec::new(),
+                    });
+
+            let delta = (!envelope.ancestry.is_empty())
+                .then(|| compute_delta(&envelope.current, &snapshot, reason));
+            envelope.ancestry.push(AncestryEntry {
+                signal_id: signal.id.clone(),
+                timestamp,
+                cell_id: cell_id.to_string(),
+                cell_addr: self.cell_addr(cell_id),
+                action: action.to_string(),
+                signal_snapshot: snapshot.clone(),
+                delta,
+            });
+            let overflow = envelope.ancestry.len().saturating_sub(MAX_ANCESTRY_DEPTH);
+            envelope.ancestry.drain(..overflow);
+            envelope
+                .integrity
+                .push(compute_integrity(&snapshot, cell_id, timestamp));
+            envelope.current = snapshot;
+            envelope.clone()
+        }
+
+        pub fn record_timing(
+            &self,
+            signal_id: &str,
+            phase: &str,
+            cell_id: &str,
+            start_time: u64,
+            end_time: u64,
+            blocking_on: Option<&str>,
+        ) {
+            if let Some(mut envelope) = self.entries.get_mut(signal_id) {
+                envelope.timings.push(TimingEntry {
+                    phase: phase.to_string(),
+                    cell_id: cell_id.to_string(),
+                    start_time,
+                    end_time,
+                    duration_ms: end_time.saturating_sub(start_time),
+                    blocking_on: blocking_on.map(str::to_string),
+                });
+            }
+        }
+
+        /// Child inherits the parent's ancestry plus a fork marker.
+        /// Returns None when the parent isn't in this ledger.
+        pub fn fork(
+            &self,
+            parent_id: &str,
+            child: &Signal,
+            reason: &str,
+        ) -> Option<NarrativeEnvelope> {
+            let ancestry = {
+                let mut parent = self.entries.get_mut(parent_id)?;
+                parent.children.push(child.id.clone());
+                parent.ancestry.clone()
+            };
+
+            let snapshot = serde_json::to_value(child).unwrap_or_default();
+            let timestamp = now_millis();
+            let mut previous_values = serde_json::Map::new();
+            previous_values.insert("parentId".into(), Value::String(parent_id.to_string()));
+
+            let mut ancestry = ancestry;
+            ancestry.push(AncestryEntry {
+                signal_id: child.id.clone(),
+                timestamp,
+                cell_id: "SYSTEM".into(),
+                cell_addr: "fork".into(),
+                action: format!("FORK_FROM_{}", parent_id),
+                signal_snapshot: snapshot.clone(),
+                delta: Some(SignalDelta {
+                    changed_fields: vec!["id".into(), "parentId".into()],
+                    previous_values,
+                    reason: reason.to_string(),
+                }),
+            });
+
+            let envelope = NarrativeEnvelope {
+                integrity: vec![compute_integrity(&snapshot, "FORK", timestamp)],
+                current: snapshot,
+                ancestry,
+                children: Vec::new(),
+                timings: Vec::new(),
+            };
+            self.entries.insert(child.id.clone(), envelope.clone());
+            Some(envelope)
+        }
+
+        pub fn reconstruct_execution_path(&self, signal_id: &str) -> Option<ExecutionPath> {
+            let envelope = self.get(signal_id)?;
+            let steps = envelope
+                .ancestry
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| ExecutionStep {
+                    step_number: i,
+                    timestamp: entry.timestamp,
+                    cell_id: entry.cell_id.clone(),
+                    cell_addr: entry.cell_addr.clone(),
+                    action: entry.action.clone(),
+                    signal_state: entry.signal_snapshot.clone(),
+                    changes: entry.delta.as_ref().map(|d| StepChanges {
+                        fields: d.changed_fields.clone(),
+                        previous_values: d.previous_values.clone(),
+                        reason: d.reason.clone(),
+                    }),
+                    timing: envelope
+                        .timings
+                        .iter()
+                        .find(|t| {
+                            t.phase == entry.action && t.start_time.abs_diff(entry.timestamp) < 100
+                        })
+                        .cloned(),
+                    integrity: envelope
+                        .integrity
+                        .iter()
+                        .find(|int| int.timestamp.abs_diff(entry.timestamp) < 100)
+                        .cloned(),
+                })
+                .collect::<Vec<_>>();
+
+            Some(ExecutionPath {
+                signal_id: signal_id.to_string(),
+                total_steps: steps.len(),
+                total_duration_ms: envelope.timings.iter().map(|t| t.duration_ms).sum(),
+                steps,
+                children: envelope.children.clone(),
+                final_state: envelope.current.clone(),
+            })
+        }
+
+        /// Everything known about a signal, with failure and timing analysis.
+        /// `error_context` follows core.ts: `{ failedAt, error: { message, ... } }`.
+        pub fn generate_forensic_report(
+            &self,
+            signal_id: &str,
+            error_context: Option<Value>,
+        ) -> Option<ForensicReport> {
+            let path = self.reconstruct_execution_path(signal_id)?;
+            let envelope = self.get(signal_id)?;
+
+            let failed_at = error_context
+                .as_ref()
+                .and_then(|c| c.get("failedAt"))
+                .and_then(Value::as_str);
+            // Rust errors name a component in `from`, not always a cell id
+            let failure_step = failed_at
+                .and_then(|cell| path.steps.iter().find(|s| s.cell_id == cell))
+                .or(path.steps.last());
+
+            let mut cells_visited: Vec<String> = Vec::new();
+            for step in &path.steps {
+                if !cells_visited.contains(&step.cell_id) {
+                    cells_visited.push(step.cell_id.clone());
+                }
+            }
+
+            Some(ForensicReport {
+                signal_id: signal_id.to_string(),
+                generated_at: now_millis(),
+                summary: ReportSummary {
+                    total_hops: path.total_steps,
+                    total_duration_ms: path.total_duration_ms,
+                    cells_visited,
+                    failure_point: failure_step.map(|s| FailurePoint {
+                        step: s.step_number,
+                        cell: s.cell_id.clone(),
+                        action: s.action.clone(),
+                        timestamp: s.timestamp,
+                    }),
+                },
+                failure_analysis: analyze_failure(&path, failure_step, error_context.as_ref()),
+                timing_breakdown: analyze_timings(&envelope.timings),
+                integrity_check: verify_integrity(&path),
+                reproduction: Reproduction {
+                    can_replay: true,
+                    initial_signal: path.steps.first().map(|s| s.signal_state.clone()),
+                    replay_script: replay_script(&path),
+                },
+                raw: RawReport {
+                    envelope: serde_json::json!({
+                        "signalId": envelope.signal_id(),
+                        "ancestryCount": envelope.ancestry.len(),
+                        "timingCount": envelope.timings.len(),
+                        "children": envelope.children,
+                    }),
+                    error_context,
+                },
+                execution_path: path,
+            })
+        }
+
+        /// Fold a remote envelope into ours, keeping every entry from both sides
+        pub fn merge(&self, remote: &NarrativeEnvelope) {
+            let Some(cid) = remote.signal_id() else {
+                return;
+            };
+
+            let mut local = match self.entries.get_mut(cid) {
+                Some(local) => local,
+                None => {
+                    self.entries.insert(cid.to_string(), remote.clone());
+                    return;
+                }
+            };
+
+            let key = |a: &AncestryEntry| format!("{}-{}-{}", a.timestamp, a.cell_id, a.action);
+            let mut seen: std::collections::HashSet<String> =
+                local.ancestry.iter().map(key).collect();
+            for entry in &remote.ancestry {
+                if seen.insert(key(entry)) {
+                    local.ancestry.push(entry.clone());
+                }
+            }
+            local.ancestry.sort_by_key(|a| a.timestamp);
+
+            let timing_key =
+                |t: &TimingEntry| format!("{}-{}-{}", t.phase, t.cell_id, t.start_time);
+            let timing_keys: std::collections::HashSet<String> =
+                local.timings.iter().map(timing_key).collect();
+            for timing in &remote.timings {
+                if !timing_keys.contains(&timing_key(timing)) {
+                    local.timings.push(timing.clone());
+                }
+            }
+
+            let hashes: std::collections::HashSet<String> =
+                local.integrity.iter().map(|i| i.hash.clone()).collect();
+            for integrity in &remote.integrity {
+                if !hashes.contains(&integrity.hash) {
+                    local.integrity.push(integrity.clone());
+                }
+            }
+
+            for child in &remote.children {
+                if !local.children.contains(child) {
+                    local.children.push(child.clone());
+                }
+            }
+
+            if remote.hops() > local.hops() {
+                local.current = remote.current.clone();
+            }
+        }
+
+        /// Drop envelopes with no activity for `max_age` (TS keeps them forever)
+        pub fn prune(&self, max_age: Duration) {
+            let cutoff = now_millis().saturating_sub(max_age.as_millis() as u64);
+            self.entries.retain(|_, e| e.last_activity() >= cutoff);
+        }
+
+        fn cell_addr(&self, cell_id: &str) -> String {
+            self.cell_addrs
+                .get(cell_id)
+                .map(|a| a.clone())
+                .unwrap_or_else(|| "unknown".to_string())
+        }
+    }
+
+    fn compute_delta(prev: &Value, curr: &Value, reason: Option<&str>) -> SignalDelta {
+        let mut changed_fields = Vec::new();
+        let mut previous_values = serde_json::Map::new();
+        if let Some(curr) = curr.as_object() {
+            for (key, value) in curr {
+                let before = prev.get(key);
+                if before != Some(value) {
+                    changed_fields.push(key.clone());
+                    previous_values.insert(key.clone(), before.cloned().unwrap_or(Value::Null));
+                }
+            }
+        }
+        SignalDelta {
+            changed_fields,
+            previous_values,
+            reason: reason.unwrap_or("unknown").to_string(),
+        }
+    }
+
+    fn compute_integrity(snapshot: &Value, cell_id: &str, timestamp: u64) -> IntegrityEntry {
+        let hash = compute_signal_hash(snapshot);
+        IntegrityEntry {
+            timestamp,
+            cell_id: cell_id.to_string(),
+            signature: sign_hash(&hash, cell_id),
+            hash,
+        }
+    }
+
+    /// Same scheme as core.ts: `JSON.stringify(signal, sortedTopLevelKeys)`,
+    /// base64, first 16 chars. A tamper hint, not a cryptographic hash.
+    pub fn compute_signal_hash(signal: &Value) -> String {
+        let mut keys: Vec<&String> = signal
+            .as_object()
+            .map(|o| o.keys().collect())
+            .unwrap_or_default();
+        keys.sort();
+        let mut canonical = String::new();
+        stringify_filtered(signal, &keys, &mut canonical);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(canonical.as_bytes());
+        format!("sha256:{}", &encoded[..encoded.len().min(16)])
+    }
+
+    fn sign_hash(hash: &str, cell_id: &str) -> String {
+        let prefix: String = hash.chars().take(8).collect();
+        format!("sig:{}:{}", cell_id, prefix)
+    }
+
+    /// JSON.stringify with a replacer array: the allowlist applies at every depth
+    fn stringify_filtered(value: &Value, keys: &[&String], out: &mut String) {
+        match value {
+            Value::Object(map) => {
+                out.push('{');
+                let mut first = true;
+                for key in keys {
+                    if let Some(v) = map.get(key.as_str()) {
+                        if !first {
+                            out.push(',');
+                        }
+                        first = false;
+                        out.push_str(&Value::String(key.to_string()).to_string());
+                        out.push(':');
+                        stringify_filtered(v, keys, out);
+                    }
+                }
+                out.push('}');
+            }
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    stringify_filtered(item, keys, out);
+                }
+                out.push(']');
+            }
+            // JS prints integral floats without a fraction
+            Value::Number(n) => match n.as_f64() {
+                Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 1e21 => {
+                    out.push_str(&(f as i64).to_string())
+                }
+                _ => out.push_str(&n.to_string()),
+            },
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    fn analyze_failure(
+        path: &ExecutionPath,
+        failure_step: Option<&ExecutionStep>,
+        error_context: Option<&Value>,
+    ) -> FailureAnalysis {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let Some(step) = failure_step else {
+            return FailureAnalysis {
+                kind: "UNKNOWN".into(),
+                description: "Could not identify failure point".into(),
+                details: None,
+                likely_causes: strings(&[
+                    "Signal completed without error",
+                    "Error occurred after last logged step",
+                ]),
+                recommendations: strings(&["Check cell logs for unhandled exceptions"]),
+            };
+        };
+        let capability = step.signal_state.pointer("/payload/capability").cloned();
+        let capability_name = capability
+            .as_ref()
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+
+        match step.action.as_str() {
+            "RPC_ATTEMPT" => {
+                return FailureAnalysis {
+                    kind: "NETWORK_FAILURE".into(),
+                    description: format!("Failed to reach {}", step.cell_addr),
+                    details: Some(serde_json::json!({
+                        "targetCell": step.cell_id,
+                        "targetAddr": step.cell_addr,
+                        "payloadSize": step.signal_state.get("payload").map(|p| p.to_string().len()),
+                    })),
+                    likely_causes: strings(&[
+                        "Target cell crashed or is unreachable",
+                        "Network partition between cells",
+                        "Target cell overloaded (circuit breaker open)",
+                        "DNS resolution failure for target address",
+                    ]),
+                    recommendations: vec![
+                        format!(
+                            "Check if cell {} is running: mesh/ping → {}",
+                            step.cell_id, step.cell_id
+                        ),
+                        format!("Verify network path: traceroute to {}", step.cell_addr),
+                        "Check target cell logs for crash reports".into(),
+                        format!("Review circuit breaker state for {}", step.cell_id),
+                    ],
+                };
+            }
+            "LOCAL_HANDLER" | "HANDLER_EXCEPTION" => {
+                let handler_error = error_context.and_then(|c| c.get("error"));
+                return FailureAnalysis {
+                    kind: "HANDLER_EXCEPTION".into(),
+                    description: format!("Capability handler threw exception in {}", step.cell_id),
+                    details: Some(serde_json::json!({
+                        "capability": capability,
+                        "handlerCell": step.cell_id,
+                        "errorMessage": handler_error.and_then(|e| e.get("message")),
+                        "errorStack": handler_error.and_then(|e| e.get("stack")),
+                    })),
+                    likely_causes: strings(&[
+                        "Handler implementation bug",
+                        "Missing required arguments in payload",
+                        "Downstream dependency failure",
+                        "State corruption in handler cell",
+                    ]),
+                    recommendations: vec![
+                        format!("Review handler code in {}", step.cell_id),
+                        format!("Validate input schema for {}", capability_name),
+                        "Check downstream service health".into(),
+                        format!("Review recent changes to {} handler", step.cell_id),
+                    ],
+                };
+            }
+            "RECEIVED_SIGNAL" => {
+                let previous_visits: Vec<u64> = path.steps[..step.step_number]
+                    .iter()
+                    .filter(|s| s.cell_id == step.cell_id)
+                    .map(|s| s.timestamp)
+                    .collect();
+                if !previous_visits.is_empty() {
+                    return FailureAnalysis {
+                        kind: "ROUTING_LOOP".into(),
+                        description: format!(
+                            "Signal visited {} {} times",
+                            step.cell_id,
+                            previous_visits.len() + 1
+                        ),
+                        details: Some(serde_json::json!({
+                            "loopCell": step.cell_id,
+                            "previousVisits": previous_visits,
+                            "loopDepth": previous_visits.len(),
+                        })),
+                        likely_causes: strings(&[
+                            "Stale atlas entry pointing to wrong address",
+                            "Cell forwarding logic error (forwarding to self)",
+                            "Circular capability chain (A→B→C→A)",
+                            "Signal ID collision (extremely unlikely)",
+                        ]),
+                        recommendations: vec![
+                            format!("Force atlas refresh: POST {}/atlas", step.cell_addr),
+                            format!(
+                                "Check {} route() implementation for self-forwarding",
+                                step.cell_id
+                            ),
+                            "Review capability chain for cycles".into(),
+                            "Verify signal ID generation is using Uuid::new_v4()".into(),
+                        ],
+                    };
+                }
+            }
+            _ => {}
+        }
+
+        let previous = step
+            .step_number
+            .checked_sub(1)
+            .and_then(|i| path.steps.get(i));
+        FailureAnalysis {
+            kind: "UNEXPECTED_FAILURE".into(),
+            description: format!("Failure during {} in {}", step.action, step.cell_id),
+            details: Some(serde_json::json!({
+                "lastKnownGoodStep": previous.map(|p| serde_json::json!({
+                    "cell": p.cell_id,
+                    "action": p.action,
+                    "timestamp": p.timestamp,
+                })),
+                "failedStep": {
+                    "cell": step.cell_id,
+                    "action": step.action,
+                    "timestamp": step.timestamp,
+                    "signalState": step.signal_state,
+                },
+            })),
+            likely_causes: strings(&["Unknown - requires manual investigation"]),
+            recommendations: strings(&[
+                "Review complete execution path below",
+                "Check cell logs for unhandled exceptions",
+            ]),
+        }
+    }
+
+    fn analyze_timings(timings: &[TimingEntry]) -> TimingAnalysis {
+        let mut by_phase: Vec<PhaseTiming> = Vec::new();
+        for t in timings {
+            match by_phase.iter_mut().find(|p| p.phase == t.phase) {
+                Some(p) => {
+                    p.count += 1;
+                    p.total_ms += t.duration_ms;
+                    p.max_ms = p.max_ms.max(t.duration_ms);
+                }
+                None => by_phase.push(PhaseTiming {
+                    phase: t.phase.clone(),
+                    count: 1,
+                    total_ms: t.duration_ms,
+                    avg_ms: 0.0,
+                    max_ms: t.duration_ms,
+                }),
+            }
+        }
+        for p in &mut by_phase {
+            p.avg_ms = p.total_ms as f64 / p.count as f64;
+        }
+
+        let mut bottlenecks: Vec<TimingEntry> = timings
+            .iter()
+            .filter(|t| t.duration_ms > 1000)
+            .cloned()
+            .collect();
+        bottlenecks.sort_by_key(|t| std::cmp::Reverse(t.duration_ms));
+        bottlenecks.truncate(5);
+
+        TimingAnalysis {
+            total_time: timings.iter().map(|t| t.duration_ms).sum(),
+            by_phase,
+            bottlenecks,
+        }
+    }
+
+    fn verify_integrity(path: &ExecutionPath) -> IntegrityResult {
+        let checks: Vec<IntegrityCheck> = path
+            .steps
+            .iter()
+            .filter_map(|step| {
+                let integrity = step.integrity.as_ref()?;
+                let computed_hash = compute_signal_hash(&step.signal_state);
+                Some(IntegrityCheck {
+                    step: step.step_number,
+                    cell: step.cell_id.clone(),
+                    timestamp: integrity.timestamp,
+                    hash_matches: computed_hash == integrity.hash,
+                    claimed_hash: integrity.hash.clone(),
+                    computed_hash,
+                    signature_valid: integrity
+                        .signature
+                        .starts_with(&format!("sig:{}:", integrity.cell_id)),
+                })
+            })
+            .collect();
+
+        let tampered_steps: Vec<usize> = checks
+            .iter()
+            .filter(|c| !c.hash_matches || !c.signature_valid)
+            .map(|c| c.step)
+            .collect();
+        IntegrityResult {
+            overall: if tampered_steps.is_empty() {
+                "VALID"
+            } else {
+                "COMPROMISED"
+            }
+            .into(),
+            checks,
+            tampered_steps,
+        }
+    }
+
+    fn replay_script(path: &ExecutionPath) -> String {
+        let pretty = |v: Option<&Value>| {
+            v.map(|v| serde_json::to_string_pretty(v).unwrap_or_default())
+                .unwrap_or_else(|| "undefined".into())
+        };
+        let mut lines = vec![
+            "// Auto-generated replay script".to_string(),
+            format!(
+                "const initialSignal = {};",
+                pretty(path.steps.first().map(|s| &s.signal_state))
+            ),
+            String::new(),
+            "// Replay each hop".to_string(),
+        ];
+        for (i, step) in path.steps.iter().enumerate().skip(1) {
+            lines.push(format!("// Step {}: {} @ {}", i, step.action, step.cell_id));
+            lines.push(format!(
+                "await simulateHop({{\n    cell: \"{}\",\n    action: \"{}\",\n    inputSignal: {},\n    expectedChanges: {}\n}});",
+                step.cell_id,
+                step.action,
+                step.signal_state,
+                step.changes
+                    .as_ref()
+                    .map(|c| serde_json::json!(c.fields).to_string())
+                    .unwrap_or_else(|| "undefined".into())
+            ));
+        }
+        lines.join("\n")
+    }
+
+    impl ForensicReport {
+        /// Human-readable rendering for logs
+        pub fn render(&self) -> String {
+            let rule = "╠══════════════════════════════════════════════════════════════════╣";
+            let mut lines = vec![
+                "╔══════════════════════════════════════════════════════════════════╗".to_string(),
+                format!("║ NARRATIVE REPORT: {}", self.signal_id),
+                rule.to_string(),
+                format!(
+                    "║ {} hops, {}ms, cells: {}",
+                    self.summary.total_hops,
+                    self.summary.total_duration_ms,
+                    self.summary.cells_visited.join(" → ")
+                ),
+            ];
+            if let Some(point) = &self.summary.failure_point {
+                lines.push(format!(
+                    "║ Failed at step {}: {} @ {}",
+                    point.step, point.action, point.cell
+                ));
+            }
+
+            lines.push(rule.to_string());
+            lines.push(format!(
+                "║ ANALYSIS: {} - {}",
+                self.failure_analysis.kind, self.failure_analysis.description
+            ));
+            for cause in &self.failure_analysis.likely_causes {
+                lines.push(format!("║   cause: {}", cause));
+            }
+            for rec in &self.failure_analysis.recommendations {
+                lines.push(format!("║   try: {}", rec));
+            }
+
+            lines.push(rule.to_string());
+            lines.push(format!(
+                "║ EXECUTION PATH ({} steps):",
+                self.execution_path.total_steps
+            ));
+            for step in &self.execution_path.steps {
+                let changes = step
+                    .changes
+                    .as_ref()
+                    .map(|c| format!(" [{}: {}]", c.reason, c.fields.join(", ")))
+                    .unwrap_or_default();
+                lines.push(format!(
+                    "║ {}. {} @ {}{}",
+                    step.step_number, step.action, step.cell_id, changes
+                ));
+            }
+
+            if !self.timing_breakdown.by_phase.is_empty() {
+                lines.push(rule.to_string());
+                lines.push(format!(
+                    "║ TIMING ({}ms total):",
+                    self.timing_breakdown.total_time
+                ));
+                for phase in &self.timing_breakdown.by_phase {
+                    lines.push(format!(
+                        "║   {}: {}x avg {:.1}ms max {}ms",
+                        phase.phase, phase.count, phase.avg_ms, phase.max_ms
+                    ));
+                }
+            }
+
+            lines.push(rule.to_string());
+            lines.push(format!(
+                "║ INTEGRITY: {} ({} checks)",
+                self.integrity_check.overall,
+                self.integrity_check.checks.len()
+            ));
+            lines.push(
+                "╚══════════════════════════════════════════════════════════════════╝".to_string(),
+            );
+            lines.join("\n")
+        }
+    }
+
+    /// Action of the step closing a history (see [`Signal::sealed_steps`])
+    pub const SEALED: &str = "SEALED";
+
+    /// What is wrong with a step, from the verifier's point of view
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    pub enum StepIssueKind {
+        /// Recorded by a cell that doesn't sign (e.g. an older TS cell)
+        Unsigned,
+        /// Unsigned, though the recording cell has a key - its signature was stripped
+        Stripped,
+        /// Signed steps that don't end in a seal counting them: the tail was cut
+        Truncated,
+        /// No public key for the recording cell in the atlas
+        UnknownSigner,
+        BadSignature,
+        /// Step content was changed after signing
+        HashMismatch,
+        /// A step before this one was removed, inserted or reordered
+        BrokenChain,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StepIssue {
+        pub index: usize,
+        pub cell: String,
+        pub action: String,
+        pub kind: StepIssueKind,
+    }
+
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StepVerification {
+        pub steps: usize,
+        pub verified: usize,
+        pub issues: Vec<StepIssue>,
+    }
+
+    impl StepVerification {
+        /// No forgeries or gaps; unsigned steps are tolerated
+        pub fn is_intact(&self) -> bool {
+            self.issues
+                .iter()
+                .all(|i| i.kind == StepIssueKind::Unsigned)
+        }
+    }
+
+    /// Check each step's chain link, hash and signature. `pub_key_for` maps a
+    /// cell id to its hex Ed25519 public key (usually from the atlas).
+    pub fn verify_steps<F>(steps: &[NarrativeStep], pub_key_for: F) -> StepVerification
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut result = StepVerification {
+            steps: steps.len(),
+            ..Default::default()
+        };
+
+        // A history with signed steps must end in its seal, counting the rest
+        let sealed = steps.last().is_some_and(|last| {
+            last.action == SEALED
+                && last.is_signed()
+                && last.data.as_ref().and_then(|d| d["steps"].as_u64())
+                    == Some(steps.len() as u64 - 1)
+        });
+        if !sealed && steps.iter().any(NarrativeStep::is_signed) {
+            let last = steps.last().expect("signed steps exist");
+            result.issues.push(StepIssue {
+                index: steps.len(),
+                cell: last.cell.clone(),
+                action: last.action.clone(),
+                kind: StepIssueKind::Truncated,
+            });
+        }
+
+        for (index, step) in steps.iter().enumerate() {
+            let kind = match check_step(step, index.checked_sub(1).map(|i| &steps[i]), &pub_key_for)
+            {
+                None => {
+                    result.verified += 1;
+                    continue;
+                }
+                Some(kind) => kind,
+            };
+            result.issues.push(StepIssue {
+                index,
+                cell: step.cell.clone(),
+                action: step.action.clone(),
+                kind,
+            });
+        }
+        result
+    }
+
+    fn check_step<F>(
+        step: &NarrativeStep,
+        previous: Option<&NarrativeStep>,
+        pub_key_for: &F,
+    ) -> Option<StepIssueKind>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let (Some(hash), Some(signature)) = (&step.hash, &step.signature) else {
+            return Some(match pub_key_for(&step.cell) {
+                Some(_) => StepIssueKind::Stripped,
+                None => StepIssueKind::Unsigned,
+            });
+        };
+        if step.prev_hash != previous.map(NarrativeStep::digest) {
+            return Some(StepIssueKind::BrokenChain);
+        }
+        if *hash != step.digest() {
+            return Some(StepIssueKind::HashMismatch);
+        }
+
+        let Some(key) = pub_key_for(&step.cell)
+            .and_then(|k| hex::decode(k).ok())
+            .and_then(|k| <[u8; 32]>::try_from(k).ok())
+            .and_then(|k| VerifyingKey::from_bytes(&k).ok())
+        else {
+            return Some(StepIssueKind::UnknownSigner);
+        };
+        let valid = hex::decode(signature)
+            .ok()
+            .and_then(|s| Signature::from_slice(&s).ok())
+            .map(|s| key.verify(hash.as_bytes(), &s).is_ok())
+            .unwrap_or(false);
+        (!valid).then_some(StepIssueKind::BadSignature)
+    }
+
+    /// `_envelope` from a foreign cell must never make the whole error unreadable
+    pub(crate) fn deserialize_lenient<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<Box<NarrativeEnvelope>>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = Option::<Value>::deserialize(deserializer)?;
+        Ok(value.and_then(|v| serde_json::from_value(v).ok()))
+    }
+}
+
+// ============================================================================
+// SIGNAL JOURNAL & REPLAY
+// ============================================================================
+
+/// Opt-in recorder of routed signals and their results as rotating JSONL,
+/// plus the helpers `rheo-replay` uses to re-send and diff them.
+pub mod journal {
+    use super::*;
+    use std::{
+        fs::{self, File, OpenOptions},
+        io::{BufRead, BufReader, BufWriter, Write},
+        path::Path,
+    };
+
+    /// One line of the journal
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct JournalRecord {
+        pub recorded_at: u64,
+        pub cell: String,
+        /// The signal as it arrived, before this cell touched it
+        pub signal: Signal,
+        pub result: TraceResult,
+        pub duration_ms: u64,
+    }
+
+    struct Writer {
+        file: BufWriter<File>,
+        bytes: u64,
+    }
+
+    enum Command {
+        Record(Box<JournalRecord>),
+        /// Answered once everything queued before it is on disk
+        Flush(std::sync::mpsc::Sender<()>),
+    }
+
+    /// Size-rotated JSONL file: `<cell>.jsonl`, `<cell>.1.jsonl`, ...
+    ///
+    /// Records are written by a dedicated thread, so routing never waits on
+    /// the disk.
+    pub struct Journal {
+        path: PathBuf,
+        commands: std::sync::mpsc::Sender<Command>,
+    }
+
+    impl Journal {
+        pub fn open(
+            dir: impl Into<PathBuf>,
+            name: impl Into<String>,
+            max_bytes: u64,
+            max_files: usize,
+        ) -> std::io::Result<Self> {
+            let mut files = Files {
+                dir: dir.into(),
+                name: name.into(),
+                max_bytes,
+                max_files: max_files.max(1),
+                writer: None,
+            };
+            fs::create_dir_all(&files.dir)?;
+            files.writer = Some(files.open_writer()?);
+            let path = files.rotated_path(0);
+
+            let (commands, queue) = std::sync::mpsc::channel();
+            std::thread::Builder::new()
+                .name("rheo-journal".into())
+                .spawn(move || files.run(queue))?;
+            Ok(Self { path, commands })
+        }
+
+        /// Path of the file currently being written
+        pub fn path(&self) -> PathBuf {
+            self.path.clone()
+        }
+
+        pub fn record(
+            &self,
+            cell: &str,
+            signal: &Signal,
+            result: &TraceResult,
+            duration: Duration,
+        ) {
+            let record = JournalRecord {
+                recorded_at: now_millis(),
+                cell: cell.to_string(),
+                signal: signal.clone(),
+                result: result.clone(),
+                duration_ms: duration.as_millis() as u64,
+            };
+            if self
+                .commands
+                .send(Command::Record(Box::new(record)))
+                .is_err()
+            {
+                warn!(journal = %self.path.display(), "Journal writer stopped, record dropped");
+            }
+        }
+
+        /// Block until every record so far is written
+        pub fn flush(&self) {
+            let (done, written) = std::sync::mpsc::channel();
+            if self.commands.send(Command::Flush(done)).is_ok() {
+                let _ = written.recv();
+            }
+        }
+    }
+
+    /// The writer thread's side of a [`Journal`]
+    struct Files {
+        dir: PathBuf,
+        name: String,
+        max_bytes: u64,
+        max_files: usize,
+        writer: Option<Writer>,
+    }
+
+    impl Files {
+        /// Write until every [`Journal`] handle is gone
+        fn run(mut self, queue: std::sync::mpsc::Receiver<Command>) {
+            for command in queue {
+                match command {
+                    Command::Record(record) => self.write(&record),
+                    Command::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        }
+
+        fn write(&mut self, record: &JournalRecord) {
+            let mut line = match serde_json::to_string(record) {
+                Ok(l) => l,
+                Err(e) => {
+                    warn!(error = %e, "Failed to serialize journal record");
+                    return;
+                }
+            };
+            line.push('\n');
+            if let Err(e) = self.write_line(line.as_bytes()) {
+                warn!(error = %e, journal = %self.rotated_path(0).display(), "Journal write failed");
+            }
+        }
+
+        fn write_line(&mut self, line: &[u8]) -> std::io::Result<()> {
+            let needs_rotation = self
+                .writer
+                .as_ref()
+                .map(|w| w.bytes > 0 && w.bytes + line.len() as u64 > self.max_bytes)
+                .unwrap_or(true);
+            if needs_rotation {
+                if let Some(mut old) = self.writer.take() {
+                    old.file.flush()?;
+                    self.rotate()?;
+                }
+                self.writer = Some(self.open_writer()?);
+            }
+
+            let writer = self.writer.as_mut().expect("journal writer present");
+            writer.file.write_all(line)?;
+            // Flush per record: the journal matters most right before a crash
+            writer.file.flush()?;
+            writer.bytes += line.len() as u64;
+            Ok(())
+        }
+
+        fn rotate(&self) -> std::io::Result<()> {
+            let _ = fs::remove_file(self.rotated_path(self.max_files - 1));
+            for i in (0..self.max_files - 1).rev() {
+                let from = self.rotated_path(i);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(i + 1))?;
+                }
+            }
+            Ok(())
+        }
+
+        fn open_writer(&self) -> std::io::Result<Writer> {
+            let path = self.rotated_path(0);
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let bytes = file.metadata()?.len();
+            Ok(Writer {
+                file: BufWriter::new(file),
+                bytes,
+            })
+        }
+
+        fn rotated_path(&self, index: usize) -> PathBuf {
+            if index == 0 {
+                self.dir.join(format!("{}.jsonl", self.name))
+            } else {
+                self.dir.join(format!("{}.{}.jsonl", self.name, index))
+            }
+        }
+    }
+
+    /// Read every record from a journal file, skipping lines that don't parse
+    pub fn read(path: impl AsRef<Path>) -> std::io::Result<Vec<JournalRecord>> {
+        let reader = BufReader::new(File::open(path.as_ref())?);
+        let mut records = Vec::new();
+        for (n, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => warn!(line = n + 1, error = %e, "Skipping unreadable journal line"),
+            }
+        }
+        Ok(records)
+    }
+
+    /// How a recorded signal is adjusted before it is sent again
+    #[derive(Debug, Clone, Default)]
+    pub struct ReplayOptions {
+        /// Give the signal a new id so the target's dedupe doesn't swallow it
+        pub fresh_ids: bool,
+        /// Fixed deadline from now; otherwise the recorded budget is kept
+        pub deadline: Option<Duration>,
+    }
+
+    impl JournalRecord {
+        /// The recorded signal, made sendable again: routing state cleared,
+        /// deadline moved to now, and optionally a new id.
+        pub fn replay_signal(&self, options: &ReplayOptions) -> Signal {
+            let mut signal = self.signal.clone();
+            if options.fresh_ids {
+                signal
+                    .extensions
+                    .insert("_replayOf".into(), Value::String(signal.id.clone()));
+                signal.id = Uuid::new_v4().to_string();
+            }
+
+            let budget = options.deadline.or_else(|| {
+                signal.deadline_ms.map(|d| {
+                    Duration::from_millis(
+                        d.saturating_sub(self.recorded_at.saturating_sub(self.duration_ms)),
+                    )
+                })
+            });
+            signal.deadline_ms = budget.map(|b| now_millis() + b.as_millis() as u64);
+
+            signal.visited_cell_ids.clear();
+            signal.visited_addrs.clear();
+            signal.hops = 0;
+            signal.flood_attempted = false;
+            signal.registry_scanned = false;
+            signal
+        }
+    }
+
+    /// Differences between a recorded and a replayed result, as `path: old -> new`.
+    /// Latency and the correlation id are expected to change and are ignored.
+    pub fn diff_results(recorded: &TraceResult, replayed: &TraceResult) -> Vec<String> {
+        let normalize = |r: &TraceResult| {
+            let mut v = serde_json::to_value(r).unwrap_or_default();
+            if let Some(obj) = v.as_object_mut() {
+                obj.remove("cid");
+                obj.remove("latencyMicros");
+            }
+            if let Some(error) = v.get_mut("error").and_then(Value::as_object_mut) {
+                // Provenance differs on every run
+                for key in [
+                    "timestamp",
+                    "trace",
+                    "history",
+                    "children",
+                    "_envelope",
+                    "integrity",
+                ] {
+                    error.remove(key);
+                }
+            }
+            v
+        };
+        let mut diffs = Vec::new();
+        diff_values("", &normalize(recorded), &normalize(replayed), &mut diffs);
+        diffs
+    }
+
+    fn diff_values(path: &str, old: &Value, new: &Value, diffs: &mut Vec<String>) {
+        match (old, new) {
+            (Value::Object(a), Value::Object(b)) => {
+                let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+                keys.sort();
+                keys.dedup();
+                for key in keys {
+                    let child = format!("{}/{}", path, key);
+                    diff_values(
+                        &child,
+                        a.get(key).unwrap_or(&Value::Null),
+                        b.get(key).unwrap_or(&Value::Null),
+                        diffs,
+                    );
+                }
+            }
+            (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+                for (i, (x, y)) in a.iter().zip(b).enumerate() {
+                    diff_values(&format!("{}/{}", path, i), x, y, diffs);
+                }
+            }
+            _ if old != new => diffs.push(format!(
+                "{}: {} -> {}",
+                if path.is_empty() { "/" } else { path },
+                old,
+                new
+            )),
+            _ => {}
+        }
+    }
+}
+
+// ============================================================================
+// TRACE CONTEXT & SPAN EXPORT
+// ============================================================================
+
+/// W3C trace-context propagation for mesh hops, and a `tracing` layer that
+/// exports the resulting spans as OTLP/JSON (to a collector or a file).
+pub mod telemetry {
+    use super::*;
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider,
+    };
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+    use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+    use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+    use opentelemetry_sdk::{
+        error::{OTelSdkError, OTelSdkResult},
+        trace::{SdkTracerProvider, SpanData},
+        Resource,
+    };
+    use std::io::Write;
+    use tracing::Subscriber;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::{filter::filter_fn, registry::LookupSpan, Layer};
+
+    pub const TRACEPARENT_HEADER: &str = "traceparent";
+    /// Signal extension carrying the sender's context for peers that drop headers
+    pub const TRACEPARENT_EXTENSION: &str = "_traceparent";
+
+    /// A position in a distributed trace, as carried by a `traceparent` header
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TraceContext {
+        pub trace_id: String,
+        pub span_id: String,
+        pub sampled: bool,
+    }
+
+    impl TraceContext {
+        pub fn root() -> Self {
+            Self {
+                trace_id: random_hex(16),
+                span_id: random_hex(8),
+                sampled: true,
+            }
+        }
+
+        /// A new span in the same trace
+        pub fn child(&self) -> Self {
+            Self {
+                trace_id: self.trace_id.clone(),
+                span_id: random_hex(8),
+                sampled: self.sampled,
+            }
+        }
+
+        /// Parse `00-<trace-id>-<span-id>-<flags>`
+        pub fn parse(header: &str) -> Option<Self> {
+            let mut parts = header.trim().split('-');
+            let (version, trace_id, span_id, flags) =
+                (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+            let is_hex = |s: &str, len: usize| {
+                s.len() == len
+                    && s.bytes().all(|b| b.is_ascii_hexdigit())
+                    && s.bytes().any(|b| b != b'0')
+            };
+            if version.len() != 2
+                || version == "ff"
+                || !is_hex(trace_id, 32)
+                || !is_hex(span_id, 16)
+            {
+                return None;
+            }
+            let flags = u8::from_str_radix(flags, 16).ok()?;
+            Some(Self {
+                trace_id: trace_id.to_ascii_lowercase(),
+                span_id: span_id.to_ascii_lowercase(),
+                sampled: flags & 1 == 1,
+            })
+        }
+
+        pub fn to_traceparent(&self) -> String {
+            format!(
+                "00-{}-{}-{:02x}",
+                self.trace_id, self.span_id, self.sampled as u8
+            )
+        }
+
+        pub fn from_signal(signal: &Signal) -> Option<Self> {
+            signal
+                .extensions
+                .get(TRACEPARENT_EXTENSION)
+                .and_then(Value::as_str)
+                .and_then(Self::parse)
+        }
+
+        pub fn inject(&self, signal: &mut Signal) {
+            signal.extensions.insert(
+                TRACEPARENT_EXTENSION.to_string(),
+                Value::String(self.to_traceparent()),
+            );
+        }
+
+        /// This context as the remote parent of an OpenTelemetry span
+        fn to_otel(&self) -> opentelemetry::Context {
+            let flags = if self.sampled {
+                TraceFlags::SAMPLED
+            } else {
+                TraceFlags::default()
+            };
+            opentelemetry::Context::new().with_remote_span_context(SpanContext::new(
+                TraceId::from_hex(&self.trace_id).unwrap_or(TraceId::INVALID),
+                SpanId::from_hex(&self.span_id).unwrap_or(SpanId::INVALID),
+                flags,
+                true,
+                TraceState::default(),
+            ))
+        }
+
+        /// The ids the exporter gave `span`, if one is installed
+        fn of_span(span: &tracing::Span) -> Option<Self> {
+            let otel = span.context();
+            let ctx = otel.span().span_context().clone();
+            ctx.is_valid().then(|| Self {
+                trace_id: ctx.trace_id().to_string(),
+                span_id: ctx.span_id().to_string(),
+                sampled: ctx.is_sampled(),
+            })
+        }
+    }
+
+    fn random_hex(bytes: usize) -> String {
+        let mut buf = vec![0u8; bytes];
+        rand::RngCore::fill_bytes(&mut OsRng, &mut buf);
+        hex::encode(buf)
+    }
+
+    /// The mesh hops that get their own span
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Hop {
+        /// A cell receiving a signal (server span)
+        Route,
+        /// Choosing and trying peers for a capability (internal span)
+        Forward,
+        /// One HTTP call to a peer (client span)
+        Rpc,
+    }
+
+    /// Open a span for the next hop of `signal`: a child of the context it
+    /// carries (or a new trace), which is then written back into the signal.
+    pub fn hop_span(signal: &mut Signal, hop: Hop, cell: &str) -> tracing::Span {
+        let parent = TraceContext::from_signal(signal);
+        let parent_span_id = parent.as_ref().map(|p| p.span_id.as_str()).unwrap_or("");
+
+        // ERROR so the span survives any level filter - the level of the
+        // events inside decides what gets printed
+        macro_rules! span {
+            ($name:literal, $kind:literal) => {
+                tracing::error_span!(
+                    $name,
+                    otel.kind = $kind,
+                    otel.status_code = tracing::field::Empty,
+                    error.code = tracing::field::Empty,
+                    peer = tracing::field::Empty,
+                    trace_id = tracing::field::Empty,
+                    span_id = tracing::field::Empty,
+                    parent_span_id = parent_span_id,
+                    cid = %signal.id,
+                    capability = %signal.payload.capability,
+                    cell = cell,
+                )
+            };
+        }
+        let span = match hop {
+            Hop::Route => span!("mesh.route", "server"),
+            Hop::Forward => span!("mesh.forward", "internal"),
+            Hop::Rpc => span!("mesh.rpc", "client"),
+        };
+
+        // With an exporter installed its ids are the ones peers must see
+        if let Some(parent) = &parent {
+            let _ = span.set_parent(parent.to_otel());
+        }
+        let ctx = TraceContext::of_span(&span).unwrap_or_else(|| {
+            parent
+                .as_ref()
+                .map(TraceContext::child)
+                .unwrap_or_else(TraceContext::root)
+        });
+        span.record("trace_id", ctx.trace_id.as_str())
+            .record("span_id", ctx.span_id.as_str());
+        ctx.inject(signal);
+        span
+    }
+
+    /// Mark the span failed when the hop's result is
+    pub fn record_result(span: &tracing::Span, result: &TraceResult) {
+        match &result.error {
+            None => span.record("otel.status_code", "OK"),
+            Some(error) => span
+                .record("otel.status_code", "ERROR")
+                .record("error.code", error.code.to_string().as_str()),
+        };
+    }
+
+    // ------------------------------------------------------------------------
+    // Export
+    // ------------------------------------------------------------------------
+
+    /// Where finished spans go
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum TraceExport {
+        /// OTLP/HTTP JSON collector, e.g. `http://localhost:4318`
+        Otlp(String),
+        /// One OTLP/JSON request per line, appended to a local file
+        File(PathBuf),
+    }
+
+    impl TraceExport {
+        /// `RHEO_TRACE_EXPORT` (`file:<path>` or a collector URL), falling back
+        /// to the standard `OTEL_EXPORTER_OTLP_ENDPOINT`
+        pub fn from_env() -> Option<Self> {
+            if let Ok(spec) = std::env::var("RHEO_TRACE_EXPORT") {
+                return match spec.strip_prefix("file:") {
+                    Some(path) => Some(TraceExport::File(PathBuf::from(path))),
+                    None if spec.is_empty() => None,
+                    None => Some(TraceExport::Otlp(spec)),
+                };
+            }
+            std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                .ok()
+                .filter(|e| !e.is_empty())
+                .map(TraceExport::Otlp)
+        }
+    }
+
+    /// Finished hop spans, batched by the OpenTelemetry SDK off the async
+    /// runtime and shipped as OTLP/JSON
+    #[derive(Debug, Clone)]
+    pub struct SpanExport {
+        provider: SdkTracerProvider,
+    }
+
+    impl SpanExport {
+        /// Spans are reported under a resource named `service`
+        pub fn new(export: TraceExport, service: &str) -> Result<Self, String> {
+            let resource = Resource::builder()
+                .with_service_name(service.to_string())
+                .build();
+            let builder = SdkTracerProvider::builder().with_resource(resource);
+            let provider = match export {
+                TraceExport::File(path) => builder.with_batch_exporter(FileExporter {
+                    path,
+                    resource: ResourceAttributesWithSchema::default(),
+                }),
+                TraceExport::Otlp(endpoint) => {
+                    let url = if endpoint.ends_with("/v1/traces") {
+                        endpoint
+                    } else {
+                        format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+                    };
+                    let exporter = opentelemetry_otlp::SpanExporter::builder()
+                        .with_http()
+                        .with_protocol(opentelemetry_otlp::Protocol::HttpJson)
+                        .with_endpoint(url)
+                        .build()
+                        .map_err(|e| e.to_string())?;
+                    builder.with_batch_exporter(exporter)
+                }
+            };
+            Ok(Self {
+                provider: provider.build(),
+            })
+        }
+
+        /// `tracing` layer exporting the spans opened by [`hop_span`]
+        pub fn layer<S>(&self) -> impl Layer<S>
+        where
+            S: Subscriber + for<'a> LookupSpan<'a>,
+        {
+            tracing_opentelemetry::layer()
+                .with_tracer(self.provider.tracer("rheo-mesh"))
+                .with_filter(filter_fn(|meta| {
+                    meta.is_span() && meta.fields().field("otel.kind").is_some()
+                }))
+        }
+
+        /// Block until every finished span has been handed to the exporter
+        pub fn flush(&self) -> Result<(), String> {
+            self.provider.force_flush().map_err(|e| e.to_string())
+        }
+    }
+
+    /// One OTLP/JSON `ExportTraceServiceRequest` per line
+    #[derive(Debug)]
+    struct FileExporter {
+        path: PathBuf,
+        resource: ResourceAttributesWithSchema,
+    }
+
+    impl opentelemetry_sdk::trace::SpanExporter for FileExporter {
+        async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+            let request = ExportTraceServiceRequest {
+                resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+            };
+            let line = serde_json::to_string(&request)
+                .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))?;
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .and_then(|mut f| writeln!(f, "{}", line))
+                .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))
+        }
+
+        fn set_resource(&mut self, resource: &Resource) {
+            self.resource = resource.into();
+        }
+    }
+}
+
+// ============================================================================
+// STRUCTURED LOGGING
+// ============================================================================
+
+/// The subscriber set up from `CellConfig` (see [`RheoCell::init_logging`] and
+/// [`RheoCell::scoped_logging`]), with optional shipping of log lines to a
+/// mesh capability.
+pub mod logging {
+    use super::*;
+    use tracing::{
+        field::{Field, Visit},
+        level_filters::LevelFilter,
+        span, Event, Subscriber,
+    };
+    use tracing_subscriber::{
+        fmt, layer::Context as LayerContext, prelude::*, registry::LookupSpan, reload, EnvFilter,
+        Layer, Registry,
+    };
+
+    pub type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+    const SHIP_BATCH: usize = 100;
+    const SHIP_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// `level` as the default, with any `RUST_LOG` directives on top
+    pub fn filter(level: Level) -> EnvFilter {
+        EnvFilter::builder()
+            .with_default_directive(LevelFilter::from_level(level).into())
+            .from_env_lossy()
+    }
+
+    /// One shipped log line
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LogEntry {
+        pub timestamp: u64,
+        pub level: String,
+        pub target: String,
+        pub message: String,
+        #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+        pub fields: serde_json::Map<String, Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub cid: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub capability: Option<String>,
+    }
+
+    #[derive(Default)]
+    struct Fields(serde_json::Map<String, Value>);
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .insert(field.name().to_string(), Value::String(value.to_string()));
+        }
+
+        fn record_i64(&mut self, field: &Field, value: i64) {
+            self.0.insert(field.name().to_string(), value.into());
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.0.insert(field.name().to_string(), value.into());
+        }
+
+        fn record_bool(&mut self, field: &Field, value: bool) {
+            self.0.insert(field.name().to_string(), value.into());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(
+                field.name().to_string(),
+                Value::String(format!("{:?}", value)),
+            );
+        }
+    }
+
+    /// The signal a span belongs to
+    struct SignalScope {
+        cid: Option<String>,
+        capability: Option<String>,
+    }
+
+    /// Layer forwarding events to the shipping task. Events about shipping
+    /// itself are dropped so a batch never produces the next one.
+    struct MeshShipper {
+        capability: String,
+        tx: mpsc::Sender<LogEntry>,
+    }
+
+    impl MeshShipper {
+        fn is_shipping(&self, value: Option<&Value>) -> bool {
+            value.and_then(Value::as_str) == Some(self.capability.as_str())
+        }
+    }
+
+    impl<S> Layer<S> for MeshShipper
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &span::Attributes<'_>,
+            id: &span::Id,
+            ctx: LayerContext<'_, S>,
+        ) {
+            let mut fields = Fields::default();
+            attrs.record(&mut fields);
+            if !fields.0.contains_key("cid") && !fields.0.contains_key("capability") {
+                return;
+            }
+            let text = |key: &str| fields.0.get(key).and_then(Value::as_str).map(String::from);
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(SignalScope {
+                    cid: text("cid"),
+                    capability: text("capability"),
+                });
+            }
+        }
+
+        fn on_event(&self, event: &Event<'_>, ctx: LayerContext<'_, S>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            if self.is_shipping(fields.0.get("capability")) {
+                return;
+            }
+
+            let (mut cid, mut capability) = (None, None);
+            if let Some(scope) = ctx.event_scope(event) {
+                for span in scope {
+                    if let Some(signal) = span.extensions().get::<SignalScope>() {
+                        if signal.capability.as_deref() == Some(self.capability.as_str()) {
+                            return;
+                        }
+                        if cid.is_none() {
+                            cid = signal.cid.clone();
+                            capability = signal.capability.clone();
+                        }
+                    }
+                }
+            }
+
+            let message = match fields.0.remove("message") {
+                Some(Value::String(m)) => m,
+                Some(other) => other.to_string(),
+                None => String::new(),
+            };
+            // Dropped when the mesh can't keep up - logging must never block
+            let _ = self.tx.try_send(LogEntry {
+                timestamp: now_millis(),
+                level: event.metadata().level().to_string(),
+                target: event.metadata().target().to_string(),
+                message,
+                fields: fields.0,
+                cid,
+                capability,
+            });
+        }
+    }
+
+    /// The subscriber for a cell, not yet installed anywhere
+    pub(crate) struct Setup {
+        pub dispatch: tracing::Dispatch,
+        pub filter: FilterHandle,
+        /// Present when `RHEO_TRACE_EXPORT` asked for span export
+        pub spans: Option<telemetry::SpanExport>,
+        export_error: Option<String>,
+        unshipped: Option<String>,
+    }
+
+    impl Setup {
+        /// Warn about anything left out - once `dispatch` is in place there is
+        /// somewhere to say it
+        pub fn report(&self) {
+            if let Some(error) = &self.export_error {
+                warn!(error = %error, "span export disabled");
+            }
+            if let Some(capability) = &self.unshipped {
+                warn!(capability = %capability, "log shipping needs a tokio runtime - disabled");
+            }
+        }
+    }
+
+    pub(crate) fn setup(cell: &Arc<RheoCell>) -> Setup {
+        let config = &cell.config;
+        let (filter, handle) = reload::Layer::new(filter(config.log_level));
+
+        let output = match config.log_format {
+            LogFormat::Text => fmt::layer().boxed(),
+            LogFormat::Json => fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(false)
+                .boxed(),
+        };
+
+        let mut export_error = None;
+        let spans = telemetry::TraceExport::from_env().and_then(|export| {
+            telemetry::SpanExport::new(export, &cell.id)
+                .map_err(|e| export_error = Some(e))
+                .ok()
+        });
+
+        let mut unshipped = None;
+        let shipper = config.log_ship_to.as_ref().and_then(|capability| {
+            if tokio::runtime::Handle::try_current().is_err() {
+                unshipped = Some(capability.clone());
+                return None;
+            }
+            let (tx, rx) = mpsc::channel(4096);
+            tokio::spawn(ship(Arc::downgrade(cell), capability.clone(), rx));
+            Some(MeshShipper {
+                capability: capability.clone(),
+                tx,
+            })
+        });
+
+        let dispatch = tracing_subscriber::registry()
+            .with(filter)
+            .with(output)
+            .with(spans.as_ref().map(telemetry::SpanExport::layer))
+            .with(shipper)
+            .into();
+        Setup {
+            dispatch,
+            filter: handle,
+            spans,
+            export_error,
+            unshipped,
+        }
+    }
+
+    async fn ship(
+        cell: std::sync::Weak<RheoCell>,
+        capability: String,
+        mut rx: mpsc::Receiver<LogEntry>,
+    ) {
+        while let Some(first) = rx.recv().await {
+            let mut entries = vec![first];
+            let flush_at = tokio::time::Instant::now() + SHIP_INTERVAL;
+            while entries.len() < SHIP_BATCH {
+                match tokio::time::timeout_at(flush_at, rx.recv()).await {
+                    Ok(Some(entry)) => entries.push(entry),
+                    _ => break,
+                }
+            }
+
+            let Some(cell) = cell.upgrade() else { return };
+            if cell.is_shutting_down.load(Ordering::SeqCst) > 0 {
+                return;
+            }
+            let mut signal = Signal::new(
+                &cell.id,
+                &capability,
+                serde_json::json!({ "cell": cell.id, "entries": entries }),
+            )
+            .with_deadline(Duration::from_secs(5));
+            signal.sign_origin(&cell.signing_key);
+            // Best effort - a missing log sink is not worth reporting
+            let _ = cell.route(signal).await;
+        }
+    }
+}
+
+// ============================================================================
+// ADMIN AUTHORIZATION
+// ============================================================================
+
+/// Capabilities that control the cell itself. They only run for the operator
+/// token or a fresh proof signed by an allowlisted key.
+pub mod admin {
+    use super::*;
+
+    pub const CAPABILITIES: &[&str] = &[
+        "cell/shutdown",
+        "cell/inspect",
+        "cell/set_log_level",
+        "cell/reload_config",
+        "cell/evict_peer",
+    ];
+
+    /// `Signal.proofs` keys
+    pub const TOKEN_PROOF: &str = "adminToken";
+    pub const KEY_PROOF: &str = "adminKey";
+    pub const TS_PROOF: &str = "adminTs";
+    pub const SIG_PROOF: &str = "adminSig";
+
+    /// Older signed proofs are refused. [`Credentials`] keeps the proofs it
+    /// accepted for this long and refuses them a second time, so a proof can't
+    /// be replayed inside the window either; other cells refuse it because it
+    /// names its target.
+    pub const MAX_PROOF_AGE_MS: u64 = 60_000;
+
+    pub fn is_admin(capability: &str) -> bool {
+        CAPABILITIES.contains(&capability)
+    }
+
+    /// Who an admin call was authorized for
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Principal {
+        Operator,
+        Key(String),
+    }
+
+    impl fmt::Display for Principal {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Principal::Operator => write!(f, "operator"),
+                Principal::Key(key) => write!(f, "key:{}", key),
+            }
+        }
+    }
+
+    /// Who may call admin capabilities - nobody when neither is configured
+    #[derive(Debug, Clone, Default)]
+    pub struct Credentials {
+        token: Option<String>,
+        keys: Vec<String>,
+        /// Digest of each signed proof accepted -> its timestamp, kept for
+        /// [`MAX_PROOF_AGE_MS`]
+        seen: Arc<std::sync::Mutex<HashMap<String, u64>>>,
+    }
+
+    impl Credentials {
+        pub fn from_config(config: &CellConfig) -> Self {
+            Self {
+                token: config.admin_token.clone().filter(|t| !t.is_empty()),
+                keys: config.admin_keys.clone(),
+                seen: Arc::default(),
+            }
+        }
+
+        /// Carry over the proofs `previous` accepted, so reloading credentials
+        /// doesn't reopen them to replay
+        pub fn remembering(mut self, previous: &Credentials) -> Self {
+            self.seen = Arc::clone(&previous.seen);
+            self
+        }
+
+        /// Check the admin proofs carried by `signal` for running on cell `audience`
+        pub fn authorize(&self, signal: &Signal, audience: &str) -> Result<Principal, String> {
+            if let Some(token) = signal.proofs.get(TOKEN_PROOF) {
+                return match &self.token {
+                    Some(expected) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+                        Ok(Principal::Operator)
+                    }
+                    _ => Err("invalid operator token".to_string()),
+                };
+            }
+
+            let (Some(key), Some(ts), Some(sig)) = (
+                signal.proofs.get(KEY_PROOF),
+                signal.proofs.get(TS_PROOF),
+                signal.proofs.get(SIG_PROOF),
+            ) else {
+                return Err("no operator token or signed proof".to_string());
+            };
+            let key = key.to_ascii_lowercase();
+            if !self.keys.contains(&key) {
+                return Err(format!("key {} is not allowlisted", key));
+            }
+            let ts: u64 = ts.parse().map_err(|_| "malformed proof timestamp")?;
+            if now_millis().abs_diff(ts) > MAX_PROOF_AGE_MS {
+                return Err("proof expired".to_string());
+            }
+
+            let verifying_key = hex::decode(&key)
+                .ok()
+                .and_then(|b| <[u8; 32]>::try_from(b).ok())
+                .and_then(|b| VerifyingKey::from_bytes(&b).ok())
+                .ok_or("malformed admin key")?;
+            let signature = hex::decode(sig)
+                .ok()
+                .and_then(|b| Signature::from_slice(&b).ok())
+                .ok_or("malformed proof signature")?;
+            let message = signing_message(signal, ts, audience);
+            verifying_key
+                .verify(message.as_bytes(), &signature)
+                .map_err(|_| "proof signature does not match this cell")?;
+
+            let digest = hex::encode(Sha256::digest(format!("{}\n{}", key, message).as_bytes()));
+            let mut seen = self.seen.lock().unwrap();
+            let now = now_millis();
+            seen.retain(|_, ts| now.abs_diff(*ts) <= MAX_PROOF_AGE_MS);
+            if seen.insert(digest, ts).is_some() {
+                return Err("proof already used".to_string());
+            }
+            Ok(Principal::Key(key))
+        }
+    }
+
+    /// What an admin key signs - naming the sender and the target cell, so a
+    /// proof captured on its way to one cell is no good on the others sharing
+    /// the key, nor when resent under another sender
+    pub fn signing_message(signal: &Signal, ts: u64, audience: &str) -> String {
+        format!(
+            "rheo-admin\n{}\n{}\n{}\n{}\n{}\n{}",
+            signal.id, signal.from, audience, signal.payload.capability, ts, signal.payload.args
+        )
+    }
+
+    /// Drop admin proofs so they are never journaled, recorded or passed to child calls
+    pub fn redact(signal: &mut Signal) {
+        signal
+            .proofs
+            .retain(|k, _| ![TOKEN_PROOF, KEY_PROOF, TS_PROOF, SIG_PROOF].contains(&k.as_str()));
+    }
+
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+// ============================================================================
+// ACCESS CONTROL POLICIES
+// ============================================================================
+
+/// Allow/deny rules on capability patterns, evaluated against the verified
+/// caller at the cell that runs the capability. Loaded from TOML:
+///
+/// ```toml
+/// default = "allow"   # when no rule matches
+///
+/// [[rule]]
+/// name = "orders-from-desk"
+/// effect = "allow"
+/// capabilities = ["trading/place_order"]
+/// callers = ["risk", "ui-gateway"]
+///
+/// [[rule]]
+/// effect = "deny"
+/// capabilities = ["trading/place_order"]   # no callers: anyone else
+///
+/// [identities]        # required for every caller a rule names
+/// risk = "<hex ed25519 public key>"
+/// ui-gateway = "<hex ed25519 public key>"
+/// ```
+///
+/// The first matching rule wins. `*` matches any run of characters. Rules with
+/// `callers` only match callers whose origin proof verifies against the key
+/// pinned here (see [`CellConfig::identity_key`]) and is under
+/// [`admin::MAX_PROOF_AGE_MS`] old.
+pub mod policy {
+    use super::*;
+
+    /// `Signal.proofs` key for the sender's signature (see [`Signal::sign_origin`])
+    pub const ORIGIN_PROOF: &str = "originSig";
+    /// `Signal.proofs` key for when it was signed, in unix millis. Proofs older
+    /// than [`admin::MAX_PROOF_AGE_MS`] don't verify.
+    pub const ORIGIN_TS_PROOF: &str = "originTs";
+    /// `Signal.proofs` key for who the sender acts on behalf of (e.g. a gateway's
+    /// API client). Covered by the origin signature.
+    pub const CALLER_PROOF: &str = "caller";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Effect {
+        #[default]
+        Allow,
+        Deny,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub struct Rule {
+        #[serde(default)]
+        pub name: String,
+        pub effect: Effect,
+        pub capabilities: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub callers: Option<Vec<String>>,
+    }
+
+    impl Rule {
+        pub fn matches(&self, capability: &str, caller: Option<&str>) -> bool {
+            self.capabilities.iter().any(|p| glob(p, capability))
+                && match (&self.callers, caller) {
+                    (None, _) => true,
+                    (Some(callers), Some(caller)) => callers.iter().any(|p| glob(p, caller)),
+                    (Some(_), None) => false,
+                }
+        }
+    }
+
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub struct Policy {
+        #[serde(default)]
+        pub default: Effect,
+        #[serde(default, rename = "rule")]
+        pub rules: Vec<Rule>,
+        /// Cell id -> hex public key its origin proofs must verify against
+        #[serde(default)]
+        pub identities: HashMap<String, String>,
+    }
+
+    impl Policy {
+        /// Everything denied - what a cell falls back to when its policy is broken
+        pub fn deny_all() -> Self {
+            Self {
+                default: Effect::Deny,
+                ..Default::default()
+            }
+        }
+
+        /// The file at `path`, or allow-all without one
+        pub fn load(path: Option<&str>) -> Result<Self, ConfigError> {
+            let Some(path) = path else {
+                return Ok(Self::default());
+            };
+            let text =
+                std::fs::read_to_string(path).map_err(|e| ConfigError::new(path, e.to_string()))?;
+            Self::parse(&text)
+                .map_err(|e| ConfigError::new(format!("{}: {}", path, e.key), e.message))
+        }
+
+        pub fn parse(text: &str) -> Result<Self, ConfigError> {
+            let mut policy: Policy = toml::from_str(text).map_err(|e: toml::de::Error| {
+                ConfigError::new("policy", e.message().to_string())
+            })?;
+            for (index, rule) in policy.rules.iter_mut().enumerate() {
+                if rule.capabilities.is_empty() {
+                    return Err(ConfigError::new(
+                        format!("rule[{}].capabilities", index),
+                        "expected at least one pattern",
+                    ));
+                }
+                if rule.name.is_empty() {
+                    rule.name = format!("rule[{}]", index);
+                }
+            }
+            // A caller is only ever verified against a pinned key
+            for (index, rule) in policy.rules.iter().enumerate() {
+                for (i, pattern) in rule.callers.iter().flatten().enumerate() {
+                    if !policy.identities.keys().any(|id| glob(pattern, id)) {
+                        return Err(ConfigError::new(
+                            format!("rule[{}].callers[{}]", index, i),
+                            format!("no key pinned in [identities] for `{}`", pattern),
+                        ));
+                    }
+                }
+            }
+            Ok(policy)
+        }
+
+        /// The rule that denies `caller` (a verified id, or None) calling `capability`.
+        /// A denial by `default` is reported as a rule named `default`.
+        pub fn denies(&self, capability: &str, caller: Option<&str>) -> Option<Rule> {
+            match self.rules.iter().find(|r| r.matches(capability, caller)) {
+                Some(rule) if rule.effect == Effect::Deny => Some(rule.clone()),
+                Some(_) => None,
+                None if self.default == Effect::Deny => Some(Rule {
+                    name: "default".to_string(),
+                    effect: Effect::Deny,
+                    capabilities: vec!["*".to_string()],
+                    callers: None,
+                }),
+                None => None,
+            }
+        }
+    }
+
+    /// What the sender signs
+    pub fn origin_message(signal: &Signal) -> String {
+        format!(
+            "rheo-origin\n{}\n{}\n{}\n{}\n{}\n{}",
+            signal.id,
+            signal.from,
+            signal.payload.capability,
+            signal.proofs.get(CALLER_PROOF).map_or("", String::as_str),
+            signal
+                .proofs
+                .get(ORIGIN_TS_PROOF)
+                .map_or("", String::as_str),
+            signal.payload.args
+        )
+    }
+
+    /// Does the signal's origin proof verify against `pub_key` (hex), and is it
+    /// recent enough not to be a replay?
+    pub fn verify_origin(signal: &Signal, pub_key: &str) -> bool {
+        let fresh = signal
+            .proofs
+            .get(ORIGIN_TS_PROOF)
+            .and_then(|ts| ts.parse::<u64>().ok())
+            .is_some_and(|ts| now_millis().abs_diff(ts) <= admin::MAX_PROOF_AGE_MS);
+        if !fresh {
+            return false;
+        }
+        let Some(signature) = signal
+            .proofs
+            .get(ORIGIN_PROOF)
+            .and_then(|s| hex::decode(s).ok())
+            .and_then(|s| Signature::from_slice(&s).ok())
+        else {
+            return false;
+        };
+        hex::decode(pub_key)
+            .ok()
+            .and_then(|k| <[u8; 32]>::try_from(k).ok())
+            .and_then(|k| VerifyingKey::from_bytes(&k).ok())
+            .is_some_and(|k| {
+                k.verify(origin_message(signal).as_bytes(), &signature)
+                    .is_ok()
+            })
+    }
+
+    /// `*` matches any run of characters, everything else literally
+    pub fn glob(pattern: &str, text: &str) -> bool {
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = text.strip_prefix(first) else {
+            return false;
+        };
+        let parts: Vec<&str> = parts.collect();
+        let Some((last, middle)) = parts.split_last() else {
+            return rest.is_empty(); // no `*` at all
+        };
+        for part in middle {
+            match rest.find(part) {
+                Some(at) => rest = &rest[at + part.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    }
+}
+
+// ============================================================================
+// TRADING-SPECIFIC EXTENSIONS
+// ============================================================================
+
+pub mod trading {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    /// High-performance order type
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Order {
+        pub id: String,
+        pub symbol: String,
+        pub side: Side,
+        pub price: f64,
+        pub quantity: f64,
+        pub timestamp: u64,
+        pub client_id: String,
+    }
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum Side {
+        Buy,
+        Sell,
+    }
+
+    /// Market data tick
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Tick {
+        pub symbol: String,
+        pub bid: f64,
+        pub ask: f64,
+        pub last: f64,
+        pub volume: f64,
+        pub timestamp: u64,
+    }
+
+    /// Position tracking
+    #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+    pub struct Position {
+        pub symbol: String,
+        pub quantity: f64,
+        pub avg_entry: f64,
+        pub unrealized_pnl: f64,
+        pub realized_pnl: f64,
+    }
+
+    /// Risk limits
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RiskLimits {
+        pub max_position: f64,
+        pub max_order_size: f64,
+        pub max_daily_loss: f64,
+        pub max_drawdown: f64,
+    }
+
+    /// Trading cell with specialized handlers
+    pub struct TradingCell {
+        cell: Arc<RheoCell>,
+        orders: Arc<DashMap<String, Order>>,
+        positions: Arc<DashMap<String, Position>>,
+        risk_limits: Arc<RwLock<RiskLimits>>,
+    }
+
+    impl TradingCell {
+        pub fn new(cell: Arc<RheoCell>, risk_limits: RiskLimits) -> Self {
+            let trading = Self {
+                cell: Arc::clone(&cell),
+                orders: Arc::new(DashMap::new()),
+                positions: Arc::new(DashMap::new()),
+                risk_limits: Arc::new(RwLock::new(risk_limits)),
+            };
+
+            trading.register_handlers();
+            trading
+        }
+
+        fn register_handlers(&self) {
+            let orders = Arc::clone(&self.orders);
+            self.cell
+                .provide("trading/place_order", move |order: Order, _signal| {
+                    let orders = Arc::clone(&orders);
+                    Box::pin(async move {
+                        // Validate order
+                        if order.quantity <= 0.0 || order.price <= 0.0 {
+                            return Err(MeshError::new(
+                                ErrorCode::ValidationFailed,
+                                "Invalid order parameters",
+                                "trading",
+                            ));
+                        }
+
+                        orders.insert(order.id.clone(), order.clone());
+                        Ok(serde_json::json!({
+                            "status": "accepted",
+                            "order_id": order.id
+                        }))
+                    })
+                });
+
+            let positions = Arc::clone(&self.positions);
+            self.cell
+                .provide("trading/get_position", move |symbol: String, _signal| {
+                    let positions = Arc::clone(&positions);
+                    Box::pin(async move {
+                        let pos = positions.get(&symbol).map(|p| p.clone());
+                        Ok(pos.unwrap_or_default())
+                    })
+                });
+
+            self.cell
+                .provide("trading/market_data", move |_args: (), ctx: Context| {
+                    Box::pin(async move {
+                        // Multicast to all market data providers
+                        let result = ctx.ask_all("marketdata/tick", (), 100).await;
+                        Ok(result.reduce(multicast::merge::<Tick>()))
+                    })
+                });
+        }
+
+        pub async fn place_order(&self, order: Order) -> Result<Value, MeshError> {
+            self.cell
+                .mesh_proxy()
+                .call("trading/place_order", order)
+                .await
+        }
+
+        pub async fn get_position(&self, symbol: impl Into<String>) -> Result<Position, MeshError> {
+            self.cell
+                .mesh_proxy()
+                .call("trading/get_position", symbol.into())
+                .await
+        }
+    }
+}
+
+// ============================================================================
+// EXAMPLE USAGE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Poll `condition` every 50ms, failing the test with `desc` after 5s
+    async fn wait_until(desc: &str, mut condition: impl FnMut() -> bool) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        panic!("timed out waiting for {}", desc);
+    }
+
+    #[tokio::test]
+    async fn test_cell_creation() {
+        let cell = RheoCell::new(CellConfig::default());
+        let addr = cell.clone().listen().await.unwrap().addr();
+        assert!(addr.port() > 0);
+
+        // Test ping
+        let result = cell.ask_mesh("mesh/ping", ()).await;
+        assert!(result.ok);
+
+        cell.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_cell_communication() {
+        let cell1 = RheoCell::new(CellConfig {
+            id: "cell_1".to_string(),
+            ..Default::default()
+        });
+        let addr1 = cell1.clone().listen().await.unwrap().addr();
+
+        // Register custom handler
+        cell1.provide("test/echo", |msg: String, _| {
+            Box::pin(async move { Ok(format!("echo: {}", msg)) })
+        });
+
+        // Wait for convergence
+        sleep(Duration::from_millis(100)).await;
+
+        let cell2 = RheoCell::new(CellConfig {
+            id: "cell_2".to_string(),
+            seed: Some(format!("http://127.0.0.1:{}", addr1.port())),
+            ..Default::default()
+        });
+        cell2.clone().listen().await.unwrap();
+
+        // Wait for bootstrap
+        sleep(Duration::from_millis(500)).await;
+
+        // Test cross-cell call
+        let result: Result<String, MeshError> =
+            cell2.mesh_proxy().call("test/echo", "hello world").await;
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "echo: hello world");
+
+        cell1.shutdown().await;
+        cell2.shutdown().await;
+    }
+
+    #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+    struct Doubled {
+        value: i64,
+    }
+
+    #[derive(Deserialize)]
+    struct Plain(#[allow(dead_code)] i64);
+
+    #[tokio::test]
+    async fn test_procedure_schemas_enforced() {
+        let cell = RheoCell::new(CellConfig::default());
+        cell.use_router(
+            router::Router::new()
+                .procedure(
+                    "double",
+                    router::Procedure::query(
+                        |n: i64, _| async move { Ok(Doubled { value: n * 2 }) },
+                    )
+                    .with_schemas(),
+                )
+                .procedure(
+                    "broken",
+                    router::Procedure::query(|_n: i64, _| async move {
+                        Ok(serde_json::json!({ "value": "not a number" }))
+                    })
+                    .with_output_schema(router::JsonSchema::<Doubled>::default()),
+                )
+                .procedure(
+                    "plain",
+                    router::Procedure::query(|n: i64, _| async move { Ok(n) })
+                        .with_input_schema(router::JsonSchema::<Plain>::default()),
+                ),
+        );
+
+        let ok = cell.route(Signal::new("test", "double", 21)).await;
+        assert_eq!(ok.value, Some(serde_json::json!({ "value": 42 })));
+
+        let bad_input = cell.route(Signal::new("test", "double", "21")).await;
+        assert_eq!(bad_input.error.unwrap().code, ErrorCode::ValidationFailed);
+
+        let bad_output = cell.route(Signal::new("test", "broken", 1)).await;
+        assert_eq!(
+            bad_output.error.unwrap().code,
+            ErrorCode::OutputValidationFailed
+        );
+
+        let contract = cell
+            .route(Signal::new(
+                "test",
+                "cell/contract",
+                serde_json::json!({ "cap": "double" }),
+            ))
+            .await
+            .value
+            .unwrap();
+        assert_eq!(contract["input"]["type"], "integer");
+        assert_eq!(contract["output"]["required"], serde_json::json!(["value"]));
+
+        // Validating types need no JSON Schema of their own
+        let plain = cell.route(Signal::new("test", "plain", 3)).await;
+        assert!(plain.ok);
+        let plain = cell.route(Signal::new("test", "plain", "3")).await;
+        assert_eq!(plain.error.unwrap().code, ErrorCode::ValidationFailed);
+    }
+
+    #[tokio::test]
+    async fn test_versioned_contracts() {
+        let cell = RheoCell::new(CellConfig::default());
+        cell.provide_contract(
+            Contract::new("orbital/create", "2.1.0").compatible_with("1.9.0"),
+            |name: String, _| Box::pin(async move { Ok(name) }),
+        );
+
+        let v1 = cell
+            .route(Signal::new("test", "orbital/create@^1", "a"))
+            .await;
+        assert!(v1.ok);
+
+        let v3 = cell
+            .route(Signal::new("test", "orbital/create@^3", "a"))
+            .await;
+        let err = v3.error.unwrap();
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert_eq!(
+            err.details.unwrap()["versionRequirement"],
+            serde_json::json!("^3")
+        );
+
+        let mut entry = AtlasEntry::new("orbital", "http://x", vec!["orbital/create".into()]);
+        assert!(entry.serves("orbital/create", None));
+        assert!(!entry.serves("orbital/create", Some("^1")));
+        entry.cap_info.insert(
+            "orbital/create".into(),
+            CapInfo {
+                versions: vec!["1.2.0".into()],
+                ..Default::default()
+            },
+        );
+        assert!(entry.serves("orbital/create", Some("^1")));
+        assert!(!entry.serves("orbital/create", Some(">=2")));
+
+        // Published schemas are enforced on both sides
+        let mut lookup = Contract::new("orbital/lookup", "1.0.0").query();
+        lookup.input_schema = Some(serde_json::json!({ "type": "string", "minLength": 2 }));
+        lookup.output_schema = Some(serde_json::json!({ "type": "integer" }));
+        cell.provide_contract(lookup, |name: String, _| {
+            Box::pin(async move {
+                match name.as_str() {
+                    "bad" => Ok(serde_json::json!("not a number")),
+                    _ => Ok(serde_json::json!(name.len())),
+                }
+            })
+        });
+        let short = cell.route(Signal::new("test", "orbital/lookup", "a")).await;
+        assert_eq!(short.error.unwrap().code, ErrorCode::ValidationFailed);
+        let ok = cell
+            .route(Signal::new("test", "orbital/lookup", "ab"))
+            .await;
+        assert_eq!(ok.value, Some(serde_json::json!(2)));
+        let bad = cell
+            .route(Signal::new("test", "orbital/lookup", "bad"))
+            .await;
+        assert_eq!(bad.error.unwrap().code, ErrorCode::OutputValidationFailed);
+        assert!(!cell.procedure_spec("orbital/lookup").unwrap().is_mutation);
+        assert!(cell.procedure_spec("orbital/create").unwrap().is_mutation);
+    }
+
+    #[tokio::test]
+    async fn test_context_child_calls() {
+        let cell = RheoCell::new(CellConfig::default());
+        cell.provide("child/lineage", |_: (), ctx: Context| {
+            Box::pin(async move {
+                Ok(serde_json::json!({
+                    "parent": ctx.extensions.get("_parentId"),
+                    "deadline": ctx.deadline_ms,
+                }))
+            })
+        });
+        cell.provide("child/fail", |_: (), _ctx: Context| {
+            Box::pin(async move {
+                Err::<(), _>(MeshError::new(ErrorCode::HandlerError, "boom", "child"))
+            })
+        });
+        cell.provide("parent/run", |_: (), ctx: Context| {
+            Box::pin(async move {
+                let lineage: Value = ctx.call("child/lineage", ()).await?;
+                assert_eq!(lineage["parent"], serde_json::json!(ctx.id));
+                assert_eq!(lineage["deadline"], serde_json::json!(ctx.deadline_ms));
+                ctx.call::<()>("child/fail", ()).await
+            })
+        });
+
+        let signal = Signal::new("test", "parent/run", ()).with_deadline(Duration::from_secs(5));
+        let err = cell.route(signal).await.error.unwrap();
+        assert_eq!(err.code, ErrorCode::HandlerError);
+        let caps: Vec<_> = err.children.iter().map(|c| c.capability.as_str()).collect();
+        assert_eq!(caps, vec!["child/lineage", "child/fail"]);
+        assert!(err.children[0].ok && !err.children[1].ok);
+        assert!(err.forensic_report().contains("CALL TREE (2 child calls)"));
+    }
+
+    #[tokio::test]
+    async fn test_narrative_envelope_merged() {
+        let cell1 = RheoCell::new(CellConfig::default());
+        let addr1 = cell1.clone().listen().await.unwrap().addr();
+        let cell2 = RheoCell::new(CellConfig {
+            seed: Some(format!("http://127.0.0.1:{}", addr1.port())),
+            ..Default::default()
+        });
+        cell2.provide("test/explode", |_: (), _ctx: Context| {
+            Box::pin(async move {
+                Err::<(), _>(MeshError::new(ErrorCode::HandlerError, "kaboom", "explode"))
+            })
+        });
+        cell2.clone().listen().await.unwrap();
+        sleep(Duration::from_millis(500)).await;
+
+        let err = cell1.ask_mesh("test/explode", ()).await.error.unwrap();
+        let envelope = err.envelope.as_ref().expect("failure carries _envelope");
+        let actions: Vec<_> = envelope
+            .ancestry
+            .iter()
+            .map(|a| a.action.as_str())
+            .collect();
+        assert!(actions.contains(&"RPC_ATTEMPT"));
+        assert!(
+            actions.contains(&"HANDLER_EXCEPTION"),
+            "remote narrative merged"
+        );
+
+        // Same wire shape as core.ts
+        let wire = serde_json::to_value(&err).unwrap();
+        assert!(wire["_envelope"]["ancestry"][0]["signalSnapshot"].is_object());
+
+        let report = err.narrative_report().unwrap();
+        assert_eq!(report.failure_analysis.kind, "HANDLER_EXCEPTION");
+        assert_eq!(
+            report.summary.cells_visited,
+            vec![cell1.id.clone(), cell2.id.clone()]
+        );
+        assert!(err.forensic_report().contains("NARRATIVE REPORT"));
+
+        // Mesh chatter isn't kept
+        let ping = cell1.route(Signal::new("test", "mesh/ping", ())).await;
+        assert!(ping.ok);
+        assert!(cell1.ledger.get(&ping.cid).is_none());
+
+        cell1.shutdown().await;
+        cell2.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_signed_steps_detect_tampering() {
+        let cell = RheoCell::new(CellConfig::default());
+        cell.provide("test/fail", |_: (), _ctx: Context| {
+            Box::pin(async move {
+                Err::<(), _>(MeshError::new(ErrorCode::HandlerError, "nope", "test"))
+            })
+        });
+
+        let mut signal = Signal::new("test", "test/fail", ());
+        signal.record_step("legacy-ts-cell", "RECEIVED");
+        let err = cell.route(signal).await.error.unwrap();
+        let integrity = err.integrity.as_ref().unwrap();
+        assert!(integrity.is_intact());
+        // RECEIVED and LOCAL_HANDLER, then the seal
+        assert_eq!(integrity.verified, 3);
+        assert_eq!(integrity.issues[0].kind, narrative::StepIssueKind::Unsigned);
+
+        let steps = err.history.clone().unwrap();
+        let mut forged = steps.clone();
+        forged[1].action = "SOMETHING_ELSE".into();
+        let kinds: Vec<_> = cell
+            .verify_steps(&forged)
+            .issues
+            .iter()
+            .map(|i| i.kind)
+            .collect();
+        assert!(kinds.contains(&narrative::StepIssueKind::HashMismatch));
+
+        let mut gapped = steps.clone();
+        gapped.remove(1);
+        assert!(!cell.verify_steps(&gapped).is_intact());
+
+        let issue_kinds = |steps: &[NarrativeStep]| -> Vec<narrative::StepIssueKind> {
+            cell.verify_steps(steps)
+                .issues
+                .iter()
+                .map(|i| i.kind)
+                .collect()
+        };
+        let mut stripped = steps.clone();
+        let last = stripped.len() - 1;
+        stripped[last].signature = None;
+        stripped[last].hash = None;
+        assert!(issue_kinds(&stripped).contains(&narrative::StepIssueKind::Stripped));
+        assert!(!cell.verify_steps(&stripped).is_intact());
+
+        let truncated = &steps[..steps.len() - 1];
+        assert_eq!(
+            issue_kinds(truncated),
+            vec![
+                narrative::StepIssueKind::Truncated,
+                narrative::StepIssueKind::Unsigned
+            ]
+        );
+        let truncated = &steps[..steps.len() - 2];
+        assert!(issue_kinds(truncated).contains(&narrative::StepIssueKind::Truncated));
+
+        let stranger = RheoCell::new(CellConfig::default());
+        let unknown = stranger.verify_steps(&steps);
+        assert!(unknown
+            .issues
+            .iter()
+            .any(|i| i.kind == narrative::StepIssueKind::UnknownSigner));
+        assert!(err.forensic_report().contains("STEP INTEGRITY: INTACT"));
+    }
+
+    #[tokio::test]
+    async fn test_describe_tools() {
+        let cell = RheoCell::new(CellConfig::default());
+        cell.use_router(
+            router::Router::new().procedure(
+                "math/double",
+                router::Procedure::query(|n: i64, _| async move { Ok(Doubled { value: n * 2 }) })
+                    .with_schemas()
+                    .describe("Double a number"),
+            ),
+        );
+
+        let result = cell.route(Signal::new("test", "mesh/describe", ())).await;
+        let descriptors: Vec<tools::CapabilityDescriptor> = result.into_value().unwrap();
+        let double = descriptors
+            .iter()
+            .find(|d| d.capability == "math/double")
+            .unwrap();
+        assert_eq!(double.description.as_deref(), Some("Double a number"));
+        assert!(!double.is_mutation);
+
+        let tools = tools::to_anthropic_tools(&descriptors);
+        assert_eq!(tools.len(), 1, "internal capabilities are hidden");
+        assert_eq!(tools[0]["name"], "math__double");
+        assert_eq!(tools[0]["input_schema"]["required"][0], "input");
+
+        let tool = tools::find_tool(&descriptors, "math__double").unwrap();
+        assert_eq!(
+            tool.args_from_tool_call(serde_json::json!({ "input": 4 })),
+            serde_json::json!(4)
+        );
+
+        // Wrapped array inputs keep their definitions where `$ref`s point
+        let mut bulk = tools::CapabilityDescriptor::new("quotes/bulk", None, "p");
+        bulk.input_schema = Some(serde_json::to_value(schemars::schema_for!(Vec<Quote>)).unwrap());
+        let parameters = bulk.parameters();
+        let input = &parameters["properties"]["input"];
+        assert_eq!(input["items"]["$ref"], "#/definitions/Quote");
+        assert!(input.get("definitions").is_none());
+        assert!(parameters["definitions"]["Quote"].is_object());
+        assert!(parameters["definitions"]["Doubled"].is_object());
+
+        // Names that flatten alike are told apart
+        let long = format!("svc/{}", "x".repeat(70));
+        let clashing = [
+            tools::CapabilityDescriptor::new("a/b", None, "p"),
+            tools::CapabilityDescriptor::new("a__b", None, "p"),
+            tools::CapabilityDescriptor::new(format!("{}1", long), None, "p"),
+            tools::CapabilityDescriptor::new(format!("{}2", long), None, "p"),
+            tools::CapabilityDescriptor::new("a/c", None, "p"),
+        ];
+        let names: Vec<String> = tools::tool_names(&clashing)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names[4], "a__c", "unique names stay readable");
+        for (name, descriptor) in names.iter().zip(&clashing) {
+            assert!(name.len() <= 64);
+            assert_eq!(names.iter().filter(|n| *n == name).count(), 1);
+            let found = tools::find_tool(&clashing, name).unwrap();
+            assert_eq!(found.capability, descriptor.capability);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_journal_replay() {
+        let dir = std::env::temp_dir().join(format!("rheo-journal-{}", Uuid::new_v4()));
+        let cell = RheoCell::new(CellConfig {
+            journal_dir: Some(dir.to_string_lossy().into_owned()),
+            ..Default::default()
+        });
+        cell.use_router(router::Router::new().procedure(
+            "double",
+            router::Procedure::query(|n: i64, _| async move { Ok(Doubled { value: n * 2 }) }),
+        ));
+
+        let mut signal = Signal::new("test", "double", 21);
+        signal.deadline_ms = Some(now_millis() + 2000);
+        cell.route(signal).await;
+        cell.route(Signal::new("test", "mesh/ping", ())).await;
+        cell.journal.as_ref().unwrap().flush();
+
+        let records = journal::read(dir.join(format!("{}.jsonl", cell.id))).unwrap();
+        assert_eq!(records.len(), 1, "mesh/* traffic is not journaled");
+        let record = &records[0];
+        assert!(record.result.ok);
+
+        let replay = record.replay_signal(&journal::ReplayOptions {
+            fresh_ids: true,
+            deadline: Some(Duration::from_secs(30)),
+        });
+        assert_ne!(replay.id, record.signal.id);
+        assert!(replay.deadline_ms.unwrap() > now_millis() + 20_000);
+
+        let replayed = cell.route(replay).await;
+        assert!(journal::diff_results(&record.result, &replayed).is_empty());
+
+        let changed = TraceResult::success(&replayed.cid, Doubled { value: 43 });
+        assert_eq!(
+            journal::diff_results(&record.result, &changed),
+            vec!["/value/value: 42 -> 43".to_string()]
+        );
+
+        let small = journal::Journal::open(&dir, "rotating", 1, 2).unwrap();
+        for _ in 0..3 {
+            small.record("c", &record.signal, &record.result, Duration::ZERO);
+        }
+        small.flush();
+        assert_eq!(journal::read(small.path()).unwrap().len(), 1);
+        assert!(dir.join("rotating.1.jsonl").exists());
+        assert!(!dir.join("rotating.2.jsonl").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_trace_context_spans() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let parsed = telemetry::TraceContext::parse(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .unwrap();
+        assert_eq!(parsed.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(
+            parsed.to_traceparent(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+        assert!(telemetry::TraceContext::parse("00-0000-00f067aa0ba902b7-01").is_none());
+
+        let path = std::env::temp_dir().join(format!("rheo-spans-{}.jsonl", Uuid::new_v4()));
+        let export =
+            telemetry::SpanExport::new(telemetry::TraceExport::File(path.clone()), "rheo-test")
+                .unwrap();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(export.layer()));
+
+        let provider = RheoCell::new(CellConfig::default());
+        provider.provide("test/echo", |msg: String, _| {
+            Box::pin(async move { Ok(msg) })
+        });
+        let addr = provider.clone().listen().await.unwrap().addr();
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(format!("http://127.0.0.1:{}", addr.port())),
+            ..Default::default()
+        });
+        caller.clone().listen().await.unwrap();
+        sleep(Duration::from_millis(500)).await;
+
+        assert!(caller.ask_mesh("test/echo", "hi").await.ok);
+
+        let mut spans: Vec<(String, Value)> = Vec::new();
+        for _ in 0..30 {
+            sleep(Duration::from_millis(100)).await;
+            export.flush().unwrap();
+            spans = std::fs::read_to_string(&path)
+                .unwrap_or_default()
+                .lines()
+                .flat_map(|line| {
+                    let request: Value = serde_json::from_str(line).unwrap();
+                    let mut found = Vec::new();
+                    for resource in request["resourceSpans"].as_array().unwrap() {
+                        let service = resource["resource"]["attributes"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .find(|a| a["key"] == "service.name")
+                            .map(|a| a["value"]["stringValue"].clone());
+                        assert_eq!(service, Some(Value::from("rheo-test")));
+                        for scope in resource["scopeSpans"].as_array().unwrap() {
+                            for span in scope["spans"].as_array().unwrap() {
+                                let attribute = |key: &str| {
+                                    span["attributes"]
+                                        .as_array()
+                                        .unwrap()
+                                        .iter()
+                                        .find(|a| a["key"] == key)
+                                        .and_then(|a| a["value"]["stringValue"].as_str())
+                                        .unwrap_or("")
+                                        .to_string()
+                                };
+                                if attribute("capability") == "test/echo" {
+                                    found.push((attribute("cell"), span.clone()));
+                                }
+                            }
+                        }
+                    }
+                    found
+                })
+                .collect();
+            if spans.len() >= 4 {
+                break;
+            }
+        }
+
+        let find = |cell: &str, name: &str| {
+            spans
+                .iter()
+                .find(|(c, span)| c == cell && span["name"] == name)
+                .map(|(_, span)| span.clone())
+                .unwrap_or_else(|| panic!("no {} span from {}", name, cell))
+        };
+        let root = find(&caller.id, "mesh.route");
+        let rpc = find(&caller.id, "mesh.rpc");
+        let server = find(&provider.id, "mesh.route");
+        assert_eq!(root["parentSpanId"].as_str().unwrap_or(""), "");
+        assert_eq!(rpc["kind"], 3);
+        assert_eq!(server["kind"], 2);
+        assert_eq!(server["traceId"], root["traceId"]);
+        assert_eq!(server["parentSpanId"], rpc["spanId"]);
+        assert_eq!(server["status"]["code"], 1);
+
+        caller.shutdown().await;
+        provider.shutdown().await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_log_shipping() {
+        let cell = RheoCell::new(CellConfig {
+            log_level: Level::WARN,
+            log_ship_to: Some("log/append".into()),
+            ..Default::default()
+        });
+        let shipped: Arc<std::sync::Mutex<Vec<logging::LogEntry>>> = Default::default();
+        let sink = Arc::clone(&shipped);
+        cell.provide("log/append", move |batch: Value, _| {
+            let entries: Vec<logging::LogEntry> =
+                serde_json::from_value(batch["entries"].clone()).unwrap_or_default();
+            sink.lock().unwrap().extend(entries);
+            Box::pin(async move { Ok(()) })
+        });
+        cell.provide("test/noisy", |_: (), _| {
+            Box::pin(async move {
+                warn!("inside handler");
+                Ok(())
+            })
+        });
+        assert!(
+            !cell.set_log_level(Level::WARN),
+            "logging not initialised yet"
+        );
+        let _logging = cell.scoped_logging();
+
+        let signal = Signal::new("test", "test/noisy", ());
+        let cid = signal.id.clone();
+        assert!(cell.route(signal).await.ok);
+
+        let find = |message: &str| {
+            shipped
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|e| e.message == message)
+                .cloned()
+        };
+        let mut entry = None;
+        for _ in 0..30 {
+            sleep(Duration::from_millis(100)).await;
+            entry = find("inside handler");
+            if entry.is_some() {
+                break;
+            }
+        }
+        let entry = entry.expect("log line shipped");
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.cid.as_deref(), Some(cid.as_str()));
+        assert_eq!(entry.capability.as_deref(), Some("test/noisy"));
+
+        assert!(cell.set_log_level(Level::ERROR));
+        warn!("below the new level");
+        sleep(Duration::from_millis(1500)).await;
+        assert!(find("below the new level").is_none());
+    }
+
+    #[test]
+    fn test_config_layering() {
+        let toml = r#"
+            id = "orbital"
+            command = "cargo run --release"
+            critical = true
+
+            [env]
+            FOO = "bar"
+
+            [mesh]
+            port = 4100
+            bind_host = "127.0.0.1"
+            rpc_timeout_ms = 2500
+            log_format = "json"
+        "#;
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        let config = CellConfig::default()
+            .apply_toml(toml)
+            .unwrap()
+            .apply_vars(env(&[
+                ("RHEO_PORT", "4200"),
+                ("RHEO_SEED", ""),
+                ("RHEO_LOG_LEVEL", "debug"),
+            ]))
+            .unwrap();
+        assert_eq!(config.id, "orbital");
+        assert!(config.critical && !config.scalable);
+        assert_eq!(config.port, 4200, "env beats file");
+        assert!(config.bind_host.is_loopback());
+        assert_eq!(config.rpc_timeout_ms, 2500);
+        assert_eq!(config.seed, None, "empty vars are unset");
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert_eq!(config.log_level, Level::DEBUG);
+
+        let err = CellConfig::default()
+            .apply_toml("[mesh]\nrpc_timout_ms = 10")
+            .unwrap_err();
+        assert_eq!(err.key, "mesh.rpc_timout_ms");
+        let err = CellConfig::default()
+            .apply_toml("[mesh]\nport = 70000")
+            .unwrap_err();
+        assert_eq!(err.key, "mesh.port");
+        let err = CellConfig::default()
+            .apply_vars(env(&[("RHEO_BIND_HOST", "localhost")]))
+            .unwrap_err();
+        assert_eq!(err.key, "RHEO_BIND_HOST");
+        let err = CellConfig::default()
+            .apply_vars(env(&[("RHEO_GOSSIP_INTERVAL_MS", "0")]))
+            .unwrap_err();
+        assert_eq!(err.key, "RHEO_GOSSIP_INTERVAL_MS");
+        assert!(err.to_string().contains("RHEO_GOSSIP_INTERVAL_MS"));
+    }
+
+    #[tokio::test]
+    async fn test_manifest_lifecycle() {
+        let dir = std::env::temp_dir().join(format!("rheo-manifests-{}", Uuid::new_v4()));
+        let config = CellConfig {
+            id: "manifest-test".into(),
+            manifest_dir: Some(dir.to_string_lossy().into_owned()),
+            ghost_cleanup: true,
+            ..Default::default()
+        };
+        let path = dir.join("manifest-test.cell.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        let leave_manifest = |pid: u32, start_time: u64| {
+            std::fs::write(
+                &path,
+                serde_json::json!({
+                    "pid": pid, "version": "old", "port": 0, "startTime": start_time,
+                    "capabilities": [], "pubKey": "old",
+                })
+                .to_string(),
+            )
+            .unwrap();
+        };
+        let sleeper = || {
+            std::process::Command::new("sleep")
+                .arg("30")
+                .spawn()
+                .unwrap()
+        };
+
+        // The pid was recycled: its process started after the manifest was written
+        let mut bystander = sleeper();
+        leave_manifest(bystander.id(), now_millis() - 60_000);
+        let cell = RheoCell::new(config.clone());
+        cell.clone().listen().await.unwrap();
+        assert!(bystander.try_wait().unwrap().is_none(), "bystander spared");
+        cell.shutdown().await;
+        bystander.kill().unwrap();
+        bystander.wait().unwrap();
+
+        // A leftover manifest from a "crashed" instance that is still running
+        let mut ghost = sleeper();
+        leave_manifest(ghost.id(), now_millis());
+
+        let cell = RheoCell::new(config);
+        cell.provide("test/echo", |msg: String, _| {
+            Box::pin(async move { Ok(msg) })
+        });
+        let addr = cell.clone().listen().await.unwrap().addr();
+        assert!(ghost.wait().unwrap().code().is_none(), "ghost was killed");
+
+        let manifest: CellManifest =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(manifest.pid, std::process::id());
+        assert_eq!(manifest.port, addr.port());
+        assert_eq!(manifest.version, binary_version());
+        assert_eq!(manifest.version.len(), 16);
+        assert!(manifest.capabilities.contains(&"test/echo".to_string()));
+
+        cell.shutdown().await;
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cell_handle_lifecycle() {
+        let seed = RheoCell::new(CellConfig::default());
+        let seed_handle = seed.clone().listen().await.unwrap();
+        seed_handle.ready().await;
+
+        let cell = RheoCell::new(CellConfig {
+            seed: Some(format!("http://127.0.0.1:{}", seed_handle.addr().port())),
+            ..Default::default()
+        });
+        let handle = cell.clone().listen().await.unwrap();
+        handle.ready().await;
+        assert!(
+            cell.atlas.contains_key(&seed.id),
+            "ready means bootstrapped"
+        );
+
+        let joined = tokio::spawn(handle.clone().join());
+        let started = Instant::now();
+        // A clone of the cell can stop the server it didn't start
+        RheoCell::clone(&cell).shutdown().await;
+        joined.await.unwrap();
+        assert!(
+            started.elapsed() < Duration::from_secs(2),
+            "tasks cancelled promptly"
+        );
+
+        let health = format!("http://127.0.0.1:{}/health", handle.addr().port());
+        assert!(reqwest::get(&health).await.is_err(), "server stopped");
+
+        seed_handle.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_drains() {
+        let fast_gossip = || CellConfig {
+            gossip_interval_ms: 200,
+            ..Default::default()
+        };
+        let seed = RheoCell::new(fast_gossip());
+        let seed_handle = seed.clone().listen().await.unwrap();
+        let seed_url = format!("http://127.0.0.1:{}", seed_handle.addr().port());
+
+        let start_worker = |id: &str| {
+            let worker = RheoCell::new(CellConfig {
+                id: id.to_string(),
+                seed: Some(seed_url.clone()),
+                ..fast_gossip()
+            });
+            worker.provide("svc/work", |n: i64, _| {
+                Box::pin(async move {
+                    sleep(Duration::from_millis(50)).await;
+                    Ok(n + 1)
+                })
+            });
+            worker
+        };
+        let worker_a = start_worker("worker-a");
+        let worker_b = start_worker("worker-b");
+        worker_a.clone().listen().await.unwrap().ready().await;
+        worker_b.clone().listen().await.unwrap().ready().await;
+
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(seed_url.clone()),
+            ..fast_gossip()
+        });
+        caller.clone().listen().await.unwrap().ready().await;
+
+        // Everyone must know everyone, so the departure reaches the caller directly
+        wait_until("workers and caller to know each other", || {
+            ["worker-a", "worker-b"].iter().all(|id| {
+                caller
+                    .atlas
+                    .get(*id)
+                    .is_some_and(|e| e.serves("svc/work", None))
+            }) && worker_a.atlas.contains_key(&caller.id)
+        })
+        .await;
+
+        let load = {
+            let caller = caller.clone();
+            tokio::spawn(async move {
+                let calls = (0..60i64).map(|n| {
+                    let caller = caller.clone();
+                    async move {
+                        sleep(Duration::from_millis(n as u64 * 20)).await;
+                        let result: Result<i64, MeshError> =
+                            caller.mesh_proxy().call("svc/work", n).await;
+                        (n, result)
+                    }
+                });
+                join_all(calls).await
+            })
+        };
+
+        sleep(Duration::from_millis(300)).await;
+        worker_a.shutdown().await;
+        assert!(
+            caller.atlas.get("worker-a").is_some_and(|e| e.leaving),
+            "departure announced"
+        );
+        assert!(worker_a.active_executions.is_empty(), "drained before exit");
+
+        let restarted = start_worker("worker-a");
+        restarted.clone().listen().await.unwrap().ready().await;
+
+        for (n, result) in load.await.unwrap() {
+            assert_eq!(result.unwrap(), n + 1, "call {} failed during restart", n);
+        }
+        wait_until("the restarted instance to replace the tombstone", || {
+            caller.atlas.get("worker-a").is_some_and(|e| !e.leaving)
+        })
+        .await;
+
+        for cell in [&restarted, &worker_b, &caller, &seed] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_authorization() {
+        let admin_key = SigningKey::generate(&mut OsRng);
+        let target = RheoCell::new(CellConfig {
+            admin_token: Some("s3cret".into()),
+            admin_keys: vec![hex::encode(VerifyingKey::from(&admin_key).to_bytes())],
+            ..Default::default()
+        });
+        let port = target.clone().listen().await.unwrap().addr().port();
+        let addr = format!("http://127.0.0.1:{}", port);
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(addr.clone()),
+            ..Default::default()
+        });
+        caller.clone().listen().await.unwrap().ready().await;
+        let signal = |cap: &str, args: Value| Signal::new(&caller.id, cap, args);
+        let denied = |result: TraceResult| {
+            result
+                .error
+                .is_some_and(|e| e.code == ErrorCode::Unauthorized)
+        };
+
+        // Reaching the port is no longer enough
+        let anonymous = caller
+            .rpc_raw(&addr, signal("cell/shutdown", serde_json::json!({})))
+            .await;
+        assert!(denied(anonymous));
+        let guessed = signal("cell/inspect", serde_json::json!({})).with_admin_token("guess");
+        assert!(denied(caller.rpc_raw(&addr, guessed).await));
+        let intruder = SigningKey::generate(&mut OsRng);
+        let forged = signal("cell/inspect", serde_json::json!({}))
+            .with_admin_signature(&intruder, &target.id);
+        assert!(denied(caller.rpc_raw(&addr, forged).await));
+        // A proof only covers the args it was signed with
+        let mut tampered = signal("cell/evict_peer", serde_json::json!({ "id": "someone" }))
+            .with_admin_signature(&admin_key, &target.id);
+        tampered.payload.args = serde_json::json!({ "id": caller.id });
+        assert!(denied(caller.rpc_raw(&addr, tampered).await));
+
+        let inspect = signal("cell/inspect", serde_json::json!({})).with_admin_token("s3cret");
+        let inspected = caller.rpc_raw(&addr, inspect).await;
+        assert!(inspected.ok, "{:?}", inspected.error);
+        let envelope = target.ledger.get(&inspected.cid).unwrap();
+        assert!(envelope
+            .ancestry
+            .iter()
+            .any(|a| a.action == "ADMIN_AUTHORIZED"));
+        assert!(
+            !serde_json::to_string(&envelope).unwrap().contains("s3cret"),
+            "token never recorded"
+        );
+
+        let evict = signal("cell/evict_peer", serde_json::json!({ "id": caller.id }))
+            .with_admin_signature(&admin_key, &target.id);
+
+        // Another cell trusting the same key won't take a proof made for target
+        let sibling = RheoCell::new(CellConfig {
+            admin_keys: vec![hex::encode(VerifyingKey::from(&admin_key).to_bytes())],
+            ..Default::default()
+        });
+        let sibling_addr = format!(
+            "http://127.0.0.1:{}",
+            sibling.clone().listen().await.unwrap().addr().port()
+        );
+        let replayed = caller.rpc_raw(&sibling_addr, evict.clone()).await;
+        assert!(denied(replayed));
+        let own = signal("cell/inspect", serde_json::json!({}))
+            .with_admin_signature(&admin_key, &sibling.id);
+        assert!(caller.rpc_raw(&sibling_addr, own).await.ok);
+
+        let evicted = caller.rpc_raw(&addr, evict).await;
+        assert_eq!(evicted.value.unwrap()["evicted"], true);
+        assert!(target.atlas.get(&caller.id).is_some_and(|e| e.leaving));
+
+        for cell in [&caller, &sibling, &target] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_proof_replay() {
+        let admin_key = SigningKey::generate(&mut OsRng);
+        let cell = RheoCell::new(CellConfig {
+            admin_token: Some("s3cret".into()),
+            admin_keys: vec![hex::encode(VerifyingKey::from(&admin_key).to_bytes())],
+            result_cache_ttl_ms: 0,
+            ..Default::default()
+        });
+        let denied = |result: TraceResult| {
+            result
+                .error
+                .is_some_and(|e| e.code == ErrorCode::Unauthorized)
+        };
+
+        let inspect =
+            Signal::new("ops", "cell/inspect", ()).with_admin_signature(&admin_key, &cell.id);
+        assert!(cell.route(inspect.clone()).await.ok);
+        // Nothing replays the result, and the proof itself is spent
+        assert!(denied(cell.route(inspect.clone()).await));
+        // Nor does it hold for another sender
+        let mut resent = inspect.clone();
+        resent.from = "mallory".into();
+        assert!(denied(cell.route(resent).await));
+
+        // Reloading the credentials doesn't forget what was used
+        let reload = Signal::new("ops", "cell/reload_config", ()).with_admin_token("s3cret");
+        assert!(cell.route(reload).await.ok);
+        assert!(denied(cell.route(inspect).await));
+        let fresh =
+            Signal::new("ops", "cell/inspect", ()).with_admin_signature(&admin_key, &cell.id);
+        assert!(cell.route(fresh).await.ok);
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_code_config() {
+        let admin_key = SigningKey::generate(&mut OsRng);
+        let cell = RheoCell::new(CellConfig {
+            admin_token: Some("s3cret".into()),
+            admin_keys: vec![hex::encode(VerifyingKey::from(&admin_key).to_bytes())],
+            ..Default::default()
+        });
+        cell.set_policy(
+            policy::Policy::parse("[[rule]]\neffect = \"deny\"\ncapabilities = [\"svc/*\"]")
+                .unwrap(),
+        );
+        cell.provide("svc/locked", |_: Value, _| Box::pin(async move { Ok(()) }));
+
+        let reload = Signal::new("ops", "cell/reload_config", ()).with_admin_token("s3cret");
+        let reloaded = cell.route(reload).await;
+        assert!(reloaded.ok, "{:?}", reloaded.error);
+        let summary = reloaded.value.unwrap();
+        assert_eq!(summary["adminToken"], true);
+        assert_eq!(summary["adminKeys"], 1);
+        assert_eq!(summary["policyRules"], 1);
+
+        // Still locked down, and the operator still gets in
+        let locked = cell.route(Signal::new("ops", "svc/locked", ())).await;
+        assert_eq!(locked.error.unwrap().code, ErrorCode::Unauthorized);
+        let inspect = Signal::new("ops", "cell/inspect", ()).with_admin_token("s3cret");
+        assert!(cell.route(inspect).await.ok);
+        let signed =
+            Signal::new("ops", "cell/inspect", ()).with_admin_signature(&admin_key, &cell.id);
+        assert!(cell.route(signed).await.ok);
+    }
+
+    #[tokio::test]
+    async fn test_policy_rules() {
+        assert!(policy::glob("trading/*", "trading/place_order"));
+        assert!(policy::glob("*/place_*", "trading/place_order"));
+        assert!(!policy::glob("trading/*", "risk/check"));
+        assert!(!policy::glob("risk", "risky"));
+
+        let unpinned = policy::Policy::parse(
+            "[[rule]]\neffect = \"allow\"\ncapabilities = [\"svc/*\"]\ncallers = [\"risk\"]",
+        )
+        .unwrap_err();
+        assert_eq!(unpinned.key, "rule[0].callers[0]");
+
+        let risk_key = SigningKey::from_bytes(&[7; 32]);
+        let path = std::env::temp_dir().join(format!("rheo-policy-{}.toml", Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+                [[rule]]
+                name = "orders-from-risk"
+                effect = "allow"
+                capabilities = ["svc/place_order"]
+                callers = ["risk"]
+
+                [[rule]]
+                effect = "deny"
+                capabilities = ["svc/*"]
+
+                [identities]
+                risk = "{}"
+                "#,
+                hex::encode(risk_key.verifying_key().to_bytes())
+            ),
+        )
+        .unwrap();
+
+        let provider = RheoCell::new(CellConfig {
+            policy_file: Some(path.display().to_string()),
+            ..Default::default()
+        });
+        provider.provide("svc/place_order", |qty: i64, _| {
+            Box::pin(async move { Ok(qty) })
+        });
+        let addr = provider.clone().listen().await.unwrap().addr();
+        let seed = format!("http://127.0.0.1:{}", addr.port());
+
+        let join = |id: &str, identity_key: Option<String>| {
+            RheoCell::new(CellConfig {
+                id: id.to_string(),
+                seed: Some(seed.clone()),
+                identity_key,
+                ..Default::default()
+            })
+        };
+        let risk = join("risk", Some(hex::encode(risk_key.to_bytes())));
+        let intruder = join("intruder", None);
+        risk.clone().listen().await.unwrap().ready().await;
+        intruder.clone().listen().await.unwrap().ready().await;
+
+        let allowed = risk.ask_mesh("svc/place_order", 5).await;
+        assert_eq!(allowed.value, Some(serde_json::json!(5)));
+
+        let refused = intruder.ask_mesh("svc/place_order", 5).await;
+        let error = refused.error.unwrap();
+        assert_eq!(error.code, ErrorCode::Unauthorized);
+        let details = error.details.unwrap();
+        assert_eq!(details["rule"]["name"], "rule[1]");
+        assert_eq!(details["verified"], false, "intruder has no pinned key");
+
+        // Claiming to be risk doesn't help without risk's key...
+        let mut forged = Signal::new("risk", "svc/place_order", 5);
+        forged.sign_origin(&SigningKey::generate(&mut OsRng));
+        let impersonated = intruder.rpc_raw(&seed, forged).await;
+        let details = impersonated.error.unwrap().details.unwrap();
+        assert_eq!(details["caller"], "risk");
+        assert_eq!(details["verified"], false);
+
+        // ...nor does gossiping a different key for risk into the atlas...
+        let mut poisoned = AtlasEntry::new(
+            "risk".to_string(),
+            intruder.addr.read().await.clone(),
+            vec![],
+        )
+        .with_pub_key(intruder.pub_key_hex.clone());
+        poisoned.last_seen = now_millis() + 1;
+        provider.merge_atlas(HashMap::from([("risk".to_string(), poisoned)]), true);
+        let mut spoofed = Signal::new("risk", "svc/place_order", 5);
+        spoofed.sign_origin(&intruder.signing_key);
+        let refused = intruder.rpc_raw(&seed, spoofed).await;
+        assert_eq!(refused.error.unwrap().details.unwrap()["verified"], false);
+
+        // ...nor replaying a proof risk signed long ago
+        let mut replayed = Signal::new("risk", "svc/place_order", 5);
+        let stale = now_millis() - admin::MAX_PROOF_AGE_MS - 1_000;
+        replayed
+            .proofs
+            .insert(policy::ORIGIN_TS_PROOF.to_string(), stale.to_string());
+        let signature = risk_key.sign(policy::origin_message(&replayed).as_bytes());
+        replayed.proofs.insert(
+            policy::ORIGIN_PROOF.to_string(),
+            hex::encode(signature.to_bytes()),
+        );
+        let refused = intruder.rpc_raw(&seed, replayed).await;
+        assert_eq!(refused.error.unwrap().details.unwrap()["verified"], false);
+
+        std::fs::write(&path, "default = \"allow\"").unwrap();
+        provider.reload_policy().unwrap();
+        assert!(intruder.ask_mesh("svc/place_order", 5).await.ok);
+
+        std::fs::write(&path, "[[rule]]\neffect = \"maybe\"").unwrap();
+        assert!(
+            provider.reload_policy().is_err(),
+            "broken policy is rejected"
+        );
+        assert!(
+            intruder.ask_mesh("svc/place_order", 5).await.ok,
+            "previous policy kept"
+        );
+
+        let _ = std::fs::remove_file(&path);
+        for cell in [&risk, &intruder, &provider] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inbound_signals_cannot_borrow_our_identity() {
+        let gateway_key = SigningKey::from_bytes(&[9; 32]);
+        let policy = policy::Policy::parse(&format!(
+            r#"
+            default = "deny"
+
+            [[rule]]
+            effect = "allow"
+            capabilities = ["svc/*"]
+            callers = ["gateway"]
+
+            [identities]
+            gateway = "{}"
+            "#,
+            hex::encode(gateway_key.verifying_key().to_bytes())
+        ))
+        .unwrap();
+
+        let provider = RheoCell::new(CellConfig::default());
+        provider.set_policy(policy);
+        provider.provide("svc/secret", |_: Value, _| {
+            Box::pin(async move { Ok("protected") })
+        });
+        let addr = provider.clone().listen().await.unwrap().addr();
+        let seed = format!("http://127.0.0.1:{}", addr.port());
+
+        let gateway = RheoCell::new(CellConfig {
+            id: "gateway".into(),
+            seed: Some(seed.clone()),
+            identity_key: Some(hex::encode(gateway_key.to_bytes())),
+            ..Default::default()
+        });
+        let gateway_addr = format!(
+            "http://127.0.0.1:{}",
+            gateway.clone().listen().await.unwrap().addr().port()
+        );
+        let outsider = RheoCell::new(CellConfig {
+            id: "outsider".into(),
+            seed: Some(seed),
+            ..Default::default()
+        });
+        outsider.clone().listen().await.unwrap().ready().await;
+
+        let allowed = gateway.ask_mesh("svc/secret", ()).await;
+        assert_eq!(allowed.value, Some(serde_json::json!("protected")));
+
+        // Unsigned signals posted to the gateway claiming to be the gateway
+        let forged =
+            || Signal::new("gateway", "svc/secret", ()).with_proof(policy::CALLER_PROOF, "nobody");
+        let refused = outsider.rpc_raw(&gateway_addr, forged()).await;
+        assert_eq!(refused.error.unwrap().code, ErrorCode::Unauthorized);
+        let batched = outsider
+            .rpc_batch(&gateway_addr, vec![forged()])
+            .await
+            .unwrap();
+        assert_eq!(
+            batched[0].error.as_ref().unwrap().code,
+            ErrorCode::Unauthorized
+        );
+
+        // Signed with someone else's key is no better
+        let mut resigned = forged();
+        resigned.sign_origin(&outsider.signing_key);
+        let refused = outsider.rpc_raw(&gateway_addr, resigned).await;
+        assert_eq!(refused.error.unwrap().code, ErrorCode::Unauthorized);
+
+        // Relayed under the sender's own name, the policy still turns it away
+        let relayed = outsider
+            .rpc_raw(&gateway_addr, Signal::new("outsider", "svc/secret", ()))
+            .await;
+        assert_eq!(relayed.error.unwrap().code, ErrorCode::Unauthorized);
+
+        for cell in [&outsider, &gateway, &provider] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+    struct Quote {
+        symbol: String,
+        price: Doubled,
+    }
+
+    #[tokio::test]
+    async fn test_openapi_document() {
+        let cell = RheoCell::new(CellConfig::default());
+        cell.use_router(
+            router::Router::new().procedure(
+                "market/quote",
+                router::Procedure::query(|symbol: String, _| async move {
+                    Ok(Quote {
+                        symbol,
+                        price: Doubled { value: 2 },
+                    })
+                })
+                .with_schemas()
+                .describe("Latest quote"),
+            ),
+        );
+        cell.provide("market/reset", |_: Value, _| {
+            Box::pin(async move { Ok(()) })
+        });
+        let addr = cell.clone().listen().await.unwrap().addr();
+
+        let document: Value = reqwest::get(format!("http://{}/openapi.json", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(document["openapi"], "3.1.0");
+        assert_eq!(document["servers"][0]["url"], "{gateway}");
+        let paths = document["paths"].as_object().unwrap();
+        assert_eq!(paths.len(), 2, "cell/* and mesh/* stay internal");
+
+        let quote = &paths["/api/market/quote"]["post"];
+        assert_eq!(quote["operationId"], "market__quote");
+        assert_eq!(quote["summary"], "Latest quote");
+        assert_eq!(quote["x-rheo-mutation"], false);
+        assert_eq!(
+            quote["requestBody"]["content"]["application/json"]["schema"]["type"],
+            "string"
+        );
+        let output = &quote["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(
+            output["properties"]["value"]["properties"]["price"]["$ref"],
+            "#/components/schemas/Doubled"
+        );
+        assert!(document["components"]["schemas"]["Doubled"].is_object());
+
+        let not_found = &quote["responses"]["404"]["content"]["application/json"]["schema"];
+        assert_eq!(
+            not_found["properties"]["error"]["allOf"][1]["properties"]["code"]["enum"],
+            serde_json::json!(["NotFound"])
+        );
+        assert!(quote["responses"]["503"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("CIRCUIT_OPEN"));
+
+        let reset = &paths["/api/market/reset"]["post"];
+        assert_eq!(reset["x-rheo-mutation"], true);
+        assert_eq!(reset["requestBody"]["required"], false);
+
+        cell.shutdown().await;
+    }
+
+    mod ledger {
+        #[derive(serde::Serialize, schemars::JsonSchema)]
+        pub struct Doubled {
+            pub amount: String,
+        }
+
+        #[derive(serde::Serialize, schemars::JsonSchema)]
+        pub struct MeshError {
+            pub reason: String,
+        }
+
+        #[derive(serde::Serialize, schemars::JsonSchema)]
+        pub struct Entry {
+            pub total: Doubled,
+            pub error: Option<MeshError>,
+        }
+    }
+
+    #[test]
+    fn test_openapi_renames_conflicting_definitions() {
+        let schema = |s: schemars::schema::RootSchema| serde_json::to_value(s).unwrap();
+        let descriptor = |capability: &str, output: Value| tools::CapabilityDescriptor {
+            capability: capability.to_string(),
+            input_schema: None,
+            output_schema: Some(output),
+            description: None,
+            is_mutation: false,
+            version: None,
+            provider: "test".to_string(),
+        };
+        let descriptors = vec![
+            descriptor("market/quote", schema(schemars::schema_for!(Quote))),
+            descriptor("ledger/entry", schema(schemars::schema_for!(ledger::Entry))),
+            descriptor("ledger/again", schema(schemars::schema_for!(ledger::Entry))),
+        ];
+        let document = openapi::OpenApi::new("test").document(&descriptors);
+        let schemas = &document["components"]["schemas"];
+        let output = |path: &str| {
+            document["paths"][path]["post"]["responses"]["200"]["content"]["application/json"]
+                ["schema"]["properties"]["value"]
+                .clone()
+        };
+
+        assert_eq!(schemas["Doubled"]["properties"]["value"]["type"], "integer");
+        assert_eq!(
+            schemas["ledger_Doubled"]["properties"]["amount"]["type"],
+            "string"
+        );
+        assert!(schemas["MeshError"]["properties"]["code"].is_object());
+        assert_eq!(
+            schemas["ledger_MeshError"]["properties"]["reason"]["type"],
+            "string"
+        );
+        assert_eq!(
+            output("/api/market/quote")["properties"]["price"]["$ref"],
+            "#/components/schemas/Doubled"
+        );
+        for path in ["/api/ledger/entry", "/api/ledger/again"] {
+            let entry = output(path);
+            assert_eq!(
+                entry["properties"]["total"]["$ref"],
+                "#/components/schemas/ledger_Doubled"
+            );
+            assert_eq!(
+                entry["properties"]["error"]["anyOf"][0]["$ref"],
+                "#/components/schemas/ledger_MeshError"
+            );
+        }
+        assert!(
+            schemas.get("ledger_Doubled_2").is_none(),
+            "identical types are shared"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_websocket_multiplexing() {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let cell = RheoCell::new(CellConfig {
+            ws_heartbeat_ms: 100,
+            narrative_events: true,
+            ..Default::default()
+        });
+        cell.provide("ws/slow", |_: Value, _| {
+            Box::pin(async move {
+                sleep(Duration::from_millis(200)).await;
+                Ok("slow")
+            })
+        });
+        cell.provide("ws/fast", |_: Value, _| Box::pin(async move { Ok("fast") }));
+        let addr = cell.clone().listen().await.unwrap().addr();
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .unwrap();
+        let hello = socket.next().await.unwrap().unwrap();
+        let hello: Value = serde_json::from_str(hello.to_text().unwrap()).unwrap();
+        assert_eq!(hello["heartbeatMs"], 100);
+
+        let send = |frame: Value| Message::Text(frame.to_string());
+        let slow = Signal::new("ui", "ws/slow", ());
+        let fast = Signal::new("ui", "ws/fast", ());
+        for frame in [
+            serde_json::json!({ "type": "subscribe", "topic": "narrative/*" }),
+            serde_json::json!({ "type": "subscribe", "topic": "ticks" }),
+            serde_json::json!({ "type": "signal", "signal": slow }),
+            serde_json::json!({ "type": "signal", "signal": fast }),
+        ] {
+            socket.send(send(frame)).await.unwrap();
+        }
+
+        let mut results = Vec::new();
+        let mut narratives = Vec::new();
+        while results.len() < 2 {
+            let Message::Text(text) = socket.next().await.unwrap().unwrap() else {
+                continue;
+            };
+            let frame: Value = serde_json::from_str(&text).unwrap();
+            match frame["type"].as_str().unwrap() {
+                "result" => {
+                    assert_eq!(frame["result"]["cid"], frame["id"]);
+                    results.push(frame["id"].as_str().unwrap().to_string());
+                }
+                "event" => narratives.push(frame["topic"].as_str().unwrap().to_string()),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            results,
+            vec![fast.id.clone(), slow.id.clone()],
+            "completion order"
+        );
+        assert!(narratives.contains(&format!("narrative/{}", fast.id)));
+
+        cell.publish("other", "ignored");
+        cell.publish("ticks", serde_json::json!({ "px": 101 }));
+        let event = loop {
+            let Message::Text(text) = socket.next().await.unwrap().unwrap() else {
+                continue;
+            };
+            let frame: Value = serde_json::from_str(&text).unwrap();
+            if frame["type"] == "event"
+                && !frame["topic"].as_str().unwrap().starts_with("narrative/")
+            {
+                break frame;
+            }
+        };
+        assert_eq!(event["topic"], "ticks");
+        assert_eq!(event["data"]["px"], 101);
+
+        // Stop reading (so no pongs go back) and the cell gives up on us
+        sleep(Duration::from_millis(400)).await;
+        let closed = timeout(Duration::from_secs(2), async {
+            loop {
+                match socket.next().await {
+                    Some(Ok(Message::Close(Some(frame)))) => return u16::from(frame.code),
+                    Some(Ok(_)) => continue,
+                    _ => return 0,
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(closed, ws::CLOSE_HEARTBEAT_TIMEOUT);
+        cell.shutdown().await;
+
+        // Off by default - nobody sees other callers' signals
+        let quiet = RheoCell::new(CellConfig::default());
+        quiet.provide("ws/fast", |_: Value, _| Box::pin(async move { Ok("fast") }));
+        let mut events = quiet.subscribe_events();
+        assert!(quiet.route(Signal::new("ui", "ws/fast", ())).await.ok);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_balancing() {
+        use balance::LoadBalancer;
+
+        let entries = |addrs: &[&str]| -> Vec<AtlasEntry> {
+            addrs
+                .iter()
+                .map(|a| AtlasEntry::new(*a, *a, vec!["lb/work".into()]))
+                .collect()
+        };
+        let order = |providers: &[AtlasEntry]| -> Vec<String> {
+            providers.iter().map(|p| p.addr.clone()).collect()
+        };
+        let stats = balance::PeerStats::default();
+        stats.record("slow", Duration::from_millis(100));
+        stats.record("fast", Duration::from_millis(5));
+
+        let mut providers = entries(&["slow", "fast", "new"]);
+        balance::Ewma.rank("lb/work", &mut providers, &stats);
+        assert_eq!(
+            order(&providers),
+            ["new", "fast", "slow"],
+            "unmeasured get probed"
+        );
+
+        let busy = [stats.begin("fast"), stats.begin("fast")];
+        let mut providers = entries(&["slow", "fast"]);
+        balance::LeastOutstanding.rank("lb/work", &mut providers, &stats);
+        assert_eq!(order(&providers), ["slow", "fast"]);
+        drop(busy);
+        assert_eq!(stats.outstanding("fast"), 0);
+
+        let round_robin = balance::RoundRobin::default();
+        let firsts: Vec<String> = (0..3)
+            .map(|_| {
+                let mut providers = entries(&["a", "b", "c"]);
+                round_robin.rank("lb/work", &mut providers, &stats);
+                providers[0].addr.clone()
+            })
+            .collect();
+        assert_eq!(firsts, ["a", "b", "c"]);
+
+        let config = CellConfig::default()
+            .apply_vars(|name| {
+                (name == "RHEO_LOAD_BALANCER").then(|| "least_outstanding, lb/*=round-robin".into())
+            })
+            .unwrap();
+        assert_eq!(config.load_balancer, balance::Strategy::LeastOutstanding);
+        assert_eq!(
+            config.load_balancers,
+            vec![("lb/*".to_string(), balance::Strategy::RoundRobin)]
+        );
+        assert!(CellConfig::default()
+            .apply_vars(|name| (name == "RHEO_LOAD_BALANCER").then(|| "fastest".into()))
+            .is_err());
+
+        // A slow provider stops getting most of the traffic once measured
+        let start_provider = |id: &str, delay_ms: u64, seed: Option<String>| {
+            let cell = RheoCell::new(CellConfig {
+                id: id.to_string(),
+                seed,
+                ..Default::default()
+            });
+            let name = id.to_string();
+            cell.provide("lb/work", move |_: Value, _| {
+                let name = name.clone();
+                Box::pin(async move {
+                    sleep(Duration::from_millis(delay_ms)).await;
+                    Ok(name)
+                })
+            });
+            cell
+        };
+        let slow = start_provider("lb-slow", 150, None);
+        let seed = format!(
+            "http://127.0.0.1:{}",
+            slow.clone().listen().await.unwrap().addr().port()
+        );
+        let fast = start_provider("lb-fast", 0, Some(seed.clone()));
+        fast.clone().listen().await.unwrap().ready().await;
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(seed),
+            ..Default::default()
+        });
+        caller.clone().listen().await.unwrap().ready().await;
+        wait_until("both lb providers in the atlas", || {
+            ["lb-slow", "lb-fast"]
+                .iter()
+                .all(|id| caller.atlas.contains_key(*id))
+        })
+        .await;
+
+        let mut served = HashMap::new();
+        for _ in 0..20 {
+            let by: String = caller.ask_mesh("lb/work", ()).await.into_value().unwrap();
+            *served.entry(by).or_insert(0) += 1;
+        }
+        assert!(
+            served.get("lb-fast").copied().unwrap_or(0) >= 17,
+            "{:?}",
+            served
+        );
+        assert!(caller.atlas.get("lb-slow").unwrap().latency_ms.unwrap() >= 100);
+
+        for cell in [&caller, &fast, &slow] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shard_routing() {
+        let ids = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let keys: Vec<String> = (0..200).map(|i| format!("sim-{}", i)).collect();
+        let three = shard::Ring::new(ids(&["a", "b", "c"]));
+        let four = shard::Ring::new(ids(&["a", "b", "c", "d"]));
+        for key in &keys {
+            let (before, after) = (three.owner(key).unwrap(), four.owner(key).unwrap());
+            assert!(
+                before == after || after == "d",
+                "{} moved {} -> {}",
+                key,
+                before,
+                after
+            );
+        }
+        let moved = keys.iter().filter(|k| four.owner(k) == Some("d")).count();
+        assert!(
+            (20..90).contains(&moved),
+            "the new member takes about a quarter: {}",
+            moved
+        );
+        assert_eq!(three.successors("sim-1").len(), 3);
+
+        let args = serde_json::json!({ "sim": { "id": 7 }, "name": "x" });
+        assert_eq!(shard::key_of(&args, "sim.id").as_deref(), Some("7"));
+        assert_eq!(shard::key_of(&args, "name").as_deref(), Some("x"));
+        assert_eq!(
+            shard::key_of(&serde_json::json!("raw"), "").as_deref(),
+            Some("raw")
+        );
+        assert_eq!(shard::key_of(&args, "missing"), None);
+
+        let start_provider = |id: &str, seed: Option<String>| {
+            let cell = RheoCell::new(CellConfig {
+                id: id.to_string(),
+                seed,
+                gossip_interval_ms: 200,
+                ..Default::default()
+            });
+            let name = id.to_string();
+            cell.provide("kv/owner", move |_: Value, _| {
+                let name = name.clone();
+                Box::pin(async move { Ok(name) })
+            });
+            cell.shard_by("kv/owner", "key");
+            cell
+        };
+        let a = start_provider("shard-a", None);
+        let seed = format!(
+            "http://127.0.0.1:{}",
+            a.clone().listen().await.unwrap().addr().port()
+        );
+        let b = start_provider("shard-b", Some(seed.clone()));
+        let c = start_provider("shard-c", Some(seed.clone()));
+        b.clone().listen().await.unwrap().ready().await;
+        c.clone().listen().await.unwrap().ready().await;
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(seed),
+            gossip_interval_ms: 200,
+            ..Default::default()
+        });
+        caller.clone().listen().await.unwrap().ready().await;
+
+        let members = ["shard-a", "shard-b", "shard-c"];
+        wait_until("every cell to see all shard providers", || {
+            [&caller, &a, &b, &c].iter().all(|cell| {
+                members.iter().all(|id| {
+                    cell.atlas.get(*id).is_some_and(|e| {
+                        e.cap_info
+                            .get("kv/owner")
+                            .is_some_and(|i| i.shard_key.is_some())
+                    })
+                })
+            })
+        })
+        .await;
+
+        let ring = shard::Ring::new(ids(&members));
+        let owner_of = |cell: &Arc<RheoCell>, key: String| {
+            let cell = Arc::clone(cell);
+            async move {
+                cell.ask_mesh("kv/owner", serde_json::json!({ "key": key }))
+                    .await
+                    .into_value::<String>()
+                    .unwrap()
+            }
+        };
+        for key in keys.iter().take(12) {
+            let expected = ring.owner(key).unwrap();
+            assert_eq!(owner_of(&caller, key.clone()).await, expected);
+            // Providers don't answer keys they don't own themselves
+            assert_eq!(owner_of(&a, key.clone()).await, expected);
+        }
+
+        let owned_by_a = keys
+            .iter()
+            .find(|k| ring.owner(k) == Some("shard-a"))
+            .unwrap();
+        assert!(a.owns_shard("kv/owner", owned_by_a));
+        assert!(!b.owns_shard("kv/owner", owned_by_a));
+
+        // The ring is built once per set of providers
+        let cached = caller.shard_ring("kv/owner");
+        assert_eq!(cached.members(), &ids(&members)[..]);
+        assert!(Arc::ptr_eq(&cached, &caller.shard_ring("kv/owner")));
+        caller.atlas.insert(
+            "shard-d".into(),
+            AtlasEntry::new("shard-d", "http://127.0.0.1:1", vec!["kv/owner".into()]),
+        );
+        let grown = caller.shard_ring("kv/owner");
+        assert!(!Arc::ptr_eq(&cached, &grown));
+        assert!(grown.members().contains(&"shard-d".to_string()));
+
+        for cell in [&caller, &c, &b, &a] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hedged_requests() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+        // The first call after `stall` is set hangs, wherever it lands
+        let stall = Arc::new(AtomicBool::new(false));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let start_provider = |id: &str, seed: Option<String>| {
+            let cell = RheoCell::new(CellConfig {
+                id: id.to_string(),
+                seed,
+                gossip_interval_ms: 200,
+                ..Default::default()
+            });
+            let (stall, calls) = (stall.clone(), calls.clone());
+            let handle = move || {
+                let (stall, calls) = (stall.clone(), calls.clone());
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    if stall.swap(false, Ordering::SeqCst) {
+                        sleep(Duration::from_millis(600)).await;
+                    }
+                    Ok(())
+                }
+            };
+            let query = handle.clone();
+            cell.use_router(
+                router::Router::new()
+                    .procedure(
+                        "quote/get",
+                        router::Procedure::query(move |_: Value, _| query()),
+                    )
+                    .procedure(
+                        "quote/set",
+                        router::Procedure::mutation(move |_: Value, _| handle()),
+                    ),
+            );
+            cell
+        };
+        let a = start_provider("hedge-a", None);
+        let seed = format!(
+            "http://127.0.0.1:{}",
+            a.clone().listen().await.unwrap().addr().port()
+        );
+        let b = start_provider("hedge-b", Some(seed.clone()));
+        b.clone().listen().await.unwrap().ready().await;
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(seed),
+            gossip_interval_ms: 200,
+            hedge: vec!["quote/*".into()],
+            ..Default::default()
+        });
+        caller.clone().listen().await.unwrap().ready().await;
+        wait_until("hedge providers with their query flags", || {
+            ["hedge-a", "hedge-b"].iter().all(|id| {
+                caller.atlas.get(*id).is_some_and(|e| {
+                    e.cap_info.get("quote/get").is_some_and(|i| i.query)
+                        && e.caps.iter().any(|c| c == "quote/set")
+                        && e.cap_info.get("quote/set").is_none_or(|i| !i.query)
+                })
+            })
+        })
+        .await;
+
+        for capability in ["quote/get", "quote/set"] {
+            for _ in 0..12 {
+                assert!(caller.ask_mesh(capability, Value::Null).await.ok);
+            }
+        }
+        assert!(caller.balancing.call_percentile("quote/get", 95).is_some());
+
+        // A slow query is answered by the hedge
+        calls.store(0, Ordering::SeqCst);
+        stall.store(true, Ordering::SeqCst);
+        let started = Instant::now();
+        assert!(caller.ask_mesh("quote/get", Value::Null).await.ok);
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // A slow mutation is never duplicated
+        sleep(Duration::from_millis(700)).await;
+        calls.store(0, Ordering::SeqCst);
+        stall.store(true, Ordering::SeqCst);
+        let started = Instant::now();
+        assert!(caller.ask_mesh("quote/set", Value::Null).await.ok);
+        assert!(started.elapsed() >= Duration::from_millis(600));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        for cell in [&caller, &b, &a] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multicast_options() {
+        let start_provider = |id: &str, seed: Option<String>, answer: u64, delay_ms: u64| {
+            let cell = RheoCell::new(CellConfig {
+                id: id.to_string(),
+                seed,
+                gossip_interval_ms: 200,
+                ..Default::default()
+            });
+            cell.provide("poll/answer", move |_: Value, _| {
+                Box::pin(async move {
+                    sleep(Duration::from_millis(delay_ms)).await;
+                    Ok(answer)
+                })
+            });
+            cell
+        };
+        let a = start_provider("poll-a", None, 1, 0);
+        let seed = format!(
+            "http://127.0.0.1:{}",
+            a.clone().listen().await.unwrap().addr().port()
+        );
+        let b = start_provider("poll-b", Some(seed.clone()), 1, 0);
+        let c = start_provider("poll-c", Some(seed.clone()), 2, 400);
+        // The caller provides it too, but never asks itself
+        let caller = start_provider("poll-caller", Some(seed), 3, 0);
+        for cell in [&b, &c, &caller] {
+            cell.clone().listen().await.unwrap().ready().await;
+        }
+        wait_until("all poll providers in the atlas", || {
+            ["poll-a", "poll-b", "poll-c"]
+                .iter()
+                .all(|id| caller.atlas.contains_key(*id))
+        })
+        .await;
+
+        let all = caller.ask_all("poll/answer", (), 2000).await;
+        assert_eq!(all.results.len(), 3);
+        assert!(all.results.iter().all(|item| item.cell_id != "poll-caller"));
+        assert_eq!(all.reduce(multicast::vote::<u64>()), Some((1, 2)));
+        assert_eq!(all.reduce(multicast::min_by_key(|n: &u64| *n)), Some(1));
+        assert_eq!(all.reduce(multicast::max_by_key(|n: &u64| *n)), Some(2));
+        let mut merged = all.reduce(multicast::merge::<u64>());
+        merged.sort();
+        assert_eq!(merged, vec![1, 1, 2]);
+
+        let options = multicast::Options::new(2000);
+        let started = Instant::now();
+        let first = caller
+            .ask_all_with("poll/answer", (), options.with_first_success())
+            .await;
+        assert_eq!(first.results.len(), 1);
+        assert_eq!(first.reduce(multicast::merge::<u64>()), vec![1]);
+        let quorum = caller
+            .ask_all_with("poll/answer", (), options.with_quorum(2))
+            .await;
+        assert_eq!(quorum.reduce(multicast::vote::<u64>()), Some((1, 2)));
+        assert!(started.elapsed() < Duration::from_millis(400));
+
+        // Providers behind an open circuit are left out
+        let b_addr = caller.atlas.get("poll-b").unwrap().addr.clone();
+        let circuit = CircuitBreaker::new(1, 30000);
+        circuit.record_failure();
+        caller.circuits.insert(b_addr, circuit);
+        let all = caller
+            .ask_all_with("poll/answer", (), options.with_first(3))
+            .await;
+        let mut answered: Vec<_> = all.results.iter().map(|i| i.cell_id.as_str()).collect();
+        answered.sort();
+        assert_eq!(answered, vec!["poll-a", "poll-c"]);
+
+        // An unreachable provider counts once per multicast. A cell that isn't
+        // listening doesn't gossip, so nothing else touches the circuit.
+        let quiet = RheoCell::new(CellConfig::default());
+        let ghost_addr = "http://127.0.0.1:1".to_string();
+        quiet.atlas.insert(
+            "poll-ghost".into(),
+            AtlasEntry::new("poll-ghost", &ghost_addr, vec!["poll/answer".into()]),
+        );
+        for expected in 1..=2 {
+            let all = quiet.ask_all("poll/answer", (), 2000).await;
+            assert_eq!(all.failures.len(), 1);
+            let circuit = quiet.circuits.get(&ghost_addr).unwrap();
+            assert_eq!(circuit.failures.load(Ordering::SeqCst), expected);
+            assert!(!circuit.is_open());
+        }
+
+        for cell in [&caller, &c, &b, &a] {
+            cell.shutdown().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batched_signals() {
+        let provider = RheoCell::new(CellConfig {
+            id: "batch-provider".into(),
+            max_batch: 4,
+            ..Default::default()
+        });
+        provider.provide("calc/double", |n: u64, _| {
+            Box::pin(async move {
+                // Later calls finish first, yet results keep call order
+                sleep(Duration::from_millis(50 - n * 10)).await;
+                Ok(n * 2)
+            })
+        });
+        provider.provide("calc/fail", |_: Value, _| {
+            Box::pin(async move {
+                Err::<(), _>(MeshError::new(ErrorCode::HandlerError, "nope", "test"))
+            })
+        });
+        let addr = provider.clone().listen().await.unwrap().addr();
+
+        let signals: Vec<Signal> = (0..3)
+            .map(|n| Signal::new("tester", "calc/double", n))
+            .chain(std::iter::once(Signal::new("tester", "calc/fail", ())))
+            .collect();
+        let ids: Vec<String> = signals.iter().map(|s| s.id.clone()).collect();
+        let client = reqwest::Client::new();
+        let body: Value = client
+            .post(format!("http://{}/", addr))
+            .json(&serde_json::json!({ "batch": signals }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let results: Vec<TraceResult> = serde_json::from_value(body["results"].clone()).unwrap();
+        assert_eq!(
+            results.iter().map(|r| r.cid.clone()).collect::<Vec<_>>(),
+            ids
+        );
+        let doubled: Vec<u64> = results[..3]
+            .iter()
+            .map(|r| r.clone().into_value().unwrap())
+            .collect();
+        assert_eq!(doubled, vec![0, 2, 4]);
+        assert_eq!(
+            results[3].error.as_ref().unwrap().code,
+            ErrorCode::HandlerError
+        );
+
+        let oversized: Vec<Signal> = (0..5)
+            .map(|n| Signal::new("tester", "calc/double", n))
+            .collect();
+        let response = client
+            .post(format!("http://{}/", addr))
+            .json(&serde_json::json!({ "batch": oversized }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(format!("http://{}", addr)),
+            ..Default::default()
+        });
+        caller.provide("calc/local", |n: u64, _| {
+            Box::pin(async move { Ok(n + 100) })
+        });
+        caller.clone().listen().await.unwrap().ready().await;
+        let results = caller
+            .ask_batch([
+                ("calc/double", serde_json::json!(1)),
+                ("calc/local", serde_json::json!(1)),
+                ("calc/double", serde_json::json!(2)),
+                ("calc/fail", Value::Null),
+            ])
+            .await;
+        let values: Vec<Option<u64>> = results
+            .iter()
+            .map(|r| r.clone().into_value().ok())
+            .collect();
+        assert_eq!(values, vec![Some(2), Some(101), Some(4), None]);
+        assert_eq!(
+            results[3].error.as_ref().unwrap().code,
+            ErrorCode::HandlerError
+        );
+
+        // Undeliverable batches fall back to routing each call
+        provider.shutdown().await;
+        let results = caller
+            .ask_batch([("calc/double", 1), ("calc/double", 2)])
+            .await;
+        assert!(results.iter().all(|r| !r.ok));
+
+        caller.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_batches_to_peers_that_decline_them() {
+        use std::sync::atomic::AtomicUsize;
+
+        // Providers in id order, so the fakes are always asked first
+        struct ById;
+        impl balance::LoadBalancer for ById {
+            fn rank(&self, _: &str, providers: &mut [AtlasEntry], _: &balance::PeerStats) {
+                providers.sort_by(|a, b| a.id.cmp(&b.id));
+            }
+        }
+
+        fn answer(signal: Signal) -> TraceResult {
+            if signal.payload.capability == "calc/moved" {
+                let error = MeshError::new(ErrorCode::NotFound, "moved away", "fake");
+                TraceResult::failure(signal.id, error)
+            } else {
+                TraceResult::success(signal.id, signal.payload.args)
+            }
+        }
+
+        /// A peer taking batches of at most 2, or - like a TypeScript cell - none.
+        /// Counts the batches and single signals it serves.
+        async fn fake_peer(takes_batches: bool) -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+            let batches = Arc::new(AtomicUsize::new(0));
+            let singles = Arc::new(AtomicUsize::new(0));
+            let counters = (batches.clone(), singles.clone());
+            let app = Router::new().route(
+                "/",
+                post(move |Json(body): Json<Value>| {
+                    let (batches, singles) = counters.clone();
+                    async move {
+                        let batch = body["batch"].as_array().filter(|_| takes_batches);
+                        if let Some(batch) = batch {
+                            if batch.len() > 2 {
+                                return (StatusCode::PAYLOAD_TOO_LARGE, Json(Value::Null));
+                            }
+                            batches.fetch_add(1, Ordering::SeqCst);
+                            let results: Vec<TraceResult> = batch
+                                .iter()
+                                .map(|s| answer(serde_json::from_value(s.clone()).unwrap()))
+                                .collect();
+                            return (
+                                StatusCode::OK,
+                                Json(serde_json::json!({ "results": results })),
+                            );
+                        }
+                        match serde_json::from_value::<Signal>(body) {
+                            Ok(signal) => {
+                                if !signal.payload.capability.starts_with("mesh/") {
+                                    singles.fetch_add(1, Ordering::SeqCst);
+                                }
+                                let result = answer(signal);
+                                (
+                                    StatusCode::OK,
+                                    Json(serde_json::json!({ "result": result })),
+                                )
+                            }
+                            Err(_) => (
+                                StatusCode::BAD_REQUEST,
+                                Json(serde_json::json!({ "error": "not a signal" })),
+                            ),
+                        }
+                    }
+                }),
+            );
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, app).await });
+            (addr, batches, singles)
+        }
+
+        let provider = RheoCell::new(CellConfig {
+            id: "b-real".into(),
+            ..Default::default()
+        });
+        provider.provide("calc/moved", |n: u64, _| Box::pin(async move { Ok(n) }));
+        let seed = format!(
+            "http://127.0.0.1:{}",
+            provider.clone().listen().await.unwrap().addr().port()
+        );
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(seed),
+            ..Default::default()
+        });
+        caller.set_load_balancer("*", Arc::new(ById));
+        caller.clone().listen().await.unwrap().ready().await;
+        wait_until("b-real in the atlas", || {
+            caller.atlas.get("b-real").is_some()
+        })
+        .await;
+
+        let (chunky, chunky_batches, chunky_singles) = fake_peer(true).await;
+        let (legacy, _, legacy_singles) = fake_peer(false).await;
+        let mut entry = AtlasEntry::new(
+            "a-chunky",
+            chunky,
+            vec!["calc/echo".into(), "calc/moved".into()],
+        );
+        entry.max_batch = Some(2);
+        caller.atlas.insert("a-chunky".into(), entry);
+        // Claims batches it turns down, as an older build would
+        let mut entry = AtlasEntry::new("a-legacy", legacy.clone(), vec!["calc/plain".into()]);
+        entry.max_batch = Some(256);
+        caller.atlas.insert("a-legacy".into(), entry);
+
+        // Groups are split to the peer's limit
+        let results = caller.ask_batch((0..5).map(|n| ("calc/echo", n))).await;
+        let values: Vec<u64> = results
+            .into_iter()
+            .map(|r| r.into_value().unwrap())
+            .collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+        assert_eq!(chunky_batches.load(Ordering::SeqCst), 3);
+        assert_eq!(chunky_singles.load(Ordering::SeqCst), 0);
+
+        // Calls a batch couldn't route fail over like single calls
+        let results = caller.ask_batch((0..2).map(|n| ("calc/moved", n))).await;
+        assert!(results.iter().all(|r| r.ok), "{:?}", results);
+        assert_eq!(chunky_batches.load(Ordering::SeqCst), 4);
+
+        // A declined batch falls back to single signals without hurting the peer
+        for _ in 0..4 {
+            let results = caller.ask_batch((0..2).map(|n| ("calc/plain", n))).await;
+            assert!(results.iter().all(|r| r.ok), "{:?}", results);
+        }
+        assert_eq!(legacy_singles.load(Ordering::SeqCst), 8);
+        assert!(!caller.circuits.get(&legacy).is_some_and(|c| c.is_open()));
+
+        caller.shutdown().await;
+        provider.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_arrivals() {
+        use std::sync::atomic::AtomicUsize;
+
+        let cell = RheoCell::new(CellConfig::default());
+        let placed = Arc::new(AtomicUsize::new(0));
+        let counter = placed.clone();
+        cell.provide("orders/place", move |_: Value, _| {
+            let counter = counter.clone();
+            Box::pin(async move {
+                sleep(Duration::from_millis(100)).await;
+                Ok(counter.fetch_add(1, Ordering::SeqCst) + 1)
+            })
+        });
+        let addr = cell.clone().listen().await.unwrap().addr();
+
+        let signal = Signal::new("tester", "orders/place", ());
+        let post = |signal: Signal| async move {
+            let body: Value = reqwest::Client::new()
+                .post(format!("http://{}/", addr))
+                .json(&signal)
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            serde_json::from_value::<TraceResult>(body["result"].clone()).unwrap()
+        };
+
+        // The retry arrives while the original is still running
+        let (first, retry) =
+            futures::future::join(post(signal.clone()), post(signal.clone())).await;
+        assert_eq!(first.clone().into_value::<usize>().unwrap(), 1);
+        assert_eq!(retry.into_value::<usize>().unwrap(), 1);
+
+        // ...or after it finished
+        let late = post(signal.clone()).await;
+        assert_eq!(late.cid, first.cid);
+        assert_eq!(late.into_value::<usize>().unwrap(), 1);
+        assert_eq!(placed.load(Ordering::SeqCst), 1);
+        assert!(cell.active_executions.is_empty());
+
+        // Knowing the id isn't enough to collect someone else's result...
+        let mut borrowed = signal.clone();
+        borrowed.from = "eavesdropper".into();
+        assert_eq!(post(borrowed).await.into_value::<usize>().unwrap(), 2);
+        // ...and the same id with other args is a conflict, not a retry
+        let mut altered = signal.clone();
+        altered.payload.args = serde_json::json!({ "qty": 1000 });
+        let conflict = post(altered).await;
+        assert_eq!(conflict.error.unwrap().code, ErrorCode::Conflict);
+        assert_eq!(placed.load(Ordering::SeqCst), 2);
+
+        let next = post(Signal::new("tester", "orders/place", ())).await;
+        assert_eq!(next.into_value::<usize>().unwrap(), 3);
+
+        // Mesh answers are never replayed
+        let ping = Signal::new("tester", "mesh/ping", ());
+        assert!(post(ping.clone()).await.ok);
+        assert!(!cell.result_cache.contains_key(&dedup_key(&ping)));
+
+        cell.shutdown().await;
+
+        // A result is only replayed for the configured ttl, and 0 turns it off
+        for ttl in [0, 50] {
+            let cell = RheoCell::new(CellConfig {
+                result_cache_ttl_ms: ttl,
+                ..Default::default()
+            });
+            let placed = Arc::new(AtomicUsize::new(0));
+            let counter = placed.clone();
+            cell.provide("orders/place", move |_: Value, _| {
+                let counter = counter.clone();
+                Box::pin(async move { Ok(counter.fetch_add(1, Ordering::SeqCst) + 1) })
+            });
+            let signal = Signal::new("tester", "orders/place", ());
+            assert!(cell.route(signal.clone()).await.ok);
+            sleep(Duration::from_millis(ttl * 2)).await;
+            assert!(cell.route(signal).await.ok);
+            assert_eq!(placed.load(Ordering::SeqCst), 2, "ttl {}", ttl);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_stops_at_provider_errors() {
+        use std::sync::atomic::AtomicUsize;
+
+        // Providers in id order, so the dead one is tried first
+        struct ById;
+        impl balance::LoadBalancer for ById {
+            fn rank(&self, _: &str, providers: &mut [AtlasEntry], _: &balance::PeerStats) {
+                providers.sort_by(|a, b| a.id.cmp(&b.id));
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let failing = RheoCell::new(CellConfig {
+            id: "fo-a".into(),
+            gossip_interval_ms: 200,
+            ..Default::default()
+        });
+        failing.provide("work/fail", |_: Value, _| {
+            Box::pin(async move {
+                Err::<(), _>(MeshError::new(ErrorCode::HandlerError, "refused", "fo-a"))
+            })
+        });
+        let seed = format!(
+            "http://127.0.0.1:{}",
+            failing.clone().listen().await.unwrap().addr().port()
+        );
+        let healthy = RheoCell::new(CellConfig {
+            id: "fo-b".into(),
+            seed: Some(seed.clone()),
+            gossip_interval_ms: 200,
+            ..Default::default()
+        });
+        for capability in ["work/ok", "work/fail"] {
+            let calls = calls.clone();
+            healthy.provide(capability, move |_: Value, _| {
+                let calls = calls.clone();
+                Box::pin(async move { Ok(calls.fetch_add(1, Ordering::SeqCst)) })
+            });
+        }
+        healthy.clone().listen().await.unwrap().ready().await;
+        let caller = RheoCell::new(CellConfig {
+            seed: Some(seed),
+            gossip_interval_ms: 200,
+            ..Default::default()
+        });
+        caller.set_load_balancer("*", Arc::new(ById));
+        caller.clone().listen().await.unwrap().ready().await;
+        wait_until("both failover providers in the atlas", || {
+            ["fo-a", "fo-b"].iter().all(|id| {
+                caller
+                    .atlas
+                    .get(*id)
+                    .is_some_and(|e| e.caps.contains(&"work/fail".to_string()))
+            })
+        })
+        .await;
+        caller.atlas.insert(
+            "fo-0".into(),
+            AtlasEntry::new(
+                "fo-0",
+                "http://127.0.0.1:1",
+                vec!["work/ok".into(), "work/fail".into()],
+            ),
+        );
+
+        // An unreachable provider fails over to the next one
+        let ok = caller.ask_mesh("work/ok", Value::Null).await;
+        assert!(ok.ok, "{:?}", ok.error);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // A provider's own error is its answer - the call must not run again elsewhere
+        let failed = caller.ask_mesh("work/fail", Value::Null).await;
+        assert_eq!(failed.error.unwrap().code, ErrorCode::HandlerError);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        for cell in [&caller, &healthy, &failing] {
+            cell.shutdown().await;
+        }
     }
 }
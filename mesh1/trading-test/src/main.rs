@@ -1,5 +1,5 @@
 // trading-test/src/main.rs
-use cell_protocol_example1_rs::{CellConfig, RheoCell, Signal, TraceResult};
+use cell_protocol_example1_rs::{CellConfig, Context, RheoCell, TraceResult};
 use serde::{Deserialize, Serialize};
 use std::{
     sync::Arc,
@@ -161,27 +161,19 @@ async fn run_tests(cell: Arc<RheoCell>) -> TestReport {
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt().with_env_filter("info").init();
-
     let args: Vec<String> = std::env::args().collect();
     let auto_mode = args.contains(&"--auto".to_string());
 
+    let cell = RheoCell::new(CellConfig::load().expect("Invalid cell configuration"));
+    cell.init_logging().expect("Failed to initialise logging");
     info!("🧪 Rheo Mesh Test Cell");
 
-    let cell = RheoCell::new(CellConfig {
-        id: "trading-test".to_string(),
-        port: 0,
-        seed: std::env::var("RHEO_SEED").ok(),
-        ..Default::default()
-    });
-
-    let cell_for_shutdown = cell.clone();
     let cell_for_tests = cell.clone();
 
     // Register handler BEFORE listen() takes ownership
     let c = cell_for_tests.clone();
     cell.clone()
-        .provide("test/run", move |_args: (), _signal: Signal| {
+        .provide("test/run", move |_args: (), _ctx: Context| {
             let c2 = c.clone();
             Box::pin(async move {
                 let report = run_tests(c2).await;
@@ -189,10 +181,11 @@ async fn main() {
             })
         });
 
-    let addr = cell.listen().await.expect("Failed to start");
-    info!("🟢 Test Cell online @ {}", addr);
+    let handle = cell.listen().await.expect("Failed to start");
+    info!("🟢 Test Cell online @ {}", handle.addr());
 
     if auto_mode {
+        handle.ready().await;
         sleep(Duration::from_millis(1000)).await;
 
         let report = run_tests(cell_for_tests).await;
@@ -210,10 +203,9 @@ async fn main() {
             }
         }
 
-        cell_for_shutdown.shutdown().await;
+        handle.shutdown().await;
         std::process::exit(if report.tests_failed > 0 { 1 } else { 0 });
     } else {
-        tokio::signal::ctrl_c().await.expect("Failed to listen");
-        cell_for_shutdown.shutdown().await;
+        handle.join().await;
     }
 }
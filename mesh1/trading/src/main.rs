@@ -2,8 +2,9 @@
 // Provides: order management, position tracking, market data, risk controls
 
 use cell_protocol_example1_rs::{
+    router::{Procedure, Router},
     trading::{RiskLimits, Side, Tick},
-    CellConfig, ErrorCode, MeshError, RheoCell, Signal,
+    CellConfig, Context, ErrorCode, MeshError, RheoCell,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -144,7 +145,7 @@ impl TradingState {
 
 async fn place_order(
     args: PlaceOrderRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<OrderResponse, MeshError> {
     let order_id = uuid::Uuid::new_v4().to_string();
@@ -310,7 +311,7 @@ async fn update_position(
 
 async fn get_position(
     args: MarketDataRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<Option<PositionUpdate>, MeshError> {
     let positions = state.positions.read().await;
@@ -319,7 +320,7 @@ async fn get_position(
 
 async fn get_all_positions(
     _args: (),
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<Vec<PositionUpdate>, MeshError> {
     let positions = state.positions.read().await;
@@ -328,7 +329,7 @@ async fn get_all_positions(
 
 async fn cancel_order(
     order_id: String,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<OrderResponse, MeshError> {
     let mut orders = state.orders.write().await;
@@ -355,7 +356,7 @@ async fn cancel_order(
 
 async fn get_order(
     order_id: String,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<Option<OrderResponse>, MeshError> {
     let orders = state.orders.read().await;
@@ -364,7 +365,7 @@ async fn get_order(
 
 async fn get_order_history(
     limit: Option<usize>,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<Vec<OrderResponse>, MeshError> {
     let history = state.order_history.read().await;
@@ -374,7 +375,7 @@ async fn get_order_history(
 
 async fn update_market_data(
     tick: Tick,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<(), MeshError> {
     let mut market_data = state.market_data.write().await;
@@ -393,7 +394,7 @@ async fn update_market_data(
 
 async fn get_market_data(
     args: MarketDataRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<Option<Tick>, MeshError> {
     let market_data = state.market_data.read().await;
@@ -402,7 +403,7 @@ async fn get_market_data(
 
 async fn check_risk(
     args: RiskCheckRequest,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<RiskCheckResponse, MeshError> {
     let positions = state.positions.read().await;
@@ -437,7 +438,7 @@ async fn check_risk(
 
 async fn update_risk_limits(
     limits: RiskLimits,
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<RiskLimits, MeshError> {
     let mut current = state.risk_limits.write().await;
@@ -448,7 +449,7 @@ async fn update_risk_limits(
 
 async fn get_risk_limits(
     _args: (),
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<RiskLimits, MeshError> {
     let limits = state.risk_limits.read().await;
@@ -457,7 +458,7 @@ async fn get_risk_limits(
 
 async fn get_stats(
     _args: (),
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<TradingStats, MeshError> {
     let stats = state.stats.read().await;
@@ -475,7 +476,7 @@ async fn get_stats(
 
 async fn simulate_market_data(
     _args: (),
-    _signal: Signal,
+    _ctx: Context,
     state: Arc<TradingState>,
 ) -> Result<(), MeshError> {
     // Simulate market data for common pairs
@@ -512,32 +513,16 @@ fn now_millis() -> u64 {
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter("info")
-        .init();
-
-    info!("🚀 Starting Trading Cell...");
-
-    // Get seed from environment (set by orchestrator)
-    let seed = std::env::var("RHEO_SEED").unwrap_or_default();
-    
-    let config = CellConfig {
-        id: "trading".to_string(),
-        port: 0, // Let system assign
-        seed: if seed.is_empty() { None } else { Some(seed) },
-        registry_dir: Some(".rheo/registry".to_string()),
-        max_concurrent: 1000,
-        rpc_timeout_ms: 5000,
-        gossip_interval_ms: 15000,
-        atlas_ttl_ms: 60000,
-        enable_compression: true,
-        enable_tls: false,
-        log_level: tracing::Level::INFO,
-    };
+    // Cell.toml, then RHEO_* from the orchestrator (seed, id, journal, ...)
+    let config = CellConfig::load().unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(2)
+    });
 
     // Create cell
     let cell = RheoCell::new(config);
+    cell.init_logging().expect("Failed to initialise logging");
+    info!("🚀 Starting Trading Cell...");
     
     // Create shared state
     let state = Arc::new(TradingState::new());
@@ -545,115 +530,114 @@ async fn main() {
     // Register all capabilities
     {
         let s = state.clone();
-        cell.provide("trading/place_order", move |args, signal| {
+        cell.provide("trading/place_order", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { place_order(args, signal, s).await })
+            Box::pin(async move { place_order(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/get_position", move |args, signal| {
+        cell.provide("trading/get_position", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { get_position(args, signal, s).await })
+            Box::pin(async move { get_position(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/get_all_positions", move |args, signal| {
+        cell.provide("trading/get_all_positions", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { get_all_positions(args, signal, s).await })
+            Box::pin(async move { get_all_positions(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/cancel_order", move |args, signal| {
+        cell.provide("trading/cancel_order", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { cancel_order(args, signal, s).await })
+            Box::pin(async move { cancel_order(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/get_order", move |args, signal| {
+        cell.provide("trading/get_order", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { get_order(args, signal, s).await })
+            Box::pin(async move { get_order(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/get_order_history", move |args, signal| {
+        cell.provide("trading/get_order_history", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { get_order_history(args, signal, s).await })
+            Box::pin(async move { get_order_history(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/update_market_data", move |args, signal| {
+        cell.provide("trading/update_market_data", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { update_market_data(args, signal, s).await })
+            Box::pin(async move { update_market_data(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/get_market_data", move |args, signal| {
-            let s = s.clone();
-            Box::pin(async move { get_market_data(args, signal, s).await })
-        });
+        // A read-only query, so callers may hedge it (see `hedge` in Cell.toml)
+        cell.use_router(Router::new().procedure(
+            "trading/get_market_data",
+            Procedure::query(move |args, ctx| get_market_data(args, ctx, s.clone())),
+        ));
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/check_risk", move |args, signal| {
+        cell.provide("trading/check_risk", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { check_risk(args, signal, s).await })
+            Box::pin(async move { check_risk(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/update_risk_limits", move |args, signal| {
+        cell.provide("trading/update_risk_limits", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { update_risk_limits(args, signal, s).await })
+            Box::pin(async move { update_risk_limits(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/get_risk_limits", move |args, signal| {
+        cell.provide("trading/get_risk_limits", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { get_risk_limits(args, signal, s).await })
+            Box::pin(async move { get_risk_limits(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/get_stats", move |args, signal| {
+        cell.provide("trading/get_stats", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { get_stats(args, signal, s).await })
+            Box::pin(async move { get_stats(args, ctx, s).await })
         });
     }
 
     {
         let s = state.clone();
-        cell.provide("trading/simulate_market_data", move |args, signal| {
+        cell.provide("trading/simulate_market_data", move |args, ctx| {
             let s = s.clone();
-            Box::pin(async move { simulate_market_data(args, signal, s).await })
+            Box::pin(async move { simulate_market_data(args, ctx, s).await })
         });
     }
 
-    // Start the cell (listen takes ownership, so clone the Arc)
-    let cell_for_shutdown = cell.clone();
-    let addr = cell.listen().await.expect("Failed to start trading cell");
-    info!(addr = ?addr, "📈 Trading Cell online");
+    // Start the cell
+    let handle = cell.listen().await.expect("Failed to start trading cell");
+    info!(addr = ?handle.addr(), "📈 Trading Cell online");
 
-    // Keep running
-    tokio::signal::ctrl_c().await.expect("Failed to listen for ctrl-c");
-    info!("Shutting down trading cell...");
-    cell_for_shutdown.shutdown().await;
+    // Runs until SIGTERM/SIGINT
+    handle.join().await;
+    info!("Trading cell stopped");
 }
\ No newline at end of file